@@ -0,0 +1,224 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Serialize, Deserialize};
+use sha2::Sha256;
+
+use crate::keys::DataKey;
+use crate::secret::SecretBytes;
+
+const AUDIT_KEY_INFO: &[u8] = b"wallet-audit-log";
+
+// Previous hash of the first record.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditEvent {
+    AccountCreated,
+    PasswordChanged,
+    RecoveryPhraseCreated,
+    AccountRecovered,
+    KeyGenerated { key: String },
+    CardCreated { card_index: usize },
+    CardImported { card_index: usize, issuer: String },
+    CardDeleted { card_index: usize },
+    ProofPresented { channel: String, credential_id: String }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct AuditRecord {
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    pub event: AuditEvent,
+    pub previous_hash: String,
+    pub hash: String
+}
+
+// Last link of the chain, kept with the account so that truncating the log alone is noticed. Both live in the data
+// directory: truncating the log and restoring an older head together is not, so the log does not prove it is complete.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct AuditHead {
+    pub sequence: u64,
+    pub hash: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum BrokenLink {
+    Malformed { line: usize, error: String },
+    SequenceMismatch { line: usize, expected: u64, found: u64 },
+    PreviousHashMismatch { line: usize, sequence: u64 },
+    HashMismatch { line: usize, sequence: u64 },
+    MissingRecords { expected: u64, found: u64 },
+    UnexpectedHead { sequence: u64 }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct AuditVerification {
+    pub records: usize,
+    pub broken_link: Option<BrokenLink>
+}
+
+// Hashed fields of a record, in this order.
+#[derive(Serialize)]
+struct Link<'a> {
+    sequence: u64,
+    timestamp: &'a DateTime<Utc>,
+    event: &'a AuditEvent,
+    previous_hash: &'a str
+}
+
+// Append-only log, one JSON record per line. Each record carries a keyed hash of its content and of the previous hash,
+// so a record cannot be edited, reordered or removed, other than from the end, without the account data key.
+pub struct AuditLog {
+    path: PathBuf,
+    key: SecretBytes<32>
+}
+
+impl AuditLog {
+    pub fn new(dir: &Path, account_id: &str, data_key: &DataKey) -> Self {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(data_key.expose()).expect("HMAC accepts any key length");
+        mac.update(AUDIT_KEY_INFO);
+
+        let mut key: [u8; 32] = mac.finalize().into_bytes().into();
+
+        AuditLog { path: dir.join(format!("{}.jsonl", account_id)), key: SecretBytes::take(&mut key) }
+    }
+
+    fn link_hash(&self, sequence: u64, timestamp: &DateTime<Utc>, event: &AuditEvent, previous_hash: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(self.key.expose()).expect("HMAC accepts any key length");
+        mac.update(&serde_json::to_vec(&Link { sequence, timestamp, event, previous_hash }).expect("audit links serialize"));
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn read_lines(&self) -> Result<Vec<String>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(content.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+            Err(error) => Err(error.to_string())
+        }
+    }
+
+    // Chains the event after the last record, returns the new head to store with the account.
+    pub fn append(&self, event: AuditEvent, timestamp: DateTime<Utc>) -> Result<AuditHead, String> {
+        let last = match self.read_lines()?.last() {
+            Some(line) => Some(serde_json::from_str::<AuditRecord>(line).map_err(|e| format!("Audit log is corrupted: {}", e))?),
+            None => None
+        };
+
+        let sequence = last.as_ref().map(|record| record.sequence + 1).unwrap_or(0);
+        let previous_hash = last.map(|record| record.hash).unwrap_or(GENESIS_HASH.to_string());
+        let hash = self.link_hash(sequence, &timestamp, &event, &previous_hash);
+
+        let record = AuditRecord { sequence, timestamp, event, previous_hash, hash: hash.clone() };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", serde_json::to_string(&record).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+
+        Ok(AuditHead { sequence, hash })
+    }
+
+    // Walks the chain from the first record and stops at the first link that does not hold.
+    pub fn verify(&self, head: Option<&AuditHead>) -> Result<AuditVerification, String> {
+        let lines = self.read_lines()?;
+        let broken = |records: usize, broken_link: BrokenLink| Ok(AuditVerification { records, broken_link: Some(broken_link) });
+
+        let mut previous_hash = GENESIS_HASH.to_string();
+
+        for (i, line) in lines.iter().enumerate() {
+            let record: AuditRecord = match serde_json::from_str(line) {
+                Ok(record) => record,
+                Err(error) => return broken(i, BrokenLink::Malformed { line: i + 1, error: error.to_string() })
+            };
+
+            if record.sequence != i as u64 {
+                return broken(i, BrokenLink::SequenceMismatch { line: i + 1, expected: i as u64, found: record.sequence });
+            }
+
+            if record.previous_hash != previous_hash {
+                return broken(i, BrokenLink::PreviousHashMismatch { line: i + 1, sequence: record.sequence });
+            }
+
+            if record.hash != self.link_hash(record.sequence, &record.timestamp, &record.event, &record.previous_hash) {
+                return broken(i, BrokenLink::HashMismatch { line: i + 1, sequence: record.sequence });
+            }
+
+            previous_hash = record.hash;
+        }
+
+        if let Some(head) = head {
+            if head.sequence + 1 > lines.len() as u64 {
+                return broken(lines.len(), BrokenLink::MissingRecords { expected: head.sequence + 1, found: lines.len() as u64 });
+            }
+
+            if head.sequence + 1 < lines.len() as u64 || head.hash != previous_hash {
+                return broken(lines.len(), BrokenLink::UnexpectedHead { sequence: head.sequence });
+            }
+        } else if !lines.is_empty() {
+            return broken(lines.len(), BrokenLink::UnexpectedHead { sequence: lines.len() as u64 - 1 });
+        }
+
+        Ok(AuditVerification { records: lines.len(), broken_link: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys;
+
+    fn write_lines(dir: &Path, lines: &[String]) {
+        fs::write(dir.join("account.jsonl"), lines.iter().map(|line| format!("{}\n", line)).collect::<String>()).unwrap();
+    }
+
+    #[test]
+    fn edited_removed_and_reordered_records_are_reported() {
+        let dir = std::env::temp_dir().join(format!("wallet-audit-test-{}", std::process::id()));
+        let key = keys::generate_data_key();
+        let log = AuditLog::new(&dir, "account", &key);
+        let now: DateTime<Utc> = "2026-10-19T12:00:00Z".parse().unwrap();
+
+        assert_eq!(log.verify(None).unwrap(), AuditVerification { records: 0, broken_link: None });
+
+        log.append(AuditEvent::AccountCreated, now).unwrap();
+        log.append(AuditEvent::CardCreated { card_index: 0 }, now).unwrap();
+        log.append(AuditEvent::CardDeleted { card_index: 0 }, now).unwrap();
+        let head = log.append(AuditEvent::PasswordChanged, now).unwrap();
+
+        assert_eq!(head.sequence, 3);
+        assert_eq!(log.verify(Some(&head)).unwrap(), AuditVerification { records: 4, broken_link: None });
+
+        let lines: Vec<String> = fs::read_to_string(dir.join("account.jsonl")).unwrap().lines().map(str::to_string).collect();
+
+        // An edited event.
+        write_lines(&dir, &[lines[0].clone(), lines[1].replace("\"card_index\":0", "\"card_index\":1"), lines[2].clone(), lines[3].clone()]);
+        assert_eq!(log.verify(Some(&head)).unwrap().broken_link, Some(BrokenLink::HashMismatch { line: 2, sequence: 1 }));
+
+        // A record removed from the middle.
+        write_lines(&dir, &[lines[0].clone(), lines[2].clone(), lines[3].clone()]);
+        assert_eq!(log.verify(Some(&head)).unwrap().broken_link, Some(BrokenLink::SequenceMismatch { line: 2, expected: 1, found: 2 }));
+
+        // Records swapped.
+        write_lines(&dir, &[lines[0].clone(), lines[2].clone(), lines[1].clone(), lines[3].clone()]);
+        assert!(log.verify(Some(&head)).unwrap().broken_link.is_some());
+
+        // The last record removed.
+        write_lines(&dir, &lines[..3]);
+        assert_eq!(log.verify(Some(&head)).unwrap().broken_link, Some(BrokenLink::MissingRecords { expected: 4, found: 3 }));
+
+        // The chain cannot be recomputed without the data key.
+        write_lines(&dir, &lines);
+        assert_eq!(AuditLog::new(&dir, "account", &keys::generate_data_key()).verify(Some(&head)).unwrap().broken_link, Some(BrokenLink::HashMismatch { line: 1, sequence: 0 }));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
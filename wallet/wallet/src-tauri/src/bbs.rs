@@ -0,0 +1,285 @@
+use blstrs::Scalar;
+use serde::{Serialize, Deserialize};
+use pairing_crypto::{
+    bbs::{
+        ciphersuites::{
+            bls12_381_g1_sha_256,
+            bls12_381_g1_shake_256
+        },
+        BbsProofGenRequest,
+        BbsProofVerifyRequest,
+        BbsSignRequest,
+        BbsVerifyRequest
+    }
+};
+
+use crate::zkp::{commitment::{self, HolderCommitment}, signature::{SecretKey, Signature}};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Ciphersuite {
+    #[default]
+    #[serde(rename = "BLS12-381-SHA-256")]
+    Bls12381Sha256,
+    #[serde(rename = "BLS12-381-SHAKE-256")]
+    Bls12381Shake256,
+    // Cards signed by the zkp module (blind signatures bound to the holder secret), with its own domain separation tags.
+    #[serde(rename = "WALLET-BBS-BLS12-381-SHA-256")]
+    WalletBbs
+}
+
+fn wallet_bbs_error() -> String {
+    "Cards signed with the wallet BBS suite are checked by the zkp module".to_string()
+}
+
+pub fn sign<T: AsRef<[u8]>>(ciphersuite: Ciphersuite, request: &BbsSignRequest<'_, T>) -> Result<[u8; 80], String> {
+    match ciphersuite {
+        Ciphersuite::Bls12381Sha256 => bls12_381_g1_sha_256::sign(request),
+        Ciphersuite::Bls12381Shake256 => bls12_381_g1_shake_256::sign(request),
+        Ciphersuite::WalletBbs => return Err(wallet_bbs_error())
+    }.map_err(|e| e.to_string())
+}
+
+pub fn verify<T: AsRef<[u8]>>(ciphersuite: Ciphersuite, request: &BbsVerifyRequest<'_, T>) -> Result<bool, String> {
+    match ciphersuite {
+        Ciphersuite::Bls12381Sha256 => bls12_381_g1_sha_256::verify(request),
+        Ciphersuite::Bls12381Shake256 => bls12_381_g1_shake_256::verify(request),
+        Ciphersuite::WalletBbs => return Err(wallet_bbs_error())
+    }.map_err(|e| e.to_string())
+}
+
+pub fn proof_gen<T: AsRef<[u8]>>(ciphersuite: Ciphersuite, request: &BbsProofGenRequest<'_, T>) -> Result<Vec<u8>, String> {
+    match ciphersuite {
+        Ciphersuite::Bls12381Sha256 => bls12_381_g1_sha_256::proof_gen(request),
+        Ciphersuite::Bls12381Shake256 => bls12_381_g1_shake_256::proof_gen(request),
+        Ciphersuite::WalletBbs => return Err(wallet_bbs_error())
+    }.map_err(|e| e.to_string())
+}
+
+pub fn proof_verify<T: AsRef<[u8]>>(ciphersuite: Ciphersuite, request: &BbsProofVerifyRequest<'_, T>) -> Result<bool, String> {
+    match ciphersuite {
+        Ciphersuite::Bls12381Sha256 => bls12_381_g1_sha_256::proof_verify(request),
+        Ciphersuite::Bls12381Shake256 => bls12_381_g1_shake_256::proof_verify(request),
+        Ciphersuite::WalletBbs => return Err(wallet_bbs_error())
+    }.map_err(|e| e.to_string())
+}
+
+// Signature over the messages and the holder commitment, for cards bound to the wallet that requested them.
+pub struct BlindSignRequest<'a> {
+    pub secret_key: &'a SecretKey,
+    pub header: &'a [u8],
+    // Ties the commitment to one issuance session.
+    pub nonce: &'a [u8],
+    pub messages: &'a [Scalar],
+    pub commitment: &'a HolderCommitment
+}
+
+// pairing_crypto has no blind signatures, only the wallet suite signs over a holder commitment.
+pub fn blind_sign(ciphersuite: Ciphersuite, request: &BlindSignRequest<'_>) -> Result<Signature, String> {
+    match ciphersuite {
+        Ciphersuite::WalletBbs => commitment::blind_sign_with_nonce(request.secret_key, &request.secret_key.public_key(), request.header, request.nonce, request.messages, request.commitment),
+        Ciphersuite::Bls12381Sha256 | Ciphersuite::Bls12381Shake256 => Err("Blind signatures need the wallet BBS suite".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pairing_crypto::bbs::BbsProofGenRevealMessageRequest;
+    use crate::zkp::{self, testing::{card_messages, issuer_key}};
+
+    // Fixtures from draft-irtf-cfrg-bbs-signatures, appendix "Test Vectors".
+    const HEADER_HEX: &str = "11223344556677889900aabbccddeeff";
+    const PRESENTATION_HEADER_HEX: &str = "bed231d880675ed101ead304512e043ade9958dd0241ea70b4b3957fba941501";
+
+    const MESSAGES_HEX: [&str; 10] = [
+        "9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02",
+        "c344136d9ab02da4dd5908bbba913ae6f58c2cc844b802a6f811f5fb075f9b80",
+        "7372e9daa5ed31e6cd5c825eac1b855e84476a1d94932aa348e07b73",
+        "77fe97eb97a1ebe2e81e4e3597a3ee740a66e9ef2412472c",
+        "496694774c5604ab1b2544eababcf0f53278ff50",
+        "515ae153e22aae04ad16f759e07237b4",
+        "d183ddc6e2665aa4e2f088af",
+        "ac55fb33a75909ed",
+        "96012096",
+        ""
+    ];
+
+    struct Fixture {
+        ciphersuite: Ciphersuite,
+        secret_key_hex: &'static str,
+        public_key_hex: &'static str,
+        single_message_signature_hex: &'static str,
+        multi_message_signature_hex: &'static str
+    }
+
+    const FIXTURES: [Fixture; 2] = [
+        Fixture {
+            ciphersuite: Ciphersuite::Bls12381Sha256,
+            secret_key_hex: "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc",
+            public_key_hex: "a820f230f6ae38503b86c70dc50b61c58a77e45c39ab25c0652bbaa8fa136f2851bd4781c9dcde39fc9d1d52c9e60268061e7d7632171d91aa8d460acee0e96f1e7c4cfb12d3ff9ab5d5dc91c277db75c845d649ef3c4f63aebc364cd55ded0c",
+            single_message_signature_hex: "88c0eb3bc1d97610c3a66d8a3a73f260f95a3028bccf7fff7d9851e2acd9f3f32fdf58a5b34d12df8177adf37aa318a20f72be7d37a8e8d8441d1bc0bc75543c681bf061ce7e7f6091fe78c1cb8af103",
+            multi_message_signature_hex: "895cd9c0ccb9aca4de913218655346d718711472f2bf1f3e68916de106a0d93cf2f47200819b45920bbda541db2d91480665df253fedab2843055bdc02535d83baddbbb2803ec3808e074f71f199751e"
+        },
+        Fixture {
+            ciphersuite: Ciphersuite::Bls12381Shake256,
+            secret_key_hex: "2eee0f60a8a3a8bec0ee942bfd46cbdae9a0738ee68f5a64e7238311cf09a079",
+            public_key_hex: "92d37d1d6cd38fea3a873953333eab23a4c0377e3e049974eb62bd45949cdeb18fb0490edcd4429adff56e65cbce42cf188b31bddbd619e419b99c2c41b38179eb001963bc3decaae0d9f702c7a8c004f207f46c734a5eae2e8e82833f3e7ea5",
+            single_message_signature_hex: "98eb37fceb31115bf647f2983aef578ad895e55f7451b1add02fa738224cb89a31b148eace4d20d001be31d162c58d12574f30e68665b6403956a83b23a16f1daceacce8c5fde25d3defd52d6d5ff2e1",
+            multi_message_signature_hex: "97a296c83ed3626fe254d26021c5e9a087b580f1e8bc91bb51efb04420bfdaca215fe376a0bc12440bcc52224fb33c696cca9239b9f28dcddb7bd850aae9cd1a9c3e9f3639953fe789dbba53b8f0dd6f"
+        }
+    ];
+
+    fn decode<const N: usize>(value: &str) -> [u8; N] {
+        hex::decode(value).expect("Error decode.").try_into().expect("Wrong size.")
+    }
+
+    fn messages() -> Vec<Vec<u8>> {
+        MESSAGES_HEX.iter().map(|m| hex::decode(m).expect("Error decode.")).collect()
+    }
+
+    #[test]
+    fn sign_matches_test_vectors() {
+        let header = hex::decode(HEADER_HEX).unwrap();
+        let messages = messages();
+
+        for fixture in FIXTURES.iter() {
+            let secret_key: [u8; 32] = decode(fixture.secret_key_hex);
+            let public_key: [u8; 96] = decode(fixture.public_key_hex);
+
+            let single_message_signature = sign(fixture.ciphersuite, &BbsSignRequest {
+                secret_key: &secret_key,
+                public_key: &public_key,
+                header: Some(header.as_ref()),
+                messages: Some(&messages[..1])
+            }).unwrap();
+
+            assert_eq!(hex::encode(single_message_signature), fixture.single_message_signature_hex);
+
+            let multi_message_signature = sign(fixture.ciphersuite, &BbsSignRequest {
+                secret_key: &secret_key,
+                public_key: &public_key,
+                header: Some(header.as_ref()),
+                messages: Some(&messages)
+            }).unwrap();
+
+            assert_eq!(hex::encode(multi_message_signature), fixture.multi_message_signature_hex);
+        }
+    }
+
+    #[test]
+    fn verify_accepts_test_vectors_and_rejects_other_suite() {
+        let header = hex::decode(HEADER_HEX).unwrap();
+        let messages = messages();
+
+        for fixture in FIXTURES.iter() {
+            let public_key: [u8; 96] = decode(fixture.public_key_hex);
+            let signature: [u8; 80] = decode(fixture.multi_message_signature_hex);
+
+            let request = BbsVerifyRequest {
+                public_key: &public_key,
+                header: Some(header.as_ref()),
+                messages: Some(&messages),
+                signature: &signature
+            };
+
+            assert_eq!(verify(fixture.ciphersuite, &request), Ok(true));
+
+            let other_ciphersuite = match fixture.ciphersuite {
+                Ciphersuite::Bls12381Sha256 => Ciphersuite::Bls12381Shake256,
+                Ciphersuite::Bls12381Shake256 | Ciphersuite::WalletBbs => Ciphersuite::Bls12381Sha256
+            };
+
+            assert_ne!(verify(other_ciphersuite, &request), Ok(true));
+        }
+    }
+
+    #[test]
+    fn wallet_bbs_suite_is_not_handled_by_pairing_crypto() {
+        let header = hex::decode(HEADER_HEX).unwrap();
+        let messages = messages();
+        let fixture = &FIXTURES[0];
+
+        let secret_key: [u8; 32] = decode(fixture.secret_key_hex);
+        let public_key: [u8; 96] = decode(fixture.public_key_hex);
+        let signature: [u8; 80] = decode(fixture.multi_message_signature_hex);
+
+        assert!(sign(Ciphersuite::WalletBbs, &BbsSignRequest {
+            secret_key: &secret_key,
+            public_key: &public_key,
+            header: Some(header.as_ref()),
+            messages: Some(&messages)
+        }).is_err());
+
+        assert!(verify(Ciphersuite::WalletBbs, &BbsVerifyRequest {
+            public_key: &public_key,
+            header: Some(header.as_ref()),
+            messages: Some(&messages),
+            signature: &signature
+        }).is_err());
+    }
+
+    #[test]
+    fn blind_signatures_need_the_wallet_suite() {
+        let secret_key = issuer_key();
+        let public_key = secret_key.public_key();
+        let messages = zkp::messages_to_scalars(&card_messages());
+        let holder_secret = zkp::random_scalar();
+
+        let (holder_commitment, prover_blind) = commitment::commit_with_nonce(&public_key, b"header", b"nonce", &holder_secret);
+
+        let request = BlindSignRequest {
+            secret_key: &secret_key,
+            header: b"header",
+            nonce: b"nonce",
+            messages: &messages,
+            commitment: &holder_commitment
+        };
+
+        let signature = blind_sign(Ciphersuite::WalletBbs, &request).unwrap();
+        assert!(zkp::signature::verify(&public_key, b"header", &messages, &[holder_secret, prover_blind], &signature));
+
+        assert!(blind_sign(Ciphersuite::Bls12381Sha256, &request).is_err());
+        assert!(blind_sign(Ciphersuite::WalletBbs, &BlindSignRequest { nonce: b"other nonce", ..request }).is_err());
+    }
+
+    #[test]
+    fn proof_roundtrip_on_test_vectors() {
+        let header = hex::decode(HEADER_HEX).unwrap();
+        let presentation_header = hex::decode(PRESENTATION_HEADER_HEX).unwrap();
+        let messages = messages();
+        let disclosed_indices = [0, 2, 4, 6];
+
+        for fixture in FIXTURES.iter() {
+            let public_key: [u8; 96] = decode(fixture.public_key_hex);
+            let signature: [u8; 80] = decode(fixture.multi_message_signature_hex);
+
+            let proof_messages: Vec<BbsProofGenRevealMessageRequest<_>> = messages.iter().enumerate().map(|(i, m)| {
+                BbsProofGenRevealMessageRequest {
+                    reveal: disclosed_indices.contains(&i),
+                    value: m.as_slice()
+                }
+            }).collect();
+
+            let proof = proof_gen(fixture.ciphersuite, &BbsProofGenRequest {
+                public_key: &public_key,
+                header: Some(header.as_ref()),
+                messages: Some(&proof_messages),
+                signature: &signature,
+                presentation_header: Some(presentation_header.as_ref()),
+                verify_signature: Some(true)
+            }).unwrap();
+
+            let disclosed_messages: Vec<(usize, &[u8])> = disclosed_indices.iter().map(|&i| (i, messages[i].as_slice())).collect();
+
+            let verified = proof_verify(fixture.ciphersuite, &BbsProofVerifyRequest {
+                public_key: &public_key,
+                header: Some(header.as_ref()),
+                presentation_header: Some(presentation_header.as_ref()),
+                proof: &proof,
+                messages: Some(&disclosed_messages)
+            });
+
+            assert_eq!(verified, Ok(true));
+        }
+    }
+}
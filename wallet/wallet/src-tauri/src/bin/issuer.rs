@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use wallet_lib::did;
+use wallet_lib::issuer::{self, CredentialTemplate, IssuerConfig};
+use wallet_lib::keys::{self, EncryptedSecretKey, KdfParams, KeyFormat};
+use wallet_lib::zkp::{self, signature::SecretKey};
+use zeroize::Zeroizing;
+
+const USAGE: &str = "Usage: issuer --key-file <issuer.key> [--listen 127.0.0.1:8090] [--base-url <url>] [--templates <templates.json>] [--records <issued.jsonl>] --admin-token <token> [--did did:web:<domain>] [--export-public-key hex|jwk|jwk_ec|multikey|pem]";
+
+// Encrypts the key file when set, and is required to open an encrypted one.
+const PASSWORD_VARIABLE: &str = "ISSUER_KEY_PASSWORD";
+
+// Alternative to `--admin-token`, which other users of the machine can read in the process list.
+const ADMIN_TOKEN_VARIABLE: &str = "ISSUER_ADMIN_TOKEN";
+
+const KEY_INFO: &[u8] = b"wallet-issuer-key";
+
+fn argument(args: &[String], name: &str) -> Option<String> {
+    args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1)).cloned()
+}
+
+// The key file holds either the hex encoded key material or an encrypted key container, generated on first start so the issuer keeps its key across restarts.
+fn load_secret_key(path: &str) -> Result<SecretKey, String> {
+    let password = std::env::var(PASSWORD_VARIABLE).ok();
+
+    // The file content and the decoded key material are wiped once the key is built.
+    match std::fs::read_to_string(path).map(Zeroizing::new) {
+        Ok(content) if content.trim_start().starts_with('{') => {
+            let container: EncryptedSecretKey = serde_json::from_str(&content).map_err(|e| format!("{}: {}", path, e))?;
+            keys::decrypt_secret_key(&container, &password.ok_or(format!("{} is encrypted, set {}", path, PASSWORD_VARIABLE))?)
+        },
+        Ok(content) => SecretKey::new(&Zeroizing::new(hex::decode(content.trim()).map_err(|e| format!("{}: {}", path, e))?), KEY_INFO),
+        // Any other error leaves the file alone: replacing the key would invalidate every card issued so far.
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", path, error)),
+        Err(_) => {
+            let ikm = Zeroizing::new(zkp::random_scalar().to_bytes_be().to_vec());
+            let secret_key = SecretKey::new(&ikm, KEY_INFO)?;
+
+            let content = Zeroizing::new(match password {
+                Some(password) => serde_json::to_string_pretty(&keys::encrypt_secret_key(&secret_key, &password, KdfParams::default())?).map_err(|e| e.to_string())?,
+                None => hex::encode(&*ikm)
+            });
+
+            keys::write_key_file(path, &content).map_err(|e| format!("{}: {}", path, e))?;
+            println!("Generated a new issuer key in {}", path);
+
+            Ok(secret_key)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let admin_token = argument(&args, "--admin-token").or_else(|| std::env::var(ADMIN_TOKEN_VARIABLE).ok()).filter(|token| !token.is_empty());
+
+    let listen = argument(&args, "--listen").unwrap_or_else(|| "127.0.0.1:8090".to_string());
+    let credential_issuer = argument(&args, "--base-url").unwrap_or_else(|| format!("http://{}", listen));
+    let secret_key = load_secret_key(&argument(&args, "--key-file").ok_or(USAGE)?)?;
+
+    if let Some(format) = argument(&args, "--export-public-key") {
+        let format: KeyFormat = serde_json::from_value(serde_json::Value::String(format)).map_err(|_| USAGE)?;
+        println!("{}", keys::export_public_key(&secret_key.public_key(), format)?);
+        return Ok(());
+    }
+
+    let templates: BTreeMap<String, CredentialTemplate> = match argument(&args, "--templates") {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?).map_err(|e| e.to_string())?,
+        None => issuer::default_templates()
+    };
+
+    let listener = tokio::net::TcpListener::bind(&listen).await.map_err(|e| e.to_string())?;
+    let did = argument(&args, "--did");
+
+    let public_key = secret_key.public_key().to_bytes();
+    println!("Issuer {} listening on {}, public key {}", credential_issuer, listen, hex::encode(public_key));
+    println!("Issuer DID {}", did.clone().unwrap_or_else(|| did::did_key(&public_key)));
+
+    issuer::serve(listener, IssuerConfig {
+        credential_issuer,
+        secret_key,
+        did,
+        templates,
+        records_path: argument(&args, "--records").map(PathBuf::from),
+        admin_token: admin_token.ok_or(format!("Set --admin-token or {} to protect the offer endpoints", ADMIN_TOKEN_VARIABLE))?
+    }).await.map_err(|e| e.to_string())
+}
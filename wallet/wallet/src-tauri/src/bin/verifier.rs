@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use url::Url;
+
+use wallet_lib::did::DidResolver;
+use wallet_lib::trust::TrustRegistry;
+use wallet_lib::verifier::{self, VerifierConfig};
+
+const USAGE: &str = "Usage: verifier --trusted-issuers <issuers.json> [--listen 127.0.0.1:8080] [--base-url <url>] [--verifier-id <id>] [--require dateOfBirth,country] [--did-documents <dir>] [--status-lists <lists.json>] [--accumulator <accumulator.json>]";
+
+fn argument(args: &[String], name: &str) -> Option<String> {
+    args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1)).cloned()
+}
+
+#[tokio::main]
+async fn main() -> Result<(), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let listen = argument(&args, "--listen").unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let base_url = argument(&args, "--base-url").unwrap_or_else(|| format!("http://{}", listen));
+    let verifier_id = argument(&args, "--verifier-id").unwrap_or_else(|| base_url.clone());
+
+    // Wallets only post responses to the origin of the verifier id, which is the client_id of the requests.
+    if !Url::parse(&verifier_id).ok().zip(Url::parse(&base_url).ok()).is_some_and(|(verifier_id, base_url)| verifier_id.origin() == base_url.origin()) {
+        return Err(format!("--verifier-id must be a URL with the same origin as {}", base_url));
+    }
+
+    // Same trusted issuers file as the wallet keeps in `data/trusted_issuers.json`.
+    let issuers_path = argument(&args, "--trusted-issuers").ok_or(USAGE)?;
+    let mut trusted_issuers: TrustRegistry = serde_json::from_str(&std::fs::read_to_string(&issuers_path).map_err(|e| format!("{}: {}", issuers_path, e))?).map_err(|e| e.to_string())?;
+
+    let did_documents = argument(&args, "--did-documents").map(PathBuf::from);
+    let status_lists = argument(&args, "--status-lists").map(PathBuf::from);
+    let accumulator = argument(&args, "--accumulator").map(PathBuf::from);
+
+    // Issuers may be listed by the DID URL of their key rather than the key itself.
+    let resolver = DidResolver::new(reqwest::Client::new(), did_documents.clone());
+
+    for issuer in trusted_issuers.issuers.iter_mut().filter(|issuer| issuer.public_key.starts_with("did:")) {
+        issuer.public_key = resolver.resolve_public_key(&issuer.public_key).await.map_err(|e| format!("{}: {}", issuer.id, e))?;
+    }
+
+    let required_fields = argument(&args, "--require").map(|fields| fields.split(',').map(|field| field.trim().to_string()).filter(|field| !field.is_empty()).collect()).unwrap_or_default();
+
+    let listener = tokio::net::TcpListener::bind(&listen).await.map_err(|e| e.to_string())?;
+    println!("Verifier {} listening on {}", verifier_id, listen);
+
+    verifier::serve(listener, VerifierConfig { verifier_id, base_url, trusted_issuers, did_documents, required_fields, status_lists, accumulator }).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+use crate::{oid4vci, oid4vp};
+
+// URI schemes the wallet registers itself for, see `plugins.deep-link` in `tauri.conf.json`.
+pub const CREDENTIAL_OFFER_SCHEME: &str = "openid-credential-offer";
+pub const PRESENTATION_REQUEST_SCHEME: &str = "openid4vp";
+
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeepLink {
+    CredentialOffer { uri: String },
+    PresentationRequest { uri: String }
+}
+
+// Only checks the link is well formed, the offer or request it refers to is fetched once the user accepts to open it.
+pub fn parse_deep_link(uri: &str) -> Result<DeepLink, String> {
+    let uri = uri.trim();
+    let url = Url::parse(uri).map_err(|e| e.to_string())?;
+
+    match url.scheme() {
+        CREDENTIAL_OFFER_SCHEME => {
+            oid4vci::parse_credential_offer(uri)?;
+
+            Ok(DeepLink::CredentialOffer { uri: uri.to_string() })
+        },
+        PRESENTATION_REQUEST_SCHEME => {
+            oid4vp::parse_authorization_request(uri)?;
+
+            Ok(DeepLink::PresentationRequest { uri: uri.to_string() })
+        },
+        scheme => Err(format!("Unsupported link scheme {}", scheme))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credential_offers_and_presentation_requests_are_recognised() {
+        let offer = "openid-credential-offer://?credential_offer_uri=https%3A%2F%2Fissuer.example%2Foffers%2F1";
+        assert_eq!(parse_deep_link(offer).unwrap(), DeepLink::CredentialOffer { uri: offer.to_string() });
+
+        let request = "openid4vp://?client_id=https%3A%2F%2Fverifier.example&request_uri=https%3A%2F%2Fverifier.example%2Fchallenges%2F1%2Frequest";
+        assert_eq!(parse_deep_link(&format!(" {}\n", request)).unwrap(), DeepLink::PresentationRequest { uri: request.to_string() });
+
+        assert_eq!(serde_json::to_value(parse_deep_link(offer).unwrap()).unwrap(), serde_json::json!({"type": "credential_offer", "uri": offer}));
+    }
+
+    #[test]
+    fn malformed_and_foreign_links_are_rejected() {
+        assert!(parse_deep_link("openid-credential-offer://?foo=bar").is_err());
+        assert!(parse_deep_link("openid-credential-offer://?credential_offer=not-json").is_err());
+        assert!(parse_deep_link("openid4vp://?client_id=https%3A%2F%2Fverifier.example").is_err());
+        assert_eq!(parse_deep_link("https://issuer.example/offers/1").unwrap_err(), "Unsupported link scheme https");
+        assert!(parse_deep_link("not a link").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+use crate::oid4vci;
+
+pub const DID_KEY_PREFIX: &str = "did:key:";
+pub const DID_WEB_PREFIX: &str = "did:web:";
+pub const MULTIKEY_TYPE: &str = "Multikey";
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+
+// Multicodec `bls12_381-g2-pub` (0xeb) as an unsigned varint, prefixed to the compressed G2 point.
+const BLS12_381_G2_PUB_MULTICODEC: [u8; 2] = [0xeb, 0x01];
+const BASE58_BTC_MULTIBASE: char = 'z';
+const PUBLIC_KEY_LENGTH: usize = 96;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub controller: String,
+    pub public_key_multibase: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context", default)]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(default)]
+    pub verification_method: Vec<VerificationMethod>,
+    // References to the methods allowed to sign credentials, embedded methods are not supported.
+    #[serde(default)]
+    pub assertion_method: Vec<String>
+}
+
+// base58btc multibase of the multicodec prefixed key, e.g. `zUC7...` for a BLS12-381 G2 key.
+pub fn encode_multikey(public_key: &[u8]) -> String {
+    format!("{}{}", BASE58_BTC_MULTIBASE, bs58::encode([&BLS12_381_G2_PUB_MULTICODEC[..], public_key].concat()).into_string())
+}
+
+// Returns the hex encoded key, the encoding the rest of the wallet uses.
+pub fn decode_multikey(multibase: &str) -> Result<String, String> {
+    let encoded = multibase.strip_prefix(BASE58_BTC_MULTIBASE).ok_or("Only base58btc multibase keys are supported")?;
+    let bytes = bs58::decode(encoded).into_vec().map_err(|e| e.to_string())?;
+
+    match bytes.strip_prefix(&BLS12_381_G2_PUB_MULTICODEC[..]) {
+        Some(public_key) if public_key.len() == PUBLIC_KEY_LENGTH => Ok(hex::encode(public_key)),
+        _ => Err("Key is not a BLS12-381 G2 public key".to_string())
+    }
+}
+
+pub fn did_key(public_key: &[u8]) -> String {
+    format!("{}{}", DID_KEY_PREFIX, encode_multikey(public_key))
+}
+
+// A did:key document holds a single method named after the key itself.
+pub fn did_key_verification_method(public_key: &[u8]) -> String {
+    format!("{}#{}", did_key(public_key), encode_multikey(public_key))
+}
+
+impl DidDocument {
+    pub fn new(did: &str, fragment: &str, public_key: &[u8]) -> Self {
+        let id = format!("{}#{}", did, fragment);
+
+        DidDocument {
+            context: vec![DID_CONTEXT.to_string(), MULTIKEY_CONTEXT.to_string()],
+            id: did.to_string(),
+            verification_method: vec![VerificationMethod {
+                id: id.clone(),
+                method_type: MULTIKEY_TYPE.to_string(),
+                controller: did.to_string(),
+                public_key_multibase: encode_multikey(public_key)
+            }],
+            assertion_method: vec![id]
+        }
+    }
+
+    // Method ids may be relative to the document (`#key-1`).
+    fn absolute_id(&self, id: &str) -> String {
+        if id.starts_with('#') { format!("{}{}", self.id, id) } else { id.to_string() }
+    }
+
+    // Without a fragment the first assertion method is used, as a bare DID names the issuer rather than one of its keys.
+    pub fn verification_method(&self, did_url: &str) -> Option<&VerificationMethod> {
+        let method_id = match did_url.split_once('#') {
+            Some((_, fragment)) => format!("{}#{}", self.id, fragment),
+            None => self.assertion_method.first().map(|id| self.absolute_id(id))?
+        };
+
+        self.verification_method.iter().find(|method| self.absolute_id(&method.id) == method_id)
+    }
+
+    pub fn public_key(&self, did_url: &str) -> Result<String, String> {
+        let method = self.verification_method(did_url).ok_or(format!("No verification method {}", did_url))?;
+
+        // Only keys the DID controller lists for assertions sign credentials, not its authentication or other keys.
+        if !self.assertion_method.iter().any(|id| self.absolute_id(id) == self.absolute_id(&method.id)) {
+            return Err(format!("{} is not an assertion method of {}", method.id, self.id));
+        }
+
+        if method.method_type != MULTIKEY_TYPE {
+            return Err(format!("Unsupported verification method type {}", method.method_type));
+        }
+
+        decode_multikey(&method.public_key_multibase)
+    }
+}
+
+pub fn did_key_document(did: &str) -> Result<DidDocument, String> {
+    let multikey = did.strip_prefix(DID_KEY_PREFIX).ok_or("Not a did:key identifier")?;
+    let public_key = hex::decode(decode_multikey(multikey)?).map_err(|e| e.to_string())?;
+
+    Ok(DidDocument::new(did, multikey, &public_key))
+}
+
+// did:web:example.com is published at example.com/.well-known/did.json, did:web:example.com:issuers:fr at example.com/issuers/fr/did.json.
+fn did_web_location(did: &str) -> Result<(String, String), String> {
+    let identifier = did.strip_prefix(DID_WEB_PREFIX).ok_or("Not a did:web identifier")?;
+    let mut segments = identifier.split(':');
+
+    let host = segments.next().filter(|host| !host.is_empty()).ok_or("did:web has no domain")?.replace("%3A", ":").replace("%3a", ":");
+    let path: Vec<&str> = segments.collect();
+
+    // The host and path also name a file under the local documents directory, which they must not leave.
+    let invalid = |segment: &str| segment.is_empty() || segment == "." || segment == ".." || segment.contains(['/', '\\']);
+
+    if invalid(&host) || path.iter().any(|segment| invalid(segment)) {
+        return Err(format!("Invalid did:web identifier {}", did));
+    }
+
+    let path = if path.is_empty() { ".well-known/did.json".to_string() } else { format!("{}/did.json", path.join("/")) };
+
+    Ok((host, path))
+}
+
+pub fn did_web_url(did: &str) -> Result<String, String> {
+    let (host, path) = did_web_location(did)?;
+
+    // Local hosts are reached over plain HTTP so a development issuer needs no certificate. The port is left out of the comparison.
+    let hostname = Url::parse(&format!("https://{}", host)).map_err(|_| format!("Invalid did:web identifier {}", did))?.host_str().map(str::to_string);
+    let scheme = if matches!(hostname.as_deref(), Some("localhost" | "127.0.0.1" | "[::1]")) { "http" } else { "https" };
+
+    Ok(format!("{}://{}/{}", scheme, host, path))
+}
+
+pub struct DidResolver {
+    pub client: reqwest::Client,
+    // Directory mirroring did:web locations (`<dir>/<host>/.well-known/did.json`), looked up before the network.
+    pub documents_dir: Option<PathBuf>
+}
+
+impl DidResolver {
+    pub fn new(client: reqwest::Client, documents_dir: Option<PathBuf>) -> Self {
+        DidResolver { client, documents_dir }
+    }
+
+    async fn resolve_web(&self, did: &str) -> Result<DidDocument, String> {
+        let (host, path) = did_web_location(did)?;
+
+        // A port separator is not allowed in Windows file names.
+        if let Some(file) = self.documents_dir.as_ref().map(|dir| dir.join(host.replace(':', "_")).join(&path)).filter(|file| file.exists()) {
+            let content = std::fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+            return serde_json::from_str(&content).map_err(|e| format!("{}: {}", file.display(), e));
+        }
+
+        oid4vci::get_json(&self.client, &did_web_url(did)?).await
+    }
+
+    pub async fn resolve(&self, did: &str) -> Result<DidDocument, String> {
+        let document = if did.starts_with(DID_KEY_PREFIX) {
+            did_key_document(did)?
+        } else if did.starts_with(DID_WEB_PREFIX) {
+            self.resolve_web(did).await?
+        } else {
+            return Err(format!("Unsupported DID method in {}", did));
+        };
+
+        if document.id != did {
+            return Err(format!("DID document {} does not describe {}", document.id, did));
+        }
+
+        Ok(document)
+    }
+
+    // Resolves a DID URL such as `did:web:example.com#key-1` to the hex encoded BBS public key it names.
+    pub async fn resolve_public_key(&self, did_url: &str) -> Result<String, String> {
+        let did = did_url.split('#').next().unwrap_or(did_url);
+
+        self.resolve(did).await?.public_key(did_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use crate::zkp::testing::issuer_key;
+
+    fn public_key() -> Vec<u8> {
+        issuer_key().public_key().to_bytes().to_vec()
+    }
+
+    fn resolver(documents_dir: Option<PathBuf>) -> DidResolver {
+        DidResolver::new(reqwest::Client::builder().no_proxy().build().unwrap(), documents_dir)
+    }
+
+    #[tokio::test]
+    async fn did_key_round_trips_the_public_key() {
+        let did = did_key(&public_key());
+        assert!(did.starts_with("did:key:zUC7"));
+
+        let document = did_key_document(&did).unwrap();
+        assert_eq!(document.verification_method[0].id, did_key_verification_method(&public_key()));
+        assert_eq!(document.public_key(&did).unwrap(), hex::encode(public_key()));
+
+        assert_eq!(resolver(None).resolve_public_key(&did_key_verification_method(&public_key())).await.unwrap(), hex::encode(public_key()));
+        assert!(resolver(None).resolve_public_key(&format!("{}#other", did)).await.is_err());
+
+        // A key listed in the document but not as an assertion method does not sign credentials.
+        let mut authentication_only = did_key_document(&did).unwrap();
+        authentication_only.assertion_method.clear();
+        assert!(authentication_only.public_key(&did_key_verification_method(&public_key())).is_err());
+        assert!(decode_multikey(&format!("z{}", bs58::encode([0xed, 0x01, 1, 2, 3]).into_string())).is_err());
+    }
+
+    #[test]
+    fn did_web_identifiers_map_to_document_urls() {
+        assert_eq!(did_web_url("did:web:example.com").unwrap(), "https://example.com/.well-known/did.json");
+        assert_eq!(did_web_url("did:web:example.com:issuers:fr").unwrap(), "https://example.com/issuers/fr/did.json");
+        assert_eq!(did_web_url("did:web:localhost%3A8090").unwrap(), "http://localhost:8090/.well-known/did.json");
+
+        assert_eq!(did_web_url("did:web:127.0.0.1%3A8090:issuers").unwrap(), "http://127.0.0.1:8090/issuers/did.json");
+        assert_eq!(did_web_url("did:web:localhost.example.com").unwrap(), "https://localhost.example.com/.well-known/did.json");
+        assert_eq!(did_web_url("did:web:127.0.0.1.example.com").unwrap(), "https://127.0.0.1.example.com/.well-known/did.json");
+
+        assert!(did_web_url("did:web:").is_err());
+        assert!(did_web_url("did:web:example.com:..:secret").is_err());
+        assert!(did_web_url("did:web:..").is_err());
+        assert!(did_web_url("did:web:example.com:a\\..").is_err());
+        assert!(did_web_url("did:key:zUC7").is_err());
+    }
+
+    #[tokio::test]
+    async fn did_web_resolves_from_local_documents_and_over_http() {
+        let documents_dir = std::env::temp_dir().join(format!("wallet-did-test-{}", std::process::id()));
+        let document = DidDocument::new("did:web:example.com:issuers:fr", "key-1", &public_key());
+
+        std::fs::create_dir_all(documents_dir.join("example.com/issuers/fr")).unwrap();
+        std::fs::write(documents_dir.join("example.com/issuers/fr/did.json"), serde_json::to_string(&document).unwrap()).unwrap();
+
+        let local = resolver(Some(documents_dir.clone()));
+        assert_eq!(local.resolve_public_key("did:web:example.com:issuers:fr#key-1").await.unwrap(), hex::encode(public_key()));
+        assert_eq!(local.resolve_public_key("did:web:example.com:issuers:fr").await.unwrap(), hex::encode(public_key()));
+
+        std::fs::remove_dir_all(&documents_dir).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let did = format!("did:web:{}", listener.local_addr().unwrap().to_string().replace(':', "%3A"));
+
+        let served = DidDocument::new(&did, "key-1", &public_key());
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().route("/.well-known/did.json", get(move || std::future::ready(Json(served.clone()))))).await.unwrap();
+        });
+
+        assert_eq!(resolver(None).resolve_public_key(&format!("{}#key-1", did)).await.unwrap(), hex::encode(public_key()));
+        assert!(resolver(None).resolve("did:web:127.0.0.1%3A1:unknown").await.is_err());
+    }
+}
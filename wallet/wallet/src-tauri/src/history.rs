@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use zeroize::Zeroizing;
+
+use crate::keys::{self, DataKey, EncryptedData};
+use crate::presentation::Predicate;
+
+pub const LOCAL_CHANNEL: &str = "local";
+pub const DIRECT_POST_CHANNEL: &str = "direct_post";
+pub const QR_CODE_CHANNEL: &str = "qr_code";
+
+// One generated proof: what was shared, with whom and when. Values are only kept when the holder opted in.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub verifier_id: Option<String>,
+    pub credential_id: String,
+    pub channel: String,
+    pub disclosed_attributes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disclosed_values: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predicates: Vec<Predicate>
+}
+
+// Append-only log, one encrypted entry per line. The account ID is authenticated with each entry so logs cannot be swapped between accounts.
+pub struct HistoryLog {
+    path: PathBuf,
+    account_id: String,
+    key: DataKey
+}
+
+impl HistoryLog {
+    pub fn new(dir: &Path, account_id: &str, key: DataKey) -> Self {
+        HistoryLog { path: dir.join(format!("{}.jsonl", account_id)), account_id: account_id.to_string(), key }
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(entry).map_err(|e| e.to_string())?);
+        let record = serde_json::to_string(&keys::encrypt_data(&self.key, &plaintext, self.account_id.as_bytes())?).map_err(|e| e.to_string())?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", record).map_err(|e| e.to_string())
+    }
+
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>, String> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(error.to_string())
+        };
+
+        content.lines().filter(|line| !line.trim().is_empty()).enumerate().map(|(i, line)| {
+            let record: EncryptedData = serde_json::from_str(line).map_err(|e| format!("History entry {}: {}", i + 1, e))?;
+            let plaintext = keys::decrypt_data(&self.key, &record, self.account_id.as_bytes()).map_err(|e| format!("History entry {}: {}", i + 1, e))?;
+
+            serde_json::from_slice(&plaintext).map_err(|e| format!("History entry {}: {}", i + 1, e))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(verifier_id: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2026-10-19T12:00:00Z".parse().unwrap(),
+            verifier_id: Some(verifier_id.to_string()),
+            credential_id: "4f2a9c1e".to_string(),
+            channel: DIRECT_POST_CHANNEL.to_string(),
+            disclosed_attributes: vec!["country".to_string(), "dateOfBirth".to_string()],
+            disclosed_values: None,
+            predicates: vec![Predicate::AgeOver { years: 18, date: "2026-10-19".to_string() }]
+        }
+    }
+
+    #[test]
+    fn entries_are_appended_encrypted_and_read_back() {
+        let dir = std::env::temp_dir().join(format!("wallet-history-test-{}", std::process::id()));
+        let key = keys::generate_data_key();
+
+        let log = HistoryLog::new(&dir, "account", key.clone());
+        assert_eq!(log.entries().unwrap(), vec![]);
+
+        log.append(&entry("https://verifier.example")).unwrap();
+        log.append(&entry("https://shop.example")).unwrap();
+
+        let entries = HistoryLog::new(&dir, "account", key.clone()).entries().unwrap();
+        assert_eq!(entries, vec![entry("https://verifier.example"), entry("https://shop.example")]);
+
+        let content = fs::read_to_string(dir.join("account.jsonl")).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(!content.contains("verifier.example") && !content.contains("dateOfBirth"));
+
+        assert!(HistoryLog::new(&dir, "account", keys::generate_data_key()).entries().is_err());
+
+        // Another account's log does not decrypt under this account's ID.
+        fs::copy(dir.join("account.jsonl"), dir.join("other.jsonl")).unwrap();
+        assert!(HistoryLog::new(&dir, "other", key).entries().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,625 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::{Path, State}, http::{HeaderMap, StatusCode}, routing::{get, post}, Form, Json, Router};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::oid4vci::{
+    claim_index, AuthorizationServerMetadata, BbsCredential, CardClaims, CredentialConfiguration, CredentialOffer, CredentialRequest, CredentialResponse, ErrorResponse,
+    Grants, IssuedCredential, IssuerMetadata, PreAuthorizedCodeGrant, TokenResponse, TxCode, CREDENTIAL_FORMAT, PRE_AUTHORIZED_CODE_GRANT, PROOF_TYPE, proof_nonce, parse_holder_commitment
+};
+use crate::did::{self, DidDocument};
+use crate::validity::{Clock, SystemClock, DATE_FORMAT};
+use crate::bbs::{self, BlindSignRequest, Ciphersuite};
+use crate::zkp::{self, signature::SecretKey};
+use crate::SIGNATURE_HEADER;
+
+pub const DEFAULT_CONFIGURATION_ID: &str = "IdentityCard";
+pub const TX_CODE_LENGTH: usize = 6;
+pub const ACCESS_TOKEN_LIFETIME_SECONDS: u64 = 300;
+// A pre-authorized code is a bearer credential, it expires and its transaction code can only be guessed a few times.
+pub const OFFER_LIFETIME_MINUTES: i64 = 30;
+pub const MAX_TX_CODE_ATTEMPTS: u32 = 5;
+pub const DID_WEB_KEY_ID: &str = "key-1";
+
+// Schema of an offered card: claims the issuer fills in itself and how long the card is valid.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CredentialTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, String>,
+    // Sets `expiryDate` this many days after issuance when the offer does not provide it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validity_days: Option<i64>
+}
+
+pub struct IssuerConfig {
+    // Credential issuer identifier, also the base URL of every endpoint.
+    pub credential_issuer: String,
+    pub secret_key: SecretKey,
+    // did:web identifier whose document this service publishes at `/.well-known/did.json`, the did:key of the issuer key when unset.
+    pub did: Option<String>,
+    pub templates: BTreeMap<String, CredentialTemplate>,
+    // JSON lines file every issued credential is appended to.
+    pub records_path: Option<PathBuf>,
+    // Bearer token required to create offers and list issued credentials.
+    pub admin_token: String
+}
+
+pub fn default_templates() -> BTreeMap<String, CredentialTemplate> {
+    BTreeMap::from([(DEFAULT_CONFIGURATION_ID.to_string(), CredentialTemplate {
+        display_name: Some("Identity card".to_string()),
+        defaults: BTreeMap::new(),
+        validity_days: Some(3650)
+    })])
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OfferRequest {
+    #[serde(default = "default_configuration_id")]
+    pub configuration_id: String,
+    pub claims: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tx_code: bool
+}
+
+fn default_configuration_id() -> String {
+    DEFAULT_CONFIGURATION_ID.to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CreatedOffer {
+    pub pre_authorized_code: String,
+    // Transaction code to hand over to the holder on another channel than the offer.
+    pub tx_code: Option<String>,
+    pub credential_offer: CredentialOffer,
+    // `openid-credential-offer://` link to hand over to the wallet, as a QR code or deep link.
+    pub credential_offer_uri: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IssuanceRecord {
+    pub id: String,
+    pub configuration_id: String,
+    pub claims: CardClaims,
+    pub public_key: String,
+    pub issued_at: DateTime<Utc>
+}
+
+struct PendingOffer {
+    configuration_id: String,
+    claims: CardClaims,
+    tx_code: Option<String>,
+    failed_attempts: u32,
+    expires_at: DateTime<Utc>,
+    redeemed: bool
+}
+
+struct TokenSession {
+    pre_authorized_code: String,
+    c_nonce: String,
+    expires_at: DateTime<Utc>
+}
+
+#[derive(Default)]
+struct Sessions {
+    offers: HashMap<String, PendingOffer>,
+    tokens: HashMap<String, TokenSession>,
+    issued: Vec<IssuanceRecord>
+}
+
+struct IssuerState {
+    config: IssuerConfig,
+    clock: Box<dyn Clock + Send + Sync>,
+    sessions: Mutex<Sessions>
+}
+
+type SharedState = Arc<IssuerState>;
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+fn api_error(status: StatusCode, error: &str, description: Option<String>) -> ApiError {
+    (status, Json(ErrorResponse { error: error.to_string(), error_description: description }))
+}
+
+fn random_hex() -> String {
+    hex::encode(zkp::random_scalar().to_bytes_be())
+}
+
+fn random_tx_code() -> String {
+    let bytes = zkp::random_scalar().to_bytes_be();
+    let value = u64::from_be_bytes(bytes[..8].try_into().unwrap()) % 10u64.pow(TX_CODE_LENGTH as u32);
+
+    format!("{:0width$}", value, width = TX_CODE_LENGTH)
+}
+
+fn check_admin(state: &IssuerState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let bearer = headers.get("authorization").and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+
+    if bearer != Some(state.config.admin_token.as_str()) {
+        return Err(api_error(StatusCode::UNAUTHORIZED, "invalid_token", None));
+    }
+
+    Ok(())
+}
+
+// Fills the template defaults and expiry date in, then checks the claims match the card schema.
+pub fn apply_template(template: &CredentialTemplate, claims: &BTreeMap<String, String>, today: NaiveDate) -> Result<CardClaims, String> {
+    let mut merged = template.defaults.clone();
+    merged.extend(claims.iter().map(|(name, value)| (name.clone(), value.clone())));
+
+    if let Some(days) = template.validity_days {
+        merged.entry("expiryDate".to_string()).or_insert_with(|| (today + Duration::days(days)).format(DATE_FORMAT).to_string());
+    }
+
+    let mut object = Map::new();
+
+    for (name, value) in merged {
+        if claim_index(&name).is_none() || name == "statusListIndex" {
+            return Err(format!("Unknown claim {}", name));
+        }
+
+        if value.trim().is_empty() {
+            return Err(format!("Claim {} is empty", name));
+        }
+
+        if (name == "dateOfBirth" || name == "expiryDate") && NaiveDate::parse_from_str(&value, DATE_FORMAT).is_err() {
+            return Err(format!("Claim {} must be a yyyy-mm-dd date", name));
+        }
+
+        object.insert(name, Value::String(value));
+    }
+
+    serde_json::from_value(Value::Object(object)).map_err(|e| e.to_string())
+}
+
+fn record_issuance(state: &IssuerState, record: &IssuanceRecord) -> Result<(), String> {
+    let Some(path) = &state.config.records_path else {
+        return Ok(());
+    };
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).map_err(|e| e.to_string())?;
+    writeln!(file, "{}", serde_json::to_string(record).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+fn credential_offer(state: &IssuerState, pre_authorized_code: &str, offer: &PendingOffer) -> CredentialOffer {
+    CredentialOffer {
+        credential_issuer: state.config.credential_issuer.clone(),
+        credential_configuration_ids: vec![offer.configuration_id.clone()],
+        grants: Grants {
+            pre_authorized_code: Some(PreAuthorizedCodeGrant {
+                pre_authorized_code: pre_authorized_code.to_string(),
+                tx_code: offer.tx_code.as_ref().map(|code| TxCode { input_mode: Some("numeric".to_string()), length: Some(code.len()), description: None }),
+                authorization_server: None
+            })
+        }
+    }
+}
+
+async fn create_offer(State(state): State<SharedState>, headers: HeaderMap, Json(request): Json<OfferRequest>) -> Result<Json<CreatedOffer>, ApiError> {
+    check_admin(&state, &headers)?;
+
+    let template = state.config.templates.get(&request.configuration_id)
+        .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "unknown_credential_configuration", Some(request.configuration_id.clone())))?;
+
+    let claims = apply_template(template, &request.claims, state.clock.today()).map_err(|e| api_error(StatusCode::BAD_REQUEST, "invalid_request", Some(e)))?;
+
+    let pre_authorized_code = random_hex();
+    let now = state.clock.now();
+
+    let offer = PendingOffer {
+        configuration_id: request.configuration_id,
+        claims,
+        tx_code: request.tx_code.then(random_tx_code),
+        failed_attempts: 0,
+        expires_at: now + Duration::minutes(OFFER_LIFETIME_MINUTES),
+        redeemed: false
+    };
+
+    let created = CreatedOffer {
+        pre_authorized_code: pre_authorized_code.clone(),
+        tx_code: offer.tx_code.clone(),
+        credential_offer: credential_offer(&state, &pre_authorized_code, &offer),
+        credential_offer_uri: format!(
+            "openid-credential-offer://?credential_offer_uri={}",
+            url::form_urlencoded::byte_serialize(format!("{}/offers/{}", state.config.credential_issuer, pre_authorized_code).as_bytes()).collect::<String>()
+        )
+    };
+
+    let mut sessions = state.sessions.lock().unwrap();
+
+    sessions.offers.retain(|_, offer| offer.expires_at > now);
+    sessions.tokens.retain(|_, token| token.expires_at > now);
+    sessions.offers.insert(pre_authorized_code, offer);
+
+    Ok(Json(created))
+}
+
+async fn get_offer(State(state): State<SharedState>, Path(code): Path<String>) -> Result<Json<CredentialOffer>, ApiError> {
+    let sessions = state.sessions.lock().unwrap();
+
+    match sessions.offers.get(&code) {
+        Some(offer) if !offer.redeemed && offer.expires_at > state.clock.now() => Ok(Json(credential_offer(&state, &code, offer))),
+        _ => Err(api_error(StatusCode::NOT_FOUND, "unknown_offer", None))
+    }
+}
+
+async fn issuer_metadata(State(state): State<SharedState>) -> Json<IssuerMetadata> {
+    let public_key = hex::encode(state.config.secret_key.public_key().to_bytes());
+
+    Json(IssuerMetadata {
+        credential_issuer: state.config.credential_issuer.clone(),
+        authorization_servers: vec![],
+        credential_endpoint: format!("{}/credential", state.config.credential_issuer),
+        // Without a nonce endpoint the wallet takes the c_nonce of the token response, which ties it to the offer.
+        nonce_endpoint: None,
+        credential_configurations_supported: state.config.templates.keys().map(|id| (id.clone(), CredentialConfiguration {
+            format: CREDENTIAL_FORMAT.to_string(),
+            proof_types_supported: HashMap::from([(PROOF_TYPE.to_string(), serde_json::json!({}))]),
+            public_key: public_key.clone()
+        })).collect()
+    })
+}
+
+fn verification_method(config: &IssuerConfig) -> String {
+    match &config.did {
+        Some(did) => format!("{}#{}", did, DID_WEB_KEY_ID),
+        None => did::did_key_verification_method(&config.secret_key.public_key().to_bytes())
+    }
+}
+
+async fn did_document(State(state): State<SharedState>) -> Json<DidDocument> {
+    let public_key = state.config.secret_key.public_key().to_bytes();
+
+    Json(match &state.config.did {
+        Some(did) => DidDocument::new(did, DID_WEB_KEY_ID, &public_key),
+        None => DidDocument::new(&did::did_key(&public_key), &did::encode_multikey(&public_key), &public_key)
+    })
+}
+
+async fn authorization_server_metadata(State(state): State<SharedState>) -> Json<AuthorizationServerMetadata> {
+    Json(AuthorizationServerMetadata {
+        issuer: state.config.credential_issuer.clone(),
+        token_endpoint: format!("{}/token", state.config.credential_issuer)
+    })
+}
+
+// A pre-authorized code is redeemed once, then the access token and its c_nonce allow a single credential request.
+async fn token(State(state): State<SharedState>, Form(form): Form<HashMap<String, String>>) -> Result<Json<TokenResponse>, ApiError> {
+    if form.get("grant_type").map(String::as_str) != Some(PRE_AUTHORIZED_CODE_GRANT) {
+        return Err(api_error(StatusCode::BAD_REQUEST, "unsupported_grant_type", None));
+    }
+
+    let code = form.get("pre-authorized_code").ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "invalid_request", None))?;
+
+    let mut sessions = state.sessions.lock().unwrap();
+
+    let offer = match sessions.offers.get_mut(code) {
+        Some(offer) if !offer.redeemed && offer.expires_at > state.clock.now() => offer,
+        _ => return Err(api_error(StatusCode::BAD_REQUEST, "invalid_grant", None))
+    };
+
+    if offer.tx_code.is_some() && form.get("tx_code") != offer.tx_code.as_ref() {
+        offer.failed_attempts += 1;
+
+        // Six digits are guessed quickly, the offer is withdrawn after a few wrong codes.
+        if offer.failed_attempts >= MAX_TX_CODE_ATTEMPTS {
+            sessions.offers.remove(code);
+        }
+
+        return Err(api_error(StatusCode::BAD_REQUEST, "invalid_grant", Some("Wrong transaction code".to_string())));
+    }
+
+    offer.redeemed = true;
+
+    let access_token = random_hex();
+    let c_nonce = random_hex();
+
+    sessions.tokens.insert(access_token.clone(), TokenSession {
+        pre_authorized_code: code.clone(),
+        c_nonce: c_nonce.clone(),
+        expires_at: state.clock.now() + Duration::seconds(ACCESS_TOKEN_LIFETIME_SECONDS as i64)
+    });
+
+    Ok(Json(TokenResponse { access_token, token_type: "Bearer".to_string(), expires_in: Some(ACCESS_TOKEN_LIFETIME_SECONDS), c_nonce: Some(c_nonce) }))
+}
+
+// Signs through the same ciphersuite dispatch as `sign_messages`, with the wallet suite: the card is bound to the holder commitment.
+async fn credential(State(state): State<SharedState>, headers: HeaderMap, Json(request): Json<CredentialRequest>) -> Result<Json<CredentialResponse>, ApiError> {
+    let bearer = headers.get("authorization").and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+
+    let mut sessions = state.sessions.lock().unwrap();
+
+    let session = match bearer.and_then(|token| sessions.tokens.remove(token)) {
+        Some(session) if session.expires_at > state.clock.now() => session,
+        _ => return Err(api_error(StatusCode::UNAUTHORIZED, "invalid_token", None))
+    };
+
+    let offer = sessions.offers.remove(&session.pre_authorized_code).ok_or_else(|| api_error(StatusCode::UNAUTHORIZED, "invalid_token", None))?;
+
+    if request.credential_configuration_id != offer.configuration_id {
+        return Err(api_error(StatusCode::BAD_REQUEST, "unknown_credential_configuration", None));
+    }
+
+    let holder_commitment = parse_holder_commitment(&request.proofs).map_err(|e| api_error(StatusCode::BAD_REQUEST, "invalid_proof", Some(e)))?;
+
+    let secret_key = &state.config.secret_key;
+    let public_key = secret_key.public_key();
+
+    let signature = bbs::blind_sign(Ciphersuite::WalletBbs, &BlindSignRequest {
+        secret_key,
+        header: SIGNATURE_HEADER,
+        nonce: &proof_nonce(&state.config.credential_issuer, &session.c_nonce),
+        messages: &zkp::messages_to_scalars(&offer.claims.messages()),
+        commitment: &holder_commitment
+    }).map_err(|e| api_error(StatusCode::BAD_REQUEST, "invalid_proof", Some(e)))?;
+
+    let record = IssuanceRecord {
+        id: random_hex(),
+        configuration_id: offer.configuration_id,
+        claims: offer.claims.clone(),
+        public_key: hex::encode(public_key.to_bytes()),
+        issued_at: state.clock.now()
+    };
+
+    record_issuance(&state, &record).map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", Some(e)))?;
+    sessions.issued.push(record);
+
+    Ok(Json(CredentialResponse {
+        credentials: vec![IssuedCredential {
+            credential: BbsCredential {
+                issuer: state.config.credential_issuer.clone(),
+                public_key: hex::encode(public_key.to_bytes()),
+                verification_method: Some(verification_method(&state.config)),
+                signature: hex::encode(signature.to_bytes()),
+                credential_subject: offer.claims
+            }
+        }]
+    }))
+}
+
+async fn issued(State(state): State<SharedState>, headers: HeaderMap) -> Result<Json<Vec<IssuanceRecord>>, ApiError> {
+    check_admin(&state, &headers)?;
+
+    Ok(Json(state.sessions.lock().unwrap().issued.clone()))
+}
+
+fn router_with_clock(config: IssuerConfig, clock: Box<dyn Clock + Send + Sync>) -> Router {
+    let state = Arc::new(IssuerState { config, clock, sessions: Mutex::new(Sessions::default()) });
+
+    Router::new()
+        .route("/offers", post(create_offer))
+        .route("/offers/{code}", get(get_offer))
+        .route("/.well-known/openid-credential-issuer", get(issuer_metadata))
+        .route("/.well-known/oauth-authorization-server", get(authorization_server_metadata))
+        .route("/.well-known/did.json", get(did_document))
+        .route("/token", post(token))
+        .route("/credential", post(credential))
+        .route("/issued", get(issued))
+        .with_state(state)
+}
+
+pub fn router(config: IssuerConfig) -> Router {
+    router_with_clock(config, Box::new(SystemClock))
+}
+
+pub async fn serve(listener: tokio::net::TcpListener, config: IssuerConfig) -> std::io::Result<()> {
+    axum::serve(listener, router(config)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::oid4vci;
+    use crate::validity::FixedClock;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())
+    }
+
+    fn claims() -> BTreeMap<String, String> {
+        [("surname", "Doe"), ("name", "John"), ("sex", "M"), ("dateOfBirth", "1990-01-01"), ("placeOfBirth", "Paris"), ("documentNo", "X1234567")]
+            .iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    fn templates() -> BTreeMap<String, CredentialTemplate> {
+        BTreeMap::from([(DEFAULT_CONFIGURATION_ID.to_string(), CredentialTemplate {
+            display_name: None,
+            defaults: BTreeMap::from([("country".to_string(), "FR".to_string())]),
+            validity_days: Some(365)
+        })])
+    }
+
+    struct SharedClock(Arc<Mutex<DateTime<Utc>>>);
+
+    impl Clock for SharedClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    async fn start_issuer(records_path: Option<PathBuf>) -> (String, reqwest::Client) {
+        start_issuer_with_clock(records_path, Box::new(clock())).await
+    }
+
+    async fn start_issuer_with_clock(records_path: Option<PathBuf>, clock: Box<dyn Clock + Send + Sync>) -> (String, reqwest::Client) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let config = IssuerConfig {
+            credential_issuer: url.clone(),
+            secret_key: zkp::testing::issuer_key(),
+            // The wallet resolves the key from the document this issuer serves itself.
+            did: Some(format!("did:web:{}", listener.local_addr().unwrap().to_string().replace(':', "%3A"))),
+            templates: templates(),
+            records_path,
+            admin_token: "admin".to_string()
+        };
+
+        let app = router_with_clock(config, clock);
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (url, reqwest::Client::builder().no_proxy().build().unwrap())
+    }
+
+    async fn create(client: &reqwest::Client, url: &str, claims: BTreeMap<String, String>) -> reqwest::Response {
+        client.post(format!("{}/offers", url)).bearer_auth("admin").json(&OfferRequest { configuration_id: default_configuration_id(), claims, tx_code: true }).send().await.unwrap()
+    }
+
+    #[test]
+    fn template_fills_defaults_and_expiry_date() {
+        let today = clock().today();
+        let card = apply_template(&templates()[DEFAULT_CONFIGURATION_ID], &claims(), today).unwrap();
+
+        assert_eq!(card.country, "FR");
+        assert_eq!(card.expiry_date, "2027-10-19");
+        assert_eq!(card.status_list_index, None);
+
+        let mut missing = claims();
+        missing.remove("surname");
+        assert!(apply_template(&templates()[DEFAULT_CONFIGURATION_ID], &missing, today).is_err());
+
+        let mut unknown = claims();
+        unknown.insert("height".to_string(), "180".to_string());
+        assert_eq!(apply_template(&templates()[DEFAULT_CONFIGURATION_ID], &unknown, today), Err("Unknown claim height".to_string()));
+
+        let mut bad_date = claims();
+        bad_date.insert("dateOfBirth".to_string(), "01/01/1990".to_string());
+        assert!(apply_template(&templates()[DEFAULT_CONFIGURATION_ID], &bad_date, today).is_err());
+    }
+
+    #[tokio::test]
+    async fn wallet_receives_offered_credential_and_issuance_is_recorded() {
+        let records_path = std::env::temp_dir().join(format!("wallet-issuer-{}.jsonl", random_hex()));
+        let (url, client) = start_issuer(Some(records_path.clone())).await;
+
+        assert_eq!(client.post(format!("{}/offers", url)).json(&OfferRequest { configuration_id: default_configuration_id(), claims: claims(), tx_code: false }).send().await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let created: CreatedOffer = create(&client, &url, claims()).await.json().await.unwrap();
+        let tx_code = created.tx_code.clone().unwrap();
+        assert_eq!(tx_code.len(), TX_CODE_LENGTH);
+
+        let offer = oid4vci::resolve_credential_offer(&client, &created.credential_offer_uri).await.unwrap();
+        assert_eq!(offer, created.credential_offer);
+
+        let holder_secret = zkp::random_scalar();
+        let wrong_tx_code = if tx_code == "000000" { "111111" } else { "000000" };
+
+        assert!(oid4vci::receive_credential(&client, &offer, Some(wrong_tx_code), &holder_secret).await.is_err());
+
+        let received = oid4vci::receive_credential(&client, &offer, Some(&tx_code), &holder_secret).await.unwrap();
+
+        assert_eq!(received.issuer, url);
+        assert_eq!(received.verification_method, Some(format!("did:web:{}#{}", url.trim_start_matches("http://").replace(':', "%3A"), DID_WEB_KEY_ID)));
+        assert_eq!(received.claims.country, "FR");
+        assert_eq!(received.claims.expiry_date, "2027-10-19");
+
+        // The pre-authorized code cannot be redeemed twice.
+        assert!(oid4vci::receive_credential(&client, &offer, Some(&tx_code), &holder_secret).await.is_err());
+
+        let records: Vec<IssuanceRecord> = client.get(format!("{}/issued", url)).bearer_auth("admin").send().await.unwrap().json().await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].claims, received.claims);
+
+        let stored: Vec<IssuanceRecord> = std::fs::read_to_string(&records_path).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(stored, records);
+
+        std::fs::remove_file(records_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn offers_expire_and_are_withdrawn_after_wrong_transaction_codes() {
+        let now = Arc::new(Mutex::new(clock().now()));
+        let (url, client) = start_issuer_with_clock(None, Box::new(SharedClock(now.clone()))).await;
+        let holder_secret = zkp::random_scalar();
+
+        let created: CreatedOffer = create(&client, &url, claims()).await.json().await.unwrap();
+        let tx_code = created.tx_code.clone().unwrap();
+        let wrong_tx_code = if tx_code == "000000" { "111111" } else { "000000" };
+
+        for _ in 0..MAX_TX_CODE_ATTEMPTS {
+            assert!(oid4vci::receive_credential(&client, &created.credential_offer, Some(wrong_tx_code), &holder_secret).await.is_err());
+        }
+
+        // Guessing stops there, the right code no longer redeems the offer.
+        assert!(oid4vci::receive_credential(&client, &created.credential_offer, Some(&tx_code), &holder_secret).await.is_err());
+        assert!(oid4vci::resolve_credential_offer(&client, &created.credential_offer_uri).await.is_err());
+
+        let created: CreatedOffer = create(&client, &url, claims()).await.json().await.unwrap();
+        assert!(oid4vci::resolve_credential_offer(&client, &created.credential_offer_uri).await.is_ok());
+
+        *now.lock().unwrap() += Duration::minutes(OFFER_LIFETIME_MINUTES);
+
+        assert!(oid4vci::resolve_credential_offer(&client, &created.credential_offer_uri).await.is_err());
+        assert!(oid4vci::receive_credential(&client, &created.credential_offer, created.tx_code.as_deref(), &holder_secret).await.is_err());
+    }
+
+    #[cfg(feature = "verifier")]
+    #[tokio::test]
+    async fn issued_credential_is_presented_to_the_reference_verifier() {
+        use crate::oid4vp;
+        use crate::presentation::BoundCredential;
+        use crate::trust::{IssuerTrust, TrustRegistry, TrustedIssuer};
+        use crate::verifier::{self, Challenge, VerificationResult, VerifierConfig};
+        use crate::secret::SecretScalar;
+        use crate::zkp::signature::{PublicKey, Signature};
+
+        let (issuer_url, client) = start_issuer(None).await;
+
+        let created: CreatedOffer = create(&client, &issuer_url, claims()).await.json().await.unwrap();
+        let holder_secret = SecretScalar::random();
+        let received = oid4vci::receive_credential(&client, &created.credential_offer, created.tx_code.as_deref(), holder_secret.expose()).await.unwrap();
+
+        let metadata = oid4vci::fetch_issuer_metadata(&client, &issuer_url).await.unwrap();
+        let public_key_hex = metadata.credential_configurations_supported[DEFAULT_CONFIGURATION_ID].public_key.clone();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let verifier_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let config = VerifierConfig {
+            verifier_id: verifier_url.clone(),
+            base_url: verifier_url.clone(),
+            trusted_issuers: TrustRegistry {
+                issuers: vec![TrustedIssuer { id: issuer_url.clone(), name: "Reference issuer".to_string(), public_key: public_key_hex, valid_from: None, valid_until: None, credential_types: vec![DEFAULT_CONFIGURATION_ID.to_string()] }]
+            },
+            did_documents: None,
+            required_fields: vec!["country".to_string()],
+            status_lists: None,
+            accumulator: None
+        };
+
+        tokio::spawn(verifier::serve(listener, config));
+
+        let challenge: Challenge = client.post(format!("{}/challenges", verifier_url)).json(&serde_json::json!({})).send().await.unwrap().json().await.unwrap();
+
+        let messages = received.claims.messages();
+        let credential = BoundCredential {
+            public_key: PublicKey::from_bytes(&hex::decode(&received.public_key).unwrap()).unwrap(),
+            signature: Signature::from_bytes(&hex::decode(&received.signature).unwrap()).unwrap(),
+            messages: &messages,
+            holder_secret,
+            prover_blind: zkp::scalar_from_bytes(&hex::decode(&received.prover_blind).unwrap()).unwrap(),
+            verification_method: received.verification_method.clone()
+        };
+
+        let request = oid4vp::resolve_authorization_request(&client, &challenge.authorization_request).await.unwrap();
+        let requested = oid4vp::match_credential(&request, &messages, &SystemClock).unwrap();
+        let response = oid4vp::create_response(&request, &requested, &credential, &requested.required).unwrap();
+        oid4vp::post_response(&client, &request, &response).await.unwrap();
+
+        let result: VerificationResult = client.get(format!("{}/challenges/{}/result", verifier_url, challenge.id)).send().await.unwrap().json().await.unwrap();
+
+        assert!(result.verified, "{:?}", result.error);
+        assert_eq!(result.issuer, IssuerTrust::Trusted { id: issuer_url, name: "Reference issuer".to_string() });
+        assert_eq!(result.disclosed.get("country").map(String::as_str), Some("FR"));
+    }
+}
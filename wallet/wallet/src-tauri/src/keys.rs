@@ -0,0 +1,414 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use aes_gcm::{aead::{Aead, KeyInit, Payload}, Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use blstrs::G2Affine;
+use rand_core::{OsRng, RngCore};
+use serde::{Serialize, Deserialize};
+use zeroize::Zeroizing;
+
+use crate::did;
+use crate::secret::SecretBytes;
+use crate::zkp::{self, signature::{PublicKey, SecretKey}, G2_LENGTH};
+
+pub const JWK_CURVE: &str = "BLS12381G2";
+pub const PEM_PUBLIC_KEY_LABEL: &str = "BLS12381G2 PUBLIC KEY";
+pub const KDF_ALGORITHM: &str = "argon2id";
+pub const CIPHER_ALGORITHM: &str = "A256GCM";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const ENCRYPTION_KEY_LENGTH: usize = 32;
+const DATA_KEY_AAD: &[u8] = b"wallet-data-key";
+
+pub const DATA_KEY_LENGTH: usize = ENCRYPTION_KEY_LENGTH;
+
+pub type DataKey = SecretBytes<DATA_KEY_LENGTH>;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyFormat {
+    Hex,
+    Jwk,
+    JwkEc,
+    Multikey,
+    Pem
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>
+}
+
+impl Jwk {
+    // `OKP` carries the compressed point in `x`.
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        Jwk {
+            kty: "OKP".to_string(),
+            crv: JWK_CURVE.to_string(),
+            x: URL_SAFE_NO_PAD.encode(public_key.to_bytes()),
+            y: None,
+            kid: None
+        }
+    }
+
+    // `EC` splits the uncompressed point into its two coordinates.
+    pub fn from_public_key_ec(public_key: &PublicKey) -> Self {
+        let uncompressed = public_key.0.to_uncompressed();
+
+        Jwk {
+            kty: "EC".to_string(),
+            crv: JWK_CURVE.to_string(),
+            x: URL_SAFE_NO_PAD.encode(&uncompressed[..G2_LENGTH]),
+            y: Some(URL_SAFE_NO_PAD.encode(&uncompressed[G2_LENGTH..])),
+            kid: None
+        }
+    }
+
+    pub fn public_key(&self) -> Result<PublicKey, String> {
+        if self.crv != JWK_CURVE {
+            return Err(format!("Unsupported JWK curve {}", self.crv));
+        }
+
+        let x = URL_SAFE_NO_PAD.decode(&self.x).map_err(|e| e.to_string())?;
+
+        match (self.kty.as_str(), &self.y) {
+            ("OKP", None) => PublicKey::from_bytes(&x),
+            ("EC", Some(y)) => {
+                let y = URL_SAFE_NO_PAD.decode(y).map_err(|e| e.to_string())?;
+
+                if x.len() != G2_LENGTH || y.len() != G2_LENGTH {
+                    return Err("Invalid JWK coordinates".to_string());
+                }
+
+                let uncompressed: [u8; 2 * G2_LENGTH] = [x, y].concat().try_into().map_err(|_| "Invalid JWK coordinates")?;
+                let point: G2Affine = Option::from(G2Affine::from_uncompressed(&uncompressed)).ok_or("Invalid G2 point")?;
+
+                PublicKey::from_bytes(&point.to_compressed())
+            },
+            _ => Err(format!("Unsupported JWK key type {}", self.kty))
+        }
+    }
+}
+
+fn pem_encode(label: &str, bytes: &[u8]) -> String {
+    let encoded: Vec<char> = STANDARD.encode(bytes).chars().collect();
+    let lines: Vec<String> = encoded.chunks(64).map(|line| line.iter().collect()).collect();
+
+    format!("-----BEGIN {}-----\n{}\n-----END {}-----\n", label, lines.join("\n"), label)
+}
+
+fn pem_decode(label: &str, input: &str) -> Result<Vec<u8>, String> {
+    let body = input.trim()
+        .strip_prefix(&format!("-----BEGIN {}-----", label))
+        .and_then(|rest| rest.strip_suffix(&format!("-----END {}-----", label)))
+        .ok_or(format!("Expected a PEM {} block", label))?;
+
+    STANDARD.decode(body.split_whitespace().collect::<String>()).map_err(|e| e.to_string())
+}
+
+pub fn export_public_key(public_key: &PublicKey, format: KeyFormat) -> Result<String, String> {
+    match format {
+        KeyFormat::Hex => Ok(hex::encode(public_key.to_bytes())),
+        KeyFormat::Jwk => serde_json::to_string_pretty(&Jwk::from_public_key(public_key)).map_err(|e| e.to_string()),
+        KeyFormat::JwkEc => serde_json::to_string_pretty(&Jwk::from_public_key_ec(public_key)).map_err(|e| e.to_string()),
+        KeyFormat::Multikey => Ok(did::encode_multikey(&public_key.to_bytes())),
+        KeyFormat::Pem => Ok(pem_encode(PEM_PUBLIC_KEY_LABEL, &public_key.to_bytes()))
+    }
+}
+
+// Accepts any of the export formats, told apart by their shape: hex never starts with `z`.
+pub fn import_public_key(input: &str) -> Result<PublicKey, String> {
+    let input = input.trim();
+
+    let bytes = if input.starts_with('{') {
+        return serde_json::from_str::<Jwk>(input).map_err(|e| e.to_string())?.public_key();
+    } else if input.starts_with("-----BEGIN") {
+        pem_decode(PEM_PUBLIC_KEY_LABEL, input)?
+    } else if input.starts_with('z') {
+        hex::decode(did::decode_multikey(input)?).map_err(|e| e.to_string())?
+    } else {
+        hex::decode(input).map_err(|e| e.to_string())?
+    };
+
+    PublicKey::from_bytes(&bytes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams { memory_kib: Params::DEFAULT_M_COST, iterations: Params::DEFAULT_T_COST, parallelism: Params::DEFAULT_P_COST }
+    }
+}
+
+// Password protected secret key, the public key stays readable and is authenticated with the ciphertext.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EncryptedSecretKey {
+    pub public_key: String,
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+    pub salt: String,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String
+}
+
+fn derive_encryption_key(password: &str, salt: &[u8], params: &KdfParams) -> Result<SecretBytes<ENCRYPTION_KEY_LENGTH>, String> {
+    let params = Params::new(params.memory_kib, params.iterations, params.parallelism, Some(ENCRYPTION_KEY_LENGTH)).map_err(|e| e.to_string())?;
+
+    let mut key = SecretBytes::zeroed();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params).hash_password_into(password.as_bytes(), salt, key.expose_mut()).map_err(|e| e.to_string())?;
+
+    Ok(key)
+}
+
+// Readable by the owner only, and never over an existing file.
+pub fn write_key_file(path: &str, content: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(content.as_bytes())
+}
+
+// Hex encoded random key material, generated on first use. Any error other than a missing file leaves the file alone:
+// a new key would invalidate everything signed with the previous one.
+pub fn load_or_generate_secret_key(path: &str, key_info: &[u8]) -> Result<SecretKey, String> {
+    match fs::read_to_string(path).map(Zeroizing::new) {
+        Ok(content) => SecretKey::new(&Zeroizing::new(hex::decode(content.trim()).map_err(|e| format!("{}: {}", path, e))?), key_info),
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", path, error)),
+        Err(_) => {
+            let ikm = Zeroizing::new(zkp::random_scalar().to_bytes_be().to_vec());
+
+            if let Some(parent) = Path::new(path).parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("{}: {}", path, e))?;
+            }
+
+            write_key_file(path, &Zeroizing::new(hex::encode(&*ikm))).map_err(|e| format!("{}: {}", path, e))?;
+
+            SecretKey::new(&ikm, key_info)
+        }
+    }
+}
+
+pub fn encrypt_secret_key(secret_key: &SecretKey, password: &str, params: KdfParams) -> Result<EncryptedSecretKey, String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let public_key = did::encode_multikey(&secret_key.public_key().to_bytes());
+
+    let cipher = Aes256Gcm::new_from_slice(derive_encryption_key(password, &salt, &params)?.expose()).map_err(|e| e.to_string())?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: Zeroizing::new(secret_key.to_bytes()).as_slice(), aad: public_key.as_bytes() }).map_err(|_| "Secret key encryption failed")?;
+
+    Ok(EncryptedSecretKey {
+        public_key,
+        kdf: KDF_ALGORITHM.to_string(),
+        kdf_params: params,
+        salt: URL_SAFE_NO_PAD.encode(salt),
+        cipher: CIPHER_ALGORITHM.to_string(),
+        nonce: URL_SAFE_NO_PAD.encode(nonce),
+        ciphertext: URL_SAFE_NO_PAD.encode(ciphertext)
+    })
+}
+
+pub fn decrypt_secret_key(container: &EncryptedSecretKey, password: &str) -> Result<SecretKey, String> {
+    if container.kdf != KDF_ALGORITHM || container.cipher != CIPHER_ALGORITHM {
+        return Err(format!("Unsupported key container {} / {}", container.kdf, container.cipher));
+    }
+
+    let salt = URL_SAFE_NO_PAD.decode(&container.salt).map_err(|e| e.to_string())?;
+    let nonce = URL_SAFE_NO_PAD.decode(&container.nonce).map_err(|e| e.to_string())?;
+    let ciphertext = URL_SAFE_NO_PAD.decode(&container.ciphertext).map_err(|e| e.to_string())?;
+
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Invalid key container nonce".to_string());
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(derive_encryption_key(password, &salt, &container.kdf_params)?.expose()).map_err(|e| e.to_string())?;
+    let plaintext = Zeroizing::new(cipher.decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: container.public_key.as_bytes() }).map_err(|_| "Wrong password or corrupted key container")?);
+
+    let secret_key = SecretKey::from_bytes(&plaintext)?;
+
+    if did::encode_multikey(&secret_key.public_key().to_bytes()) != container.public_key {
+        return Err("Key container public key does not match the secret key".to_string());
+    }
+
+    Ok(secret_key)
+}
+
+// AES-256-GCM ciphertext and its nonce.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EncryptedData {
+    pub nonce: String,
+    pub ciphertext: String
+}
+
+pub fn encrypt_data(key: &DataKey, plaintext: &[u8], aad: &[u8]) -> Result<EncryptedData, String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new_from_slice(key.expose()).map_err(|e| e.to_string())?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad }).map_err(|_| "Encryption failed")?;
+
+    Ok(EncryptedData { nonce: URL_SAFE_NO_PAD.encode(nonce), ciphertext: URL_SAFE_NO_PAD.encode(ciphertext) })
+}
+
+// The plaintext is wiped once the caller drops it.
+pub fn decrypt_data(key: &DataKey, data: &EncryptedData, aad: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+    let nonce = URL_SAFE_NO_PAD.decode(&data.nonce).map_err(|e| e.to_string())?;
+    let ciphertext = URL_SAFE_NO_PAD.decode(&data.ciphertext).map_err(|e| e.to_string())?;
+
+    if nonce.len() != NONCE_LENGTH {
+        return Err("Invalid nonce".to_string());
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(key.expose()).map_err(|e| e.to_string())?;
+
+    cipher.decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad }).map(Zeroizing::new).map_err(|_| "Wrong key or corrupted data".to_string())
+}
+
+// Random key encrypting an account's data. Only this wrapped form is stored, so a new password re-wraps the key instead of re-encrypting the data.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct WrappedKey {
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+    pub salt: String,
+    pub cipher: String,
+    #[serde(flatten)]
+    pub data: EncryptedData
+}
+
+pub fn generate_data_key() -> DataKey {
+    DataKey::random()
+}
+
+pub fn wrap_data_key(key: &DataKey, password: &str, params: KdfParams) -> Result<WrappedKey, String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+
+    let data = encrypt_data(&derive_encryption_key(password, &salt, &params)?, key.expose(), DATA_KEY_AAD)?;
+
+    Ok(WrappedKey {
+        kdf: KDF_ALGORITHM.to_string(),
+        kdf_params: params,
+        salt: URL_SAFE_NO_PAD.encode(salt),
+        cipher: CIPHER_ALGORITHM.to_string(),
+        data
+    })
+}
+
+pub fn unwrap_data_key(wrapped: &WrappedKey, password: &str) -> Result<DataKey, String> {
+    if wrapped.kdf != KDF_ALGORITHM || wrapped.cipher != CIPHER_ALGORITHM {
+        return Err(format!("Unsupported wrapped key {} / {}", wrapped.kdf, wrapped.cipher));
+    }
+
+    let salt = URL_SAFE_NO_PAD.decode(&wrapped.salt).map_err(|e| e.to_string())?;
+    let key = decrypt_data(&derive_encryption_key(password, &salt, &wrapped.kdf_params)?, &wrapped.data, DATA_KEY_AAD).map_err(|_| "Wrong password or corrupted data key")?;
+
+    DataKey::from_slice(&key).map_err(|_| "Invalid data key length".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::testing::{issuer_key, other_issuer_key};
+
+    // Cheap parameters, the defaults take seconds in debug builds.
+    const TEST_KDF_PARAMS: KdfParams = KdfParams { memory_kib: 64, iterations: 1, parallelism: 1 };
+
+
+    #[test]
+    fn public_key_round_trips_through_every_format() {
+        let public_key = issuer_key().public_key();
+
+        for format in [KeyFormat::Hex, KeyFormat::Jwk, KeyFormat::JwkEc, KeyFormat::Multikey, KeyFormat::Pem] {
+            let exported = export_public_key(&public_key, format).unwrap();
+            assert_eq!(import_public_key(&exported).unwrap(), public_key, "{:?}", format);
+        }
+
+        let pem = export_public_key(&public_key, KeyFormat::Pem).unwrap();
+        assert!(pem.starts_with("-----BEGIN BLS12381G2 PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= 64));
+    }
+
+    #[test]
+    fn malformed_jwks_are_rejected() {
+        let public_key = issuer_key().public_key();
+
+        let wrong_curve = Jwk { crv: "Bls12381G1".to_string(), ..Jwk::from_public_key(&public_key) };
+        assert!(wrong_curve.public_key().is_err());
+
+        let missing_y = Jwk { y: None, ..Jwk::from_public_key_ec(&public_key) };
+        assert!(missing_y.public_key().is_err());
+
+        let mut other_y = Jwk::from_public_key_ec(&other_issuer_key().public_key());
+        other_y.x = Jwk::from_public_key_ec(&public_key).x;
+        assert!(other_y.public_key().is_err());
+    }
+
+    #[test]
+    fn encrypted_container_needs_the_password() {
+        let secret_key = issuer_key();
+        let container = encrypt_secret_key(&secret_key, "correct horse battery staple", TEST_KDF_PARAMS).unwrap();
+
+        let container: EncryptedSecretKey = serde_json::from_str(&serde_json::to_string(&container).unwrap()).unwrap();
+        assert_eq!(import_public_key(&container.public_key).unwrap(), secret_key.public_key());
+
+        assert_eq!(decrypt_secret_key(&container, "correct horse battery staple").unwrap().to_bytes(), secret_key.to_bytes());
+        assert!(decrypt_secret_key(&container, "wrong password").is_err());
+
+        let swapped = EncryptedSecretKey { public_key: did::encode_multikey(&other_issuer_key().public_key().to_bytes()), ..container };
+        assert!(decrypt_secret_key(&swapped, "correct horse battery staple").is_err());
+    }
+
+    #[test]
+    fn generated_key_is_kept_across_loads() {
+        let dir = std::env::temp_dir().join(format!("wallet-keys-test-{}", std::process::id()));
+        let path = dir.join("keys").join("issuer.key");
+        let path = path.to_str().unwrap();
+
+        let secret_key = load_or_generate_secret_key(path, b"key-info").unwrap();
+        assert_eq!(load_or_generate_secret_key(path, b"key-info").unwrap().to_bytes(), secret_key.to_bytes());
+        assert_ne!(load_or_generate_secret_key(path, b"other-key-info").unwrap().to_bytes(), secret_key.to_bytes());
+
+        fs::write(path, "not hex").unwrap();
+        assert!(load_or_generate_secret_key(path, b"key-info").is_err());
+        assert_eq!(fs::read_to_string(path).unwrap(), "not hex");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn data_key_is_wrapped_by_the_password() {
+        let key = generate_data_key();
+        let wrapped = wrap_data_key(&key, "correct horse battery staple", TEST_KDF_PARAMS).unwrap();
+
+        let wrapped: WrappedKey = serde_json::from_str(&serde_json::to_string(&wrapped).unwrap()).unwrap();
+        assert_eq!(unwrap_data_key(&wrapped, "correct horse battery staple").unwrap().expose(), key.expose());
+        assert!(unwrap_data_key(&wrapped, "wrong password").is_err());
+
+        let data = encrypt_data(&key, b"history", b"account").unwrap();
+        assert_eq!(decrypt_data(&key, &data, b"account").unwrap().as_slice(), b"history");
+        assert!(decrypt_data(&key, &data, b"other account").is_err());
+        assert!(decrypt_data(&generate_data_key(), &data, b"account").is_err());
+    }
+}
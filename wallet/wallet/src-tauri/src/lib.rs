@@ -1,14 +1,16 @@
 #![allow(non_snake_case)]
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
 use serde::{Serialize, Deserialize};
 use sha2::{Sha256, Digest};
+use zeroize::Zeroizing;
 use pairing_crypto::{
     bbs::{
         ciphersuites::{
-            bls12_381::KeyPair,
-            bls12_381_g1_sha_256::{proof_gen, proof_verify, sign, verify}
+            bls12_381::KeyPair
         },
         BbsProofGenRequest,
         BbsProofGenRevealMessageRequest,
@@ -19,7 +21,56 @@ use pairing_crypto::{
 };
 use hex;
 
+use blstrs::Scalar;
+
+mod audit;
+mod bbs;
+mod deep_link;
+pub mod did;
+mod history;
+#[cfg(feature = "issuer")]
+pub mod issuer;
+pub mod keys;
+mod lockout;
+mod password;
+mod oid4vci;
+mod oid4vp;
+mod presentation;
+mod presentation_exchange;
+mod qr;
+mod recovery;
+mod secret;
+mod status_list;
+pub mod trust;
+pub mod validity;
+#[cfg(feature = "verifier")]
+pub mod verifier;
+pub mod zkp;
+
+use audit::{AuditEvent, AuditHead, AuditLog, AuditVerification};
+use bbs::Ciphersuite;
+use deep_link::DeepLink;
+use did::DidResolver;
+use history::{HistoryEntry, HistoryLog};
+use keys::{DataKey, KeyFormat};
+use lockout::{LoginAttempts, LoginError};
+use password::{check_password_policy, estimate_strength, PasswordStrength};
+use secret::{SecretBytes, SecretScalar, SecretString};
+use presentation::{AccumulatorState, BoundCredential, NonRevocationWitness, Predicate, Presentation, PresentationResult};
+use status_list::{StatusList, StatusListCredential, StatusListEntry, StatusPurpose, STATUS_LIST_LENGTH};
+use trust::{IssuerTrust, TrustRegistry, TrustedIssuer, IDENTITY_CARD_TYPE};
+use validity::{Clock, SystemClock, ValidityError};
+
 const ACCOUNTS_FILE_PATH: &str = "../../data/accounts.json";
+const STATUS_LISTS_FILE_PATH: &str = "../../data/status_lists.json";
+const STATUS_LIST_CACHE_FILE_PATH: &str = "../../data/status_list_cache.json";
+const ACCUMULATOR_FILE_PATH: &str = "../../data/accumulator.json";
+const TRUSTED_ISSUERS_FILE_PATH: &str = "../../data/trusted_issuers.json";
+const DID_DOCUMENTS_DIR_PATH: &str = "../../data/did";
+const HISTORY_DIR_PATH: &str = "../../data/history";
+const AUDIT_DIR_PATH: &str = "../../data/audit";
+const LOCAL_ISSUER_KEY_FILE_PATH: &str = "../../data/keys/issuer.key";
+const ACCUMULATOR_KEY_FILE_PATH: &str = "../../data/keys/accumulator.key";
 
 fn check_data_file() -> std::io::Result<()> {
     let file_path = Path::new(ACCOUNTS_FILE_PATH);
@@ -42,6 +93,21 @@ struct AccountFileData {
     accountsArray: Vec<Account>
 }
 
+fn read_accounts_file() -> AccountFileData {
+    let accounts_file_raw = fs::read_to_string(ACCOUNTS_FILE_PATH).unwrap_or_else(|_| "{\"accountsArray\":[]}".to_string());
+
+    serde_json::from_str(&accounts_file_raw).unwrap_or(AccountFileData { accountsArray: vec![] })
+}
+
+fn write_accounts_file(accounts_file_data: &AccountFileData) -> bool {
+    let json_string = match serde_json::to_string_pretty(accounts_file_data) {
+        Ok(s) => s,
+        Err(_) => return false
+    };
+
+    fs::write(ACCOUNTS_FILE_PATH, json_string).is_ok()
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct Wallet {
     country: String,
@@ -49,7 +115,27 @@ struct Wallet {
     dateOfBirth: String,
     placeOfBirth: String,
     documentNo: String,
-    expiryDate: String
+    expiryDate: String,
+    #[serde(default)]
+    ciphersuite: Ciphersuite,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    statusListIndex: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accumulatorWitness: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accumulatorEpoch: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    issuedCredential: Option<IssuedCredential>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct IssuedCredential {
+    issuer: String,
+    signature: String,
+    publicKey: String,
+    proverBlind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verificationMethod: Option<String>
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -57,7 +143,27 @@ struct Account {
     surname: String,
     name: String,
     password: String,
-    walletArray: Vec<Wallet>
+    walletArray: Vec<Wallet>,
+    // Written in clear by older versions, moved to encryptedHolderSecret on first use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    holderSecret: Option<String>,
+    // Secret the issued cards are bound to, encrypted by the data key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encryptedHolderSecret: Option<keys::EncryptedData>,
+    // Encrypts the presentation history, wrapped by the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dataKey: Option<keys::WrappedKey>,
+    // The same data key wrapped by the recovery phrase, to set a new password when the old one is lost.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recoveryKey: Option<keys::WrappedKey>,
+    // Whether the history keeps disclosed values, not only attribute names.
+    #[serde(default)]
+    historyValues: bool,
+    // Last record of the audit log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auditHead: Option<AuditHead>,
+    #[serde(default, skip_serializing_if = "LoginAttempts::is_default")]
+    loginAttempts: LoginAttempts
 }
 
 #[derive(Serialize, Deserialize)]
@@ -69,7 +175,12 @@ struct IdentityCard {
     dateOfBirth: String,
     placeOfBirth: String,
     documentNo: String,
-    expiryDate: String
+    expiryDate: String,
+    ciphersuite: Ciphersuite,
+    statusListIndex: Option<usize>,
+    credentialStatus: Vec<StatusListEntry>,
+    expired: bool,
+    issuedCredential: Option<IssuedCredential>
 }
 
 fn sha256(input: &str) -> String {
@@ -80,104 +191,348 @@ fn sha256(input: &str) -> String {
     format!("{:x}", result)
 }
 
+// Data keys of the accounts logged in since the wallet started, only ever held in memory.
+#[derive(Default)]
+struct Sessions(Mutex<HashMap<String, DataKey>>);
+
+fn account_id(surname: &str, name: &str) -> String {
+    sha256(&format!("{}\n{}", surname, name))
+}
+
+// Unwraps the account data key with the password, creating it for accounts made before the history existed.
+fn open_session(sessions: &Sessions, account: &mut Account, password: &str) -> Result<bool, String> {
+    let (data_key, created) = match &account.dataKey {
+        Some(wrapped) => (keys::unwrap_data_key(wrapped, password)?, false),
+        None => {
+            let data_key = keys::generate_data_key();
+            account.dataKey = Some(keys::wrap_data_key(&data_key, password, keys::KdfParams::default())?);
+
+            (data_key, true)
+        }
+    };
+
+    sessions.0.lock().unwrap().insert(account_id(&account.surname, &account.name), data_key);
+
+    Ok(created)
+}
+
+// Failed attempts are counted per account and slow down, then lock, the next ones.
+fn check_password(account: &mut Account, password: &str, clock: &dyn Clock) -> Result<(), LoginError> {
+    account.loginAttempts.check(clock)?;
+
+    if account.password != sha256(password) {
+        return Err(account.loginAttempts.record_failure(clock));
+    }
+
+    account.loginAttempts.record_success();
+
+    Ok(())
+}
+
 #[tauri::command]
-fn login_account(surname: &str, name: &str, password: &str) -> bool {
-    #[derive(Serialize, Deserialize)]
-    struct Args {
-        surname: String,
-        name: String,
-        password: String
+fn login_account(surname: &str, name: &str, password: SecretString, sessions: tauri::State<Sessions>) -> Result<(), LoginError> {
+    let mut accounts_file_data = read_accounts_file();
+
+    let Some(account) = accounts_file_data.accountsArray.iter_mut().find(|e| e.surname == surname && e.name == name) else {
+        return Err(LoginError::WrongCredentials { remaining_attempts: None });
+    };
+
+    let attempts = account.loginAttempts.clone();
+    let checked = check_password(account, password.expose(), &SystemClock);
+
+    let opened = match checked {
+        Ok(()) => open_session(&sessions, account, password.expose()),
+        Err(_) => Ok(false)
+    };
+
+    let created = opened.as_ref().is_ok_and(|created| *created);
+
+    // The reset failure counter is saved even when the session could not be opened.
+    if (created || account.loginAttempts != attempts) && !write_accounts_file(&accounts_file_data) {
+        return Err(LoginError::Failed { error: "Failed to save the account".to_string() });
     }
 
-    let accounts_file_raw = fs::read_to_string(ACCOUNTS_FILE_PATH).unwrap_or_else(|_| "{\"accountsArray\":[]}".to_string());
+    checked?;
+    opened.map_err(|error| LoginError::Failed { error })?;
+
+    if created {
+        record_audit_event(&sessions, surname, name, AuditEvent::KeyGenerated { key: String::from("data_key") }).map_err(|error| LoginError::Failed { error })?;
+    }
+
+    Ok(())
+}
 
-    let accounts_file_data: AccountFileData = serde_json::from_str(&accounts_file_raw).unwrap_or(AccountFileData { accountsArray: vec![] });
+// Dropping the data key wipes it, the account has to log in again to use it.
+#[tauri::command]
+fn logout_account(surname: &str, name: &str, sessions: tauri::State<Sessions>, pending: tauri::State<PendingRecoveryKeys>) {
+    sessions.0.lock().unwrap().remove(&account_id(surname, name));
+    pending.0.lock().unwrap().remove(&account_id(surname, name));
+}
+
+// Returns the recovery phrase, shown once and never stored.
+#[tauri::command]
+fn create_account(surname: &str, name: &str, password: SecretString, sessions: tauri::State<Sessions>) -> Result<Zeroizing<String>, String> {
+    let mut accounts_file_data = read_accounts_file();
 
     let is_account_already_created = accounts_file_data.accountsArray.iter().any(|entry| entry.surname == surname && entry.name == name);
 
     if is_account_already_created == true {
-        let password_hash = accounts_file_data.accountsArray.iter().find(|e| e.surname == surname && e.name == name).map(|e| e.password.clone());
-        
-        if Some(sha256(password)) == password_hash {
-            return true;
-        }
+        return Err("An account with this surname and name already exists".to_string());
     }
 
-    false
+    check_password_policy(password.expose(), &[surname, name])?;
+
+    let mut account = Account {
+        surname: surname.to_string(),
+        name: name.to_string(),
+        password: sha256(password.expose()),
+        walletArray: vec![],
+        holderSecret: None,
+        encryptedHolderSecret: None,
+        dataKey: None,
+        recoveryKey: None,
+        historyValues: false,
+        auditHead: None,
+        loginAttempts: LoginAttempts::default()
+    };
+
+    open_session(&sessions, &mut account, password.expose())?;
+
+    let recovery_phrase = recovery::generate_recovery_phrase();
+    let data_key = sessions.0.lock().unwrap().get(&account_id(surname, name)).cloned().ok_or("Account session not opened")?;
+
+    account.recoveryKey = Some(recovery::wrap_with_recovery_phrase(&data_key, &recovery_phrase, keys::KdfParams::default())?);
+
+    accounts_file_data.accountsArray.push(account);
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the account".to_string());
+    }
+
+    record_audit_event(&sessions, surname, name, AuditEvent::AccountCreated)?;
+
+    Ok(recovery_phrase)
 }
 
+// Recovery phrases waiting to be confirmed, the previous phrase keeps working until then.
+#[derive(Default)]
+struct PendingRecoveryKeys(Mutex<HashMap<String, keys::WrappedKey>>);
+
+// Creates a new recovery phrase, for accounts created without one or when the written copy is lost.
+// It only replaces the previous one once confirmed with confirm_recovery_phrase.
 #[tauri::command]
-fn create_account(surname: &str, name: &str, password: &str) -> bool {
-    let accounts_file_raw = fs::read_to_string(ACCOUNTS_FILE_PATH).unwrap_or_else(|_| "{\"accountsArray\":[]}".to_string());
+fn create_recovery_phrase(surname: &str, name: &str, password: SecretString, sessions: tauri::State<Sessions>, pending: tauri::State<PendingRecoveryKeys>) -> Result<Zeroizing<String>, String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
 
-    let mut accounts_file_data: AccountFileData = serde_json::from_str(&accounts_file_raw).unwrap_or(AccountFileData { accountsArray: vec![] });
+    let checked = check_password(account, password.expose(), &SystemClock);
+    let opened = checked.is_ok().then(|| open_session(&sessions, account, password.expose()));
 
-    let is_account_already_created = accounts_file_data.accountsArray.iter().any(|entry| entry.surname == surname && entry.name == name);
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the account".to_string());
+    }
 
-    if is_account_already_created == false {
-        let account = Account {
-            surname: surname.to_string(),
-            name: name.to_string(),
-            password: sha256(password),
-            walletArray: vec![],
-        };
+    checked.map_err(|error| error.to_string())?;
+    opened.transpose()?;
 
-        accounts_file_data.accountsArray.push(account);
+    let recovery_phrase = recovery::generate_recovery_phrase();
+    let data_key = sessions.0.lock().unwrap().get(&account_id(surname, name)).cloned().ok_or("Account session not opened")?;
 
-        let json_string = match serde_json::to_string_pretty(&accounts_file_data) {
-            Ok(s) => s,
-            Err(_) => return false
-        };
+    pending.0.lock().unwrap().insert(account_id(surname, name), recovery::wrap_with_recovery_phrase(&data_key, &recovery_phrase, keys::KdfParams::default())?);
 
-        if fs::write(ACCOUNTS_FILE_PATH, json_string).is_err() {
-            return false;
-        }
+    Ok(recovery_phrase)
+}
+
+#[tauri::command]
+fn confirm_recovery_phrase(surname: &str, name: &str, sessions: tauri::State<Sessions>, pending: tauri::State<PendingRecoveryKeys>) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    let recovery_key = pending.0.lock().unwrap().get(&account_id(surname, name)).cloned().ok_or("No new recovery phrase to confirm")?;
+
+    account.recoveryKey = Some(recovery_key);
+
+    append_audit_event(&sessions, account, AuditEvent::RecoveryPhraseCreated)?;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the recovery phrase".to_string());
     }
 
-    true
+    pending.0.lock().unwrap().remove(&account_id(surname, name));
+
+    Ok(())
 }
 
+// Forgotten password: the recovery phrase unwraps the data key, which is wrapped again by the new password.
+// The history and the audit log stay readable and the account is logged in.
+#[tauri::command]
+fn recover_account(surname: &str, name: &str, recoveryPhrase: SecretString, newPassword: SecretString, sessions: tauri::State<Sessions>) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    let recovery_key = account.recoveryKey.as_ref().ok_or("No recovery phrase was set up for this account")?;
+    let data_key = recovery::unwrap_with_recovery_phrase(recovery_key, recoveryPhrase.expose())?;
+
+    check_password_policy(newPassword.expose(), &[surname, name])?;
 
+    account.dataKey = Some(keys::wrap_data_key(&data_key, newPassword.expose(), keys::KdfParams::default())?);
+    account.password = sha256(newPassword.expose());
+    account.loginAttempts.record_success();
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the new password".to_string());
+    }
+
+    sessions.0.lock().unwrap().insert(account_id(surname, name), data_key);
+
+    record_audit_event(&sessions, surname, name, AuditEvent::AccountRecovered)
+}
+
+// Feeds the strength meter while the password is typed, the same estimate decides whether it is accepted.
 #[tauri::command]
-fn create_card(surname: &str, name: &str, country: &str, sex: &str, date_of_birth: &str, place_of_birth: &str, document_no: &str, expiry_date: &str) -> bool {
-    let accounts_file_raw = fs::read_to_string(ACCOUNTS_FILE_PATH).unwrap_or_else(|_| "{\"accountsArray\":[]}".to_string());
+fn password_strength(surname: &str, name: &str, password: SecretString) -> PasswordStrength {
+    estimate_strength(password.expose(), &[surname, name])
+}
+
+// The data key is only rewrapped, the history and the audit log stay readable under the new password.
+#[tauri::command]
+fn change_password(surname: &str, name: &str, password: SecretString, newPassword: SecretString, sessions: tauri::State<Sessions>) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    let attempts = account.loginAttempts.clone();
+    let checked = check_password(account, password.expose(), &SystemClock);
+
+    // The failure counter, or its reset, is saved before anything else about the new password can fail.
+    if account.loginAttempts != attempts && !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the account".to_string());
+    }
+
+    checked.map_err(|error| error.to_string())?;
+
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    check_password_policy(newPassword.expose(), &[surname, name])?;
+
+    open_session(&sessions, account, password.expose())?;
+
+    let data_key = sessions.0.lock().unwrap().get(&account_id(surname, name)).cloned().ok_or("Log in again to change the password")?;
+
+    account.dataKey = Some(keys::wrap_data_key(&data_key, newPassword.expose(), keys::KdfParams::default())?);
+    account.password = sha256(newPassword.expose());
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the new password".to_string());
+    }
+
+    record_audit_event(&sessions, surname, name, AuditEvent::PasswordChanged)
+}
+
+fn audit_log(sessions: &Sessions, surname: &str, name: &str) -> Result<AuditLog, String> {
+    let account_id = account_id(surname, name);
+    let sessions = sessions.0.lock().unwrap();
+    let data_key = sessions.get(&account_id).ok_or("Log in again to unlock the audit log")?;
+
+    Ok(AuditLog::new(Path::new(AUDIT_DIR_PATH), &account_id, data_key))
+}
+
+// Chains the event to the account audit log and moves the head kept with the account, saved by the caller.
+fn append_audit_event(sessions: &Sessions, account: &mut Account, event: AuditEvent) -> Result<(), String> {
+    account.auditHead = Some(audit_log(sessions, &account.surname, &account.name)?.append(event, SystemClock.now())?);
+
+    Ok(())
+}
+
+fn record_audit_event(sessions: &Sessions, surname: &str, name: &str, event: AuditEvent) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    append_audit_event(sessions, account, event)?;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the audit log head".to_string());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+fn verify_audit_log(surname: &str, name: &str, sessions: tauri::State<Sessions>) -> Result<AuditVerification, String> {
+    let head = read_accounts_file().accountsArray.into_iter().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?.auditHead;
+
+    audit_log(&sessions, surname, name)?.verify(head.as_ref())
+}
+
+
+// Card changes are chained to the audit log before they are saved, so that no saved change goes unrecorded.
+#[tauri::command]
+fn create_card(surname: &str, name: &str, country: &str, sex: &str, date_of_birth: &str, place_of_birth: &str, document_no: &str, expiry_date: &str, ciphersuite: Ciphersuite, sessions: tauri::State<Sessions>) -> Result<(), String> {
+    // Only cards received from an issuer are signed by the zkp module.
+    if ciphersuite == Ciphersuite::WalletBbs {
+        return Err("Unsupported ciphersuite for a new card".to_string());
+    }
 
-    let mut accounts_file_data: AccountFileData = serde_json::from_str(&accounts_file_raw).unwrap_or(AccountFileData { accountsArray: vec![] });
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
 
-    let new_wallet = Wallet {
+    // Nothing is allocated for the card without a session to record it.
+    audit_log(&sessions, surname, name)?;
+
+    let status_list_index = allocate_status_list_index()?;
+    let (accumulator_witness, accumulator_epoch) = accumulator_add(status_list_index)?;
+
+    account.walletArray.push(Wallet {
         country: country.to_string(),
         sex: sex.to_string(),
         dateOfBirth: date_of_birth.to_string(),
         placeOfBirth: place_of_birth.to_string(),
         documentNo: document_no.to_string(),
-        expiryDate: expiry_date.to_string()
-    };
+        expiryDate: expiry_date.to_string(),
+        ciphersuite,
+        statusListIndex: Some(status_list_index),
+        accumulatorWitness: Some(accumulator_witness),
+        accumulatorEpoch: Some(accumulator_epoch),
+        issuedCredential: None
+    });
 
-    if let Some(account) = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name) {
-        account.walletArray.push(new_wallet);
-    } else {
-        return false;
+    let card_index = account.walletArray.len() - 1;
+
+    append_audit_event(&sessions, account, AuditEvent::CardCreated { card_index })?;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the card".to_string());
     }
 
-    let json_string = match serde_json::to_string_pretty(&accounts_file_data) {
-        Ok(s) => s,
-        Err(_) => return false
-    };
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_card(surname: &str, name: &str, cardIndex: usize, sessions: tauri::State<Sessions>) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
 
-    if fs::write(ACCOUNTS_FILE_PATH, json_string).is_err() {
-        return false;
+    if cardIndex >= account.walletArray.len() {
+        return Err("Card not found".to_string());
     }
 
-    true
+    account.walletArray.remove(cardIndex);
+
+    append_audit_event(&sessions, account, AuditEvent::CardDeleted { card_index: cardIndex })?;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to delete the card".to_string());
+    }
+
+    Ok(())
 }
 
 #[tauri::command]
 fn fetch_wallet_data(surname: &str, name: &str) -> Vec<IdentityCard> {
-    let accounts_file_raw = fs::read_to_string(ACCOUNTS_FILE_PATH).unwrap_or_else(|_| "{\"accountsArray\":[]}".to_string());
-
-    let accounts_file_data: AccountFileData = serde_json::from_str(&accounts_file_raw).unwrap_or(AccountFileData { accountsArray: vec![] });
+    let accounts_file_data = read_accounts_file();
 
     if let Some(account) = accounts_file_data.accountsArray.into_iter().find(|acc| acc.surname == surname && acc.name == name) {
+        let clock = SystemClock;
+
         return account.walletArray.into_iter().map(|item| IdentityCard {
             surname: account.surname.clone(),
             name: account.name.clone(),
@@ -186,7 +541,16 @@ fn fetch_wallet_data(surname: &str, name: &str) -> Vec<IdentityCard> {
             dateOfBirth: item.dateOfBirth,
             placeOfBirth: item.placeOfBirth,
             documentNo: item.documentNo,
+            // Received cards were recorded with the default suite by older versions.
+            ciphersuite: if item.issuedCredential.is_some() { Ciphersuite::WalletBbs } else { item.ciphersuite },
+            statusListIndex: item.statusListIndex,
+            credentialStatus: item.statusListIndex.map(|index| vec![
+                StatusListEntry::new(StatusPurpose::Revocation, index),
+                StatusListEntry::new(StatusPurpose::Suspension, index)
+            ]).unwrap_or_default(),
+            expired: validity::check_expiry_date(&item.expiryDate, &clock).is_err(),
             expiryDate: item.expiryDate,
+            issuedCredential: item.issuedCredential,
         }).collect();
     }
 
@@ -196,7 +560,8 @@ fn fetch_wallet_data(surname: &str, name: &str) -> Vec<IdentityCard> {
 #[derive(Serialize, Deserialize)]
 struct Signature {
     signature: String,
-    public_key: String
+    public_key: String,
+    ciphersuite: Ciphersuite
 }
 
 const KEY_GEN_IKM: &[u8; 49] = b"PKGVYkAHJrc95mGeRwLLVN7JxmHwEPwxEnvhyB7UCQsE4k9Py";
@@ -204,89 +569,172 @@ const SIGNATURE_KEY_INFO: &[u8; 15] = b"wallet-key-info";
 const SIGNATURE_HEADER: &[u8; 13] = b"wallet-header";
 const SIGNATURE_PRESENTATION_HEADER: &[u8; 26] = b"wallet-presentation-header";
 
-fn generate_keypair() -> Result<([u8; 32], [u8; 96]), String> {
+fn generate_keypair() -> Result<(SecretBytes<32>, [u8; 96]), String> {
     let keypair = KeyPair::new(KEY_GEN_IKM, SIGNATURE_KEY_INFO).ok_or("KeyPair generation failed")?;
 
     Ok((
-        keypair.secret_key.to_bytes(),
+        SecretBytes::take(&mut keypair.secret_key.to_bytes()),
         keypair.public_key.to_octets()
     ))
 }
 
-fn sign_messages(secret_key: &[u8; 32], public_key: &[u8; 96], messages: &[&[u8]]) -> Result<[u8; 80], String> {
-    sign(&BbsSignRequest {
+fn sign_messages(ciphersuite: Ciphersuite, secret_key: &[u8; 32], public_key: &[u8; 96], messages: &[&[u8]]) -> Result<[u8; 80], String> {
+    bbs::sign(ciphersuite, &BbsSignRequest {
         secret_key,
         public_key,
         header: Some(SIGNATURE_HEADER.as_ref()),
         messages: Some(messages)
-    }).map_err(|e| e.to_string())
+    })
 }
 
-fn verify_messages(public_key: &[u8; 96], signature: &[u8; 80], messages: &[&[u8]]) -> Result<bool, String> {
-    verify(&BbsVerifyRequest {
+fn verify_messages(ciphersuite: Ciphersuite, public_key: &[u8; 96], signature: &[u8; 80], messages: &[&[u8]]) -> Result<bool, String> {
+    bbs::verify(ciphersuite, &BbsVerifyRequest {
         public_key,
         header: Some(SIGNATURE_HEADER.as_ref()),
         messages: Some(messages),
         signature
-    }).map_err(|e| e.to_string())
+    })
 }
 
 #[tauri::command]
-fn create_signature(messagesArray: Vec<String>) -> Result<Signature, String> {
+fn create_signature(messagesArray: Vec<String>, ciphersuite: Ciphersuite) -> Result<Signature, String> {
     let messages_bytes: Vec<Vec<u8>> = messagesArray.into_iter().map(|s| s.into_bytes()).collect();
     let messages: Vec<&[u8]> = messages_bytes.iter().map(|m| m.as_slice()).collect();
 
     let (secret_key, public_key) = generate_keypair()?;
 
-    let signature = sign_messages(&secret_key, &public_key, &messages)?;
+    let signature = sign_messages(ciphersuite, secret_key.expose(), &public_key, &messages)?;
 
     Ok(Signature {
         signature: hex::encode(signature),
-        public_key: hex::encode(public_key)
+        public_key: hex::encode(public_key),
+        ciphersuite
     })
 }
 
+// did:web documents copied into the data directory are used before fetching them.
+fn did_resolver() -> DidResolver {
+    DidResolver::new(reqwest::Client::new(), Some(PathBuf::from(DID_DOCUMENTS_DIR_PATH)))
+}
+
+fn read_trust_registry() -> TrustRegistry {
+    let trusted_issuers_file_raw = fs::read_to_string(TRUSTED_ISSUERS_FILE_PATH).unwrap_or_else(|_| "{\"issuers\":[]}".to_string());
+
+    serde_json::from_str(&trusted_issuers_file_raw).unwrap_or_default()
+}
+
+fn write_trust_registry(registry: &TrustRegistry) -> Result<(), String> {
+    let json_string = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+
+    fs::write(TRUSTED_ISSUERS_FILE_PATH, json_string).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
-fn verify_signature(signatureHex: String, publicKeyHex: String, messagesArray: Vec<String>) -> bool {
+fn fetch_trusted_issuers() -> Vec<TrustedIssuer> {
+    read_trust_registry().issuers
+}
+
+#[tauri::command]
+async fn add_trusted_issuer(mut trustedIssuer: TrustedIssuer) -> Result<(), String> {
+    // The key may be given as the DID URL the issuer publishes it under.
+    if trustedIssuer.public_key.starts_with("did:") {
+        trustedIssuer.public_key = did_resolver().resolve_public_key(&trustedIssuer.public_key).await?;
+    } else {
+        trustedIssuer.public_key = hex::encode(keys::import_public_key(&trustedIssuer.public_key)?.to_bytes());
+    }
+
+    let mut registry = read_trust_registry();
+
+    registry.add(trustedIssuer)?;
+
+    write_trust_registry(&registry)
+}
+
+#[tauri::command]
+fn remove_trusted_issuer(id: String) -> Result<(), String> {
+    let mut registry = read_trust_registry();
+
+    registry.remove(&id)?;
+
+    write_trust_registry(&registry)
+}
+
+#[tauri::command]
+fn export_public_key(publicKeyHex: String, format: KeyFormat) -> Result<String, String> {
+    let public_key = zkp::signature::PublicKey::from_bytes(&hex::decode(publicKeyHex).map_err(|e| e.to_string())?)?;
+
+    keys::export_public_key(&public_key, format)
+}
+
+// Accepts a JWK, Multikey, PEM or hex key and returns it as hex, the encoding used everywhere else in the wallet.
+#[tauri::command]
+fn import_public_key(publicKey: String) -> Result<String, String> {
+    keys::import_public_key(&publicKey).map(|public_key| hex::encode(public_key.to_bytes()))
+}
+
+#[tauri::command]
+fn verify_signature(signatureHex: String, publicKeyHex: String, messagesArray: Vec<String>, ciphersuite: Ciphersuite) -> SignatureResult {
+    let issuer = read_trust_registry().check(&publicKeyHex, IDENTITY_CARD_TYPE, &SystemClock);
+
     let public_key: [u8; 96] = match hex::decode(publicKeyHex).ok().and_then(|v| v.try_into().ok()){
         Some(pk) => pk,
-        None => return false
+        None => return SignatureResult { verified: false, error: None, issuer }
     };
 
     let signature: [u8; 80] = match hex::decode(signatureHex).ok().and_then(|v| v.try_into().ok()) {
         Some(sig) => sig,
-        None => return false
+        None => return SignatureResult { verified: false, error: None, issuer }
     };
 
+    // A valid signature over an expired card is still rejected.
+    if let Err(error) = validity::check_messages_validity(&messagesArray, &SystemClock) {
+        return SignatureResult { verified: false, error: Some(error), issuer };
+    }
+
     let messages_bytes: Vec<Vec<u8>> = messagesArray.into_iter().map(|s| s.into_bytes()).collect();
     let messages: Vec<&[u8]> = messages_bytes.iter().map(|m| m.as_slice()).collect();
 
-    verify_messages(&public_key, &signature, &messages).unwrap_or(false)
+    SignatureResult {
+        verified: verify_messages(ciphersuite, &public_key, &signature, &messages).unwrap_or(false),
+        error: None,
+        issuer
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct DisclosedMessage {
     pub index: usize,
     pub value: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SignatureResult {
+    pub verified: bool,
+    pub error: Option<ValidityError>,
+    pub issuer: IssuerTrust,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct PartialSignatureResult {
     pub verified: bool,
     pub disclosed_messages: Vec<DisclosedMessage>,
+    pub error: Option<ValidityError>,
+    pub issuer: IssuerTrust,
 }
 
 #[tauri::command]
-fn verify_signature_indices(signatureHex: String, publicKeyHex: String, messagesArray: Vec<String>, indicesArray: Vec<usize>) -> PartialSignatureResult {
+fn verify_signature_indices(signatureHex: String, publicKeyHex: String, messagesArray: Vec<String>, indicesArray: Vec<usize>, ciphersuite: Ciphersuite) -> PartialSignatureResult {
+
+    let issuer = read_trust_registry().check(&publicKeyHex, IDENTITY_CARD_TYPE, &SystemClock);
 
     let public_key: [u8; 96] = match hex::decode(publicKeyHex).ok().and_then(|v| v.try_into().ok()) {
         Some(pk) => pk,
-        None => return PartialSignatureResult { verified: false, disclosed_messages: vec![] }
+        None => return PartialSignatureResult { verified: false, disclosed_messages: vec![], error: None, issuer }
     };
 
     let signature: [u8; 80] = match hex::decode(signatureHex).ok().and_then(|v| v.try_into().ok()) {
         Some(sig) => sig,
-        None => return PartialSignatureResult { verified: false, disclosed_messages: vec![] }
+        None => return PartialSignatureResult { verified: false, disclosed_messages: vec![], error: None, issuer }
     };
 
     let proof_messages: Vec<BbsProofGenRevealMessageRequest<_>> = messagesArray.iter().enumerate().map(|(i, msg)| {
@@ -310,11 +758,13 @@ fn verify_signature_indices(signatureHex: String, publicKeyHex: String, messages
     if disclosed_for_verify.is_empty() {
         return PartialSignatureResult {
             verified: false,
-            disclosed_messages
+            disclosed_messages,
+            error: None,
+            issuer
         };
     }
 
-    let proof = match proof_gen(&BbsProofGenRequest {
+    let proof = match bbs::proof_gen(ciphersuite, &BbsProofGenRequest {
         public_key: &public_key,
         header: Some(SIGNATURE_HEADER.as_ref()),
         messages: Some(&proof_messages),
@@ -323,10 +773,10 @@ fn verify_signature_indices(signatureHex: String, publicKeyHex: String, messages
         verify_signature: None
     }) {
         Ok(p) => p,
-        Err(_) => return PartialSignatureResult { verified: false, disclosed_messages }
+        Err(_) => return PartialSignatureResult { verified: false, disclosed_messages, error: None, issuer }
     };
 
-    let verified = match proof_verify(&BbsProofVerifyRequest {
+    let verified = match bbs::proof_verify(ciphersuite, &BbsProofVerifyRequest {
         public_key: &public_key,
         header: Some(SIGNATURE_HEADER.as_ref()),
         presentation_header: Some(SIGNATURE_PRESENTATION_HEADER.as_ref()),
@@ -337,26 +787,855 @@ fn verify_signature_indices(signatureHex: String, publicKeyHex: String, messages
         Err(_) => false
     };
 
+    // The expiry date is always checked on the full card, even when it is not disclosed.
+    if let Err(error) = validity::check_messages_validity(&messagesArray, &SystemClock) {
+        return PartialSignatureResult { verified: false, disclosed_messages, error: Some(error), issuer };
+    }
+
     PartialSignatureResult {
         verified,
-        disclosed_messages
+        disclosed_messages,
+        error: None,
+        issuer
+    }
+}
+
+const BOUND_SIGNATURE_KEY_INFO: &[u8; 21] = b"wallet-bound-key-info";
+
+#[derive(Serialize, Deserialize)]
+struct BoundSignature {
+    signature: String,
+    public_key: String,
+    prover_blind: String
+}
+
+// Cards received from an issuer keep the DID URL it signed with, the others are referred to by the did:key of their key.
+fn card_verification_method(surname: &str, name: &str, publicKeyHex: &str) -> Result<String, String> {
+    let accounts_file_data = read_accounts_file();
+
+    let stored = accounts_file_data.accountsArray.iter()
+        .find(|acc| acc.surname == surname && acc.name == name)
+        .and_then(|acc| acc.walletArray.iter().filter_map(|w| w.issuedCredential.as_ref()).find(|credential| credential.publicKey.eq_ignore_ascii_case(publicKeyHex)))
+        .and_then(|credential| credential.verificationMethod.clone());
+
+    match stored {
+        Some(verification_method) => Ok(verification_method),
+        None => Ok(did::did_key_verification_method(&hex::decode(publicKeyHex).map_err(|e| e.to_string())?))
+    }
+}
+
+// The holder secret is generated on first use and stored encrypted by the data key, so it needs an open session.
+fn load_holder_secret(sessions: &Sessions, surname: &str, name: &str) -> Result<SecretScalar, String> {
+    let account_id = account_id(surname, name);
+    let data_key = sessions.0.lock().unwrap().get(&account_id).cloned().ok_or("Log in again to unlock the holder secret")?;
+    let aad = format!("{}\nholder_secret", account_id);
+
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    if let Some(encrypted) = &account.encryptedHolderSecret {
+        return SecretScalar::from_bytes(&keys::decrypt_data(&data_key, encrypted, aad.as_bytes())?);
+    }
+
+    let (holder_secret, generated) = match account.holderSecret.take().map(Zeroizing::new) {
+        Some(holder_secret_hex) => (SecretScalar::from_bytes(&Zeroizing::new(hex::decode(holder_secret_hex.as_bytes()).map_err(|e| e.to_string())?))?, false),
+        None => (SecretScalar::random(), true)
+    };
+
+    account.encryptedHolderSecret = Some(keys::encrypt_data(&data_key, Zeroizing::new(holder_secret.expose().to_bytes_be()).as_slice(), aad.as_bytes())?);
+
+    if generated {
+        append_audit_event(sessions, account, AuditEvent::KeyGenerated { key: String::from("holder_secret") })?;
+    }
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to store holder secret".to_string());
+    }
+
+    Ok(holder_secret)
+}
+
+#[tauri::command]
+fn create_bound_signature(surname: &str, name: &str, messagesArray: Vec<String>, sessions: tauri::State<Sessions>) -> Result<BoundSignature, String> {
+    let holder_secret = load_holder_secret(&sessions, surname, name)?;
+
+    let secret_key = keys::load_or_generate_secret_key(LOCAL_ISSUER_KEY_FILE_PATH, BOUND_SIGNATURE_KEY_INFO)?;
+    let public_key = secret_key.public_key();
+
+    let (commitment, prover_blind) = zkp::commitment::commit(&public_key, SIGNATURE_HEADER, holder_secret.expose());
+
+    let messages = zkp::messages_to_scalars(&messagesArray);
+
+    let signature = zkp::commitment::blind_sign(&secret_key, &public_key, SIGNATURE_HEADER, &messages, &commitment)?;
+
+    if !zkp::signature::verify(&public_key, SIGNATURE_HEADER, &messages, &[*holder_secret.expose(), prover_blind], &signature) {
+        return Err("Issued signature is invalid".to_string());
+    }
+
+    Ok(BoundSignature {
+        signature: hex::encode(signature.to_bytes()),
+        public_key: hex::encode(public_key.to_bytes()),
+        prover_blind: hex::encode(prover_blind.to_bytes_be())
+    })
+}
+
+#[tauri::command]
+fn verify_bound_signature(surname: &str, name: &str, signatureHex: String, publicKeyHex: String, proverBlindHex: String, messagesArray: Vec<String>, sessions: tauri::State<Sessions>) -> SignatureResult {
+    let issuer = read_trust_registry().check(&publicKeyHex, IDENTITY_CARD_TYPE, &SystemClock);
+
+    let Ok(holder_secret) = load_holder_secret(&sessions, surname, name) else {
+        return SignatureResult { verified: false, error: None, issuer };
+    };
+
+    let public_key = match hex::decode(publicKeyHex).map_err(|e| e.to_string()).and_then(|v| zkp::signature::PublicKey::from_bytes(&v)) {
+        Ok(pk) => pk,
+        Err(_) => return SignatureResult { verified: false, error: None, issuer }
+    };
+
+    let signature = match hex::decode(signatureHex).map_err(|e| e.to_string()).and_then(|v| zkp::signature::Signature::from_bytes(&v)) {
+        Ok(sig) => sig,
+        Err(_) => return SignatureResult { verified: false, error: None, issuer }
+    };
+
+    let prover_blind = match hex::decode(proverBlindHex).map_err(|e| e.to_string()).and_then(|v| zkp::scalar_from_bytes(&v)) {
+        Ok(blind) => blind,
+        Err(_) => return SignatureResult { verified: false, error: None, issuer }
+    };
+
+    if let Err(error) = validity::check_messages_validity(&messagesArray, &SystemClock) {
+        return SignatureResult { verified: false, error: Some(error), issuer };
+    }
+
+    SignatureResult {
+        verified: zkp::signature::verify(&public_key, SIGNATURE_HEADER, &zkp::messages_to_scalars(&messagesArray), &[*holder_secret.expose(), prover_blind], &signature),
+        error: None,
+        issuer
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StatusListsFileData {
+    nextIndex: usize,
+    revocationList: String,
+    suspensionList: String
+}
+
+fn read_status_lists_file() -> Result<StatusListsFileData, String> {
+    match fs::read_to_string(STATUS_LISTS_FILE_PATH) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| e.to_string()),
+        Err(_) => {
+            let empty_list = StatusList::new(STATUS_LIST_LENGTH).encode()?;
+
+            Ok(StatusListsFileData {
+                nextIndex: 0,
+                revocationList: empty_list.clone(),
+                suspensionList: empty_list
+            })
+        }
     }
 }
 
+fn write_status_lists_file(status_lists_file_data: &StatusListsFileData) -> Result<(), String> {
+    let json_string = serde_json::to_string_pretty(status_lists_file_data).map_err(|e| e.to_string())?;
+
+    fs::write(STATUS_LISTS_FILE_PATH, json_string).map_err(|e| e.to_string())?;
+
+    publish_status_lists(status_lists_file_data)
+}
+
+// Signed by the key of the bound cards, verifiers match the list issuer against the card issuer.
+fn issue_status_lists(status_lists_file_data: &StatusListsFileData) -> Result<Vec<StatusListCredential>, String> {
+    let secret_key = keys::load_or_generate_secret_key(LOCAL_ISSUER_KEY_FILE_PATH, BOUND_SIGNATURE_KEY_INFO)?;
+
+    Ok(vec![
+        StatusListCredential::issue(&secret_key, StatusPurpose::Revocation, &StatusList::decode(&status_lists_file_data.revocationList)?)?,
+        StatusListCredential::issue(&secret_key, StatusPurpose::Suspension, &StatusList::decode(&status_lists_file_data.suspensionList)?)?
+    ])
+}
+
+// The wallet acts as its own issuer, so updated lists are published straight into the verifier cache.
+fn publish_status_lists(status_lists_file_data: &StatusListsFileData) -> Result<(), String> {
+    write_status_list_cache(&issue_status_lists(status_lists_file_data)?)
+}
+
+fn write_status_list_cache(status_lists: &[StatusListCredential]) -> Result<(), String> {
+    let json_string = serde_json::to_string_pretty(status_lists).map_err(|e| e.to_string())?;
+
+    fs::write(STATUS_LIST_CACHE_FILE_PATH, json_string).map_err(|e| e.to_string())
+}
+
+fn allocate_status_list_index() -> Result<usize, String> {
+    let mut status_lists_file_data = read_status_lists_file()?;
+
+    let index = status_lists_file_data.nextIndex;
+
+    if index >= STATUS_LIST_LENGTH {
+        return Err("Status list is full".to_string());
+    }
+
+    status_lists_file_data.nextIndex += 1;
+    write_status_lists_file(&status_lists_file_data)?;
+
+    Ok(index)
+}
+
+fn set_status(statusListIndex: usize, purpose: StatusPurpose, value: bool) -> Result<(), String> {
+    let mut status_lists_file_data = read_status_lists_file()?;
+
+    if statusListIndex >= status_lists_file_data.nextIndex {
+        return Err("Unknown status list index".to_string());
+    }
+
+    let encoded_list = match purpose {
+        StatusPurpose::Revocation => &mut status_lists_file_data.revocationList,
+        StatusPurpose::Suspension => &mut status_lists_file_data.suspensionList
+    };
+
+    let mut list = StatusList::decode(encoded_list)?;
+    list.set(statusListIndex, value)?;
+    *encoded_list = list.encode()?;
+
+    write_status_lists_file(&status_lists_file_data)
+}
+
+#[tauri::command]
+fn revoke_card(statusListIndex: usize) -> Result<(), String> {
+    set_status(statusListIndex, StatusPurpose::Revocation, true)?;
+
+    accumulator_remove(statusListIndex)
+}
+
+#[tauri::command]
+fn suspend_card(statusListIndex: usize, suspended: bool) -> Result<(), String> {
+    set_status(statusListIndex, StatusPurpose::Suspension, suspended)
+}
+
+#[tauri::command]
+fn export_status_lists() -> Result<Vec<StatusListCredential>, String> {
+    issue_status_lists(&read_status_lists_file()?)
+}
+
+fn load_status_lists(statusListFile: Option<String>) -> Option<Vec<StatusListCredential>> {
+    match statusListFile {
+        Some(path) => {
+            let status_lists: Vec<StatusListCredential> = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+
+            let _ = write_status_list_cache(&status_lists);
+
+            Some(status_lists)
+        },
+        None => serde_json::from_str(&fs::read_to_string(STATUS_LIST_CACHE_FILE_PATH).ok()?).ok()
+    }
+}
+
+const ACCUMULATOR_KEY_INFO: &[u8; 27] = b"wallet-accumulator-key-info";
+
+// Written as "add" and "remove", anything else fails to read instead of being taken for a revocation.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum AccumulatorAction {
+    Add,
+    Remove
+}
+
+impl From<AccumulatorAction> for zkp::accumulator::UpdateAction {
+    fn from(action: AccumulatorAction) -> Self {
+        match action {
+            AccumulatorAction::Add => zkp::accumulator::UpdateAction::Add,
+            AccumulatorAction::Remove => zkp::accumulator::UpdateAction::Remove
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct AccumulatorUpdateData {
+    epoch: u64,
+    action: AccumulatorAction,
+    element: String,
+    value: String
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccumulatorFileData {
+    epoch: u64,
+    value: String,
+    updates: Vec<AccumulatorUpdateData>
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccumulatorPublicData {
+    epoch: u64,
+    value: String,
+    publicKey: String
+}
+
+// Only its public key leaves the key file, through fetch_accumulator.
+fn accumulator_secret_key() -> Result<zkp::signature::SecretKey, String> {
+    keys::load_or_generate_secret_key(ACCUMULATOR_KEY_FILE_PATH, ACCUMULATOR_KEY_INFO)
+}
+
+fn read_accumulator_file() -> Result<AccumulatorFileData, String> {
+    match fs::read_to_string(ACCUMULATOR_FILE_PATH) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|e| e.to_string()),
+        Err(_) => Ok(AccumulatorFileData {
+            epoch: 0,
+            value: hex::encode(zkp::accumulator::Accumulator::new().to_bytes()),
+            updates: vec![]
+        })
+    }
+}
+
+fn write_accumulator_file(accumulator_file_data: &AccumulatorFileData) -> Result<(), String> {
+    let json_string = serde_json::to_string_pretty(accumulator_file_data).map_err(|e| e.to_string())?;
+
+    fs::write(ACCUMULATOR_FILE_PATH, json_string).map_err(|e| e.to_string())
+}
+
+fn revocation_element(statusListIndex: usize) -> Scalar {
+    zkp::message_to_scalar(statusListIndex.to_string().as_bytes())
+}
+
+fn update_accumulator(action: AccumulatorAction, statusListIndex: usize) -> Result<(zkp::accumulator::Accumulator, u64), String> {
+    let secret_key = accumulator_secret_key()?;
+    let mut accumulator_file_data = read_accumulator_file()?;
+
+    let element = revocation_element(statusListIndex);
+    let accumulator = zkp::accumulator::Accumulator::from_bytes(&hex::decode(&accumulator_file_data.value).map_err(|e| e.to_string())?)?;
+
+    let accumulator = match action {
+        AccumulatorAction::Add => accumulator.add(&secret_key, &element),
+        AccumulatorAction::Remove => accumulator.remove(&secret_key, &element)?
+    };
+
+    accumulator_file_data.epoch += 1;
+    accumulator_file_data.value = hex::encode(accumulator.to_bytes());
+    accumulator_file_data.updates.push(AccumulatorUpdateData {
+        epoch: accumulator_file_data.epoch,
+        action,
+        element: hex::encode(element.to_bytes_be()),
+        value: accumulator_file_data.value.clone()
+    });
+
+    write_accumulator_file(&accumulator_file_data)?;
+
+    Ok((accumulator, accumulator_file_data.epoch))
+}
+
+fn accumulator_add(statusListIndex: usize) -> Result<(String, u64), String> {
+    let (accumulator, epoch) = update_accumulator(AccumulatorAction::Add, statusListIndex)?;
+
+    let witness = accumulator.witness(&accumulator_secret_key()?, &revocation_element(statusListIndex))?;
+
+    Ok((hex::encode(witness.to_bytes()), epoch))
+}
+
+fn accumulator_remove(statusListIndex: usize) -> Result<(), String> {
+    update_accumulator(AccumulatorAction::Remove, statusListIndex).map(|_| ())
+}
+
+fn accumulator_value_at(accumulator_file_data: &AccumulatorFileData, epoch: u64) -> Result<zkp::accumulator::Accumulator, String> {
+    let value = match epoch {
+        0 => hex::encode(zkp::accumulator::Accumulator::new().to_bytes()),
+        _ => accumulator_file_data.updates.iter().find(|u| u.epoch == epoch).map(|u| u.value.clone()).ok_or("Unknown accumulator epoch")?
+    };
+
+    zkp::accumulator::Accumulator::from_bytes(&hex::decode(value).map_err(|e| e.to_string())?)
+}
+
+// None once the card was removed from the accumulator: a revoked card keeps its last witness.
+fn updated_witness(wallet: &Wallet, accumulator_file_data: &AccumulatorFileData) -> Result<Option<NonRevocationWitness>, String> {
+    let index = wallet.statusListIndex.ok_or("Card has no revocation identifier")?;
+    let epoch = wallet.accumulatorEpoch.ok_or("Card has no accumulator witness")?;
+    let witness = zkp::accumulator::Witness::from_bytes(&hex::decode(wallet.accumulatorWitness.as_ref().ok_or("Card has no accumulator witness")?).map_err(|e| e.to_string())?)?;
+
+    let updates = accumulator_file_data.updates.iter().filter(|u| u.epoch > epoch).map(|u| {
+        Ok(zkp::accumulator::AccumulatorUpdate {
+            action: u.action.into(),
+            element: zkp::scalar_from_bytes(&hex::decode(&u.element).map_err(|e| e.to_string())?)?,
+            accumulator: zkp::accumulator::Accumulator::from_bytes(&hex::decode(&u.value).map_err(|e| e.to_string())?)?
+        })
+    }).collect::<Result<Vec<zkp::accumulator::AccumulatorUpdate>, String>>()?;
+
+    let element = revocation_element(index);
+
+    if updates.iter().any(|u| u.action == zkp::accumulator::UpdateAction::Remove && u.element == element) {
+        return Ok(None);
+    }
+
+    let accumulator = accumulator_value_at(accumulator_file_data, epoch)?;
+
+    Ok(Some(NonRevocationWitness {
+        witness: witness.update(&element, &accumulator, &updates)?,
+        accumulator: zkp::accumulator::Accumulator::from_bytes(&hex::decode(&accumulator_file_data.value).map_err(|e| e.to_string())?)?,
+        epoch: accumulator_file_data.epoch
+    }))
+}
+
+#[tauri::command]
+fn update_accumulator_witness(surname: &str, name: &str) -> Result<(), String> {
+    let mut accounts_file_data = read_accounts_file();
+    let accumulator_file_data = read_accumulator_file()?;
+
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    for wallet in account.walletArray.iter_mut().filter(|w| w.accumulatorWitness.is_some()) {
+        let Some(witness) = updated_witness(wallet, &accumulator_file_data)? else {
+            continue;
+        };
+
+        wallet.accumulatorWitness = Some(hex::encode(witness.witness.to_bytes()));
+        wallet.accumulatorEpoch = Some(witness.epoch);
+    }
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to store accumulator witness".to_string());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+fn fetch_accumulator() -> Result<AccumulatorPublicData, String> {
+    let accumulator_file_data = read_accumulator_file()?;
+
+    Ok(AccumulatorPublicData {
+        epoch: accumulator_file_data.epoch,
+        value: accumulator_file_data.value,
+        publicKey: hex::encode(accumulator_secret_key()?.public_key().to_bytes())
+    })
+}
+
+// None until the wallet has published an accumulator, read_accumulator_file would start an empty one.
+fn load_accumulator_state() -> Option<AccumulatorState> {
+    if !Path::new(ACCUMULATOR_FILE_PATH).exists() {
+        return None;
+    }
+
+    let accumulator = fetch_accumulator().ok()?;
+
+    Some(AccumulatorState {
+        public_key: zkp::signature::PublicKey::from_bytes(&hex::decode(accumulator.publicKey).ok()?).ok()?,
+        accumulator: zkp::accumulator::Accumulator::from_bytes(&hex::decode(accumulator.value).ok()?).ok()?,
+        epoch: accumulator.epoch
+    })
+}
+
+fn history_log(sessions: &Sessions, surname: &str, name: &str) -> Result<HistoryLog, String> {
+    let account_id = account_id(surname, name);
+    let data_key = sessions.0.lock().unwrap().get(&account_id).cloned().ok_or("Log in again to unlock the presentation history")?;
+
+    Ok(HistoryLog::new(Path::new(HISTORY_DIR_PATH), &account_id, data_key))
+}
+
+// Cards have no identifier of their own, a digest of the signature names one without revealing it.
+fn history_entry(channel: &str, verifier_id: Option<&str>, signature_hex: &str, messages: &[String], disclosed_indices: &[usize], predicates: &[Predicate]) -> HistoryEntry {
+    let disclosed: BTreeMap<String, String> = disclosed_indices.iter()
+        .filter_map(|&i| Some((oid4vci::CLAIM_NAMES.get(i)?.to_string(), messages.get(i)?.clone())))
+        .collect();
+
+    HistoryEntry {
+        timestamp: SystemClock.now(),
+        verifier_id: verifier_id.map(|id| id.to_string()),
+        credential_id: sha256(&signature_hex.to_lowercase())[..16].to_string(),
+        channel: channel.to_string(),
+        disclosed_attributes: disclosed.keys().cloned().collect(),
+        disclosed_values: Some(disclosed),
+        predicates: predicates.to_vec()
+    }
+}
+
+// Every generated proof is logged before it leaves the wallet, values only if the holder opted in.
+fn record_presentation(sessions: &Sessions, surname: &str, name: &str, mut entry: HistoryEntry) -> Result<(), String> {
+    let record_values = read_accounts_file().accountsArray.iter().any(|acc| acc.surname == surname && acc.name == name && acc.historyValues);
+
+    if !record_values {
+        entry.disclosed_values = None;
+    }
+
+    let channel = entry.channel.clone();
+    let credential_id = entry.credential_id.clone();
+
+    history_log(sessions, surname, name)?.append(&entry)?;
+
+    record_audit_event(sessions, surname, name, AuditEvent::ProofPresented { channel, credential_id })
+}
+
+#[tauri::command]
+fn create_presentation(surname: &str, name: &str, signatureHex: String, publicKeyHex: String, proverBlindHex: String, messagesArray: Vec<String>, indicesArray: Vec<usize>, verifierId: Option<String>, predicates: Vec<Predicate>, nonRevocation: bool, sessions: tauri::State<Sessions>) -> Result<Presentation, String> {
+    let holder_secret = load_holder_secret(&sessions, surname, name)?;
+
+    let non_revocation = if nonRevocation {
+        let status_list_index: usize = messagesArray.get(presentation::STATUS_INDEX).and_then(|m| m.parse().ok()).ok_or("Card has no revocation identifier")?;
+
+        let accounts_file_data = read_accounts_file();
+        let wallet = accounts_file_data.accountsArray.iter()
+            .find(|acc| acc.surname == surname && acc.name == name)
+            .and_then(|acc| acc.walletArray.iter().find(|w| w.statusListIndex == Some(status_list_index)))
+            .ok_or("Card not found")?;
+
+        Some(updated_witness(wallet, &read_accumulator_file()?)?.ok_or("Credential has been revoked")?)
+    } else {
+        None
+    };
+
+    let verification_method = card_verification_method(surname, name, &publicKeyHex)?;
+    let public_key = zkp::signature::PublicKey::from_bytes(&hex::decode(publicKeyHex).map_err(|e| e.to_string())?)?;
+    let signature = zkp::signature::Signature::from_bytes(&hex::decode(&signatureHex).map_err(|e| e.to_string())?)?;
+    let prover_blind = zkp::scalar_from_bytes(&hex::decode(proverBlindHex).map_err(|e| e.to_string())?)?;
+
+    let presentation = presentation::create_presentation(&BoundCredential {
+        public_key,
+        signature,
+        messages: &messagesArray,
+        holder_secret,
+        prover_blind,
+        verification_method: Some(verification_method)
+    }, &indicesArray, SIGNATURE_PRESENTATION_HEADER, verifierId.as_deref(), &predicates, non_revocation.as_ref())?;
+
+    record_presentation(&sessions, surname, name, history_entry(history::LOCAL_CHANNEL, verifierId.as_deref(), &signatureHex, &messagesArray, &indicesArray, &predicates))?;
+
+    Ok(presentation)
+}
+
+#[derive(Serialize, Deserialize)]
+struct QrCodeImage {
+    payload: String,
+    svg: String
+}
+
+#[tauri::command]
+fn presentation_qr_code(presentation: Presentation) -> Result<QrCodeImage, String> {
+    let payload = qr::encode_presentation(&presentation, None)?;
+    let svg = qr::render_svg(&payload)?;
+
+    Ok(QrCodeImage { payload, svg })
+}
+
+#[derive(Serialize, Deserialize)]
+struct History {
+    entries: Vec<HistoryEntry>,
+    recordValues: bool
+}
+
+#[tauri::command]
+fn fetch_history(surname: &str, name: &str, sessions: tauri::State<Sessions>) -> Result<History, String> {
+    let recordValues = read_accounts_file().accountsArray.iter().any(|acc| acc.surname == surname && acc.name == name && acc.historyValues);
+
+    Ok(History {
+        entries: history_log(&sessions, surname, name)?.entries()?,
+        recordValues
+    })
+}
+
+#[tauri::command]
+fn set_history_values(surname: &str, name: &str, recordValues: bool, sessions: tauri::State<Sessions>) -> Result<(), String> {
+    if !sessions.0.lock().unwrap().contains_key(&account_id(surname, name)) {
+        return Err("Log in again to change the history settings".to_string());
+    }
+
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    account.historyValues = recordValues;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to save the history settings".to_string());
+    }
+
+    Ok(())
+}
+
+// Decrypted copy of the history for an auditor, returns the number of exported entries.
+#[tauri::command]
+fn export_history(surname: &str, name: &str, exportPath: String, sessions: tauri::State<Sessions>) -> Result<usize, String> {
+    let entries = history_log(&sessions, surname, name)?.entries()?;
+
+    fs::write(&exportPath, serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?).map_err(|e| format!("{}: {}", exportPath, e))?;
+
+    Ok(entries.len())
+}
+
+#[tauri::command]
+async fn receive_credential(surname: String, name: String, credentialOffer: String, txCode: Option<String>, sessions: tauri::State<'_, Sessions>) -> Result<(), String> {
+    let holder_secret = load_holder_secret(&sessions, &surname, &name)?;
+
+    let client = reqwest::Client::new();
+    let offer = oid4vci::resolve_credential_offer(&client, &credentialOffer).await?;
+    let received = oid4vci::receive_credential(&client, &offer, txCode.as_deref().filter(|code| !code.is_empty()), holder_secret.expose()).await?;
+
+    if received.claims.surname != surname || received.claims.name != name {
+        return Err("Credential subject does not match the account".to_string());
+    }
+
+    let mut accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter_mut().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    let card_index = account.walletArray.len();
+    let issuer = received.issuer.clone();
+
+    account.walletArray.push(Wallet {
+        country: received.claims.country,
+        sex: received.claims.sex,
+        dateOfBirth: received.claims.date_of_birth,
+        placeOfBirth: received.claims.place_of_birth,
+        documentNo: received.claims.document_no,
+        expiryDate: received.claims.expiry_date,
+        ciphersuite: Ciphersuite::WalletBbs,
+        statusListIndex: received.claims.status_list_index,
+        accumulatorWitness: None,
+        accumulatorEpoch: None,
+        issuedCredential: Some(IssuedCredential {
+            issuer: received.issuer,
+            signature: received.signature,
+            publicKey: received.public_key,
+            proverBlind: received.prover_blind,
+            verificationMethod: received.verification_method
+        })
+    });
+
+    append_audit_event(&sessions, account, AuditEvent::CardImported { card_index, issuer })?;
+
+    if !write_accounts_file(&accounts_file_data) {
+        return Err("Failed to store credential".to_string());
+    }
+
+    Ok(())
+}
+
+fn card_messages(surname: &str, name: &str, wallet: &Wallet) -> Vec<String> {
+    vec![
+        surname.to_string(),
+        name.to_string(),
+        wallet.country.clone(),
+        wallet.sex.clone(),
+        wallet.dateOfBirth.clone(),
+        wallet.placeOfBirth.clone(),
+        wallet.documentNo.clone(),
+        wallet.expiryDate.clone()
+    ].into_iter().chain(wallet.statusListIndex.map(|index| index.to_string())).collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct PendingAuthorization {
+    authorizationRequest: String,
+    verifierId: String,
+    matchingCards: Vec<usize>,
+    requiredIndices: Vec<usize>,
+    optionalIndices: Vec<usize>,
+    requestedFields: Vec<String>
+}
+
+#[tauri::command]
+async fn load_authorization_request(surname: String, name: String, authorizationRequest: String) -> Result<PendingAuthorization, String> {
+    let client = reqwest::Client::new();
+    let request = oid4vp::resolve_authorization_request(&client, &authorizationRequest).await?;
+
+    let accounts_file_data = read_accounts_file();
+    let account = accounts_file_data.accountsArray.iter().find(|acc| acc.surname == surname && acc.name == name).ok_or("Account not found")?;
+
+    let matches: Vec<(usize, Result<oid4vp::RequestedCredential, String>)> = account.walletArray.iter().enumerate()
+        .map(|(i, wallet)| (i, oid4vp::match_credential(&request, &card_messages(&surname, &name, wallet), &SystemClock)))
+        .collect();
+
+    let matchingCards: Vec<usize> = matches.iter().filter(|(_, m)| m.is_ok()).map(|(i, _)| *i).collect();
+
+    let requested = match matches.into_iter().map(|(_, m)| m).reduce(|first, other| first.or(other)) {
+        Some(requested) => requested?,
+        None => return Err("No card in the wallet".to_string())
+    };
+
+    Ok(PendingAuthorization {
+        authorizationRequest: serde_json::to_string(&request).map_err(|e| e.to_string())?,
+        verifierId: request.client_id,
+        matchingCards,
+        requestedFields: requested.required.iter().map(|i| oid4vci::CLAIM_NAMES[*i].to_string()).chain(requested.predicates.iter().map(|p| p.to_string())).collect(),
+        requiredIndices: requested.required,
+        optionalIndices: requested.optional
+    })
+}
+
+// Reads a verifier QR code from a screenshot, for desktops without a camera. The request is then loaded like a pasted link.
+#[tauri::command]
+fn scan_qr_code_image(imagePath: String) -> Result<String, String> {
+    qr::scanned_request(&qr::decode_image(Path::new(&imagePath))?)
+}
+
+#[tauri::command]
+async fn respond_authorization_request(surname: String, name: String, authorizationRequest: String, signatureHex: String, publicKeyHex: String, proverBlindHex: String, messagesArray: Vec<String>, indicesArray: Vec<usize>, sessions: tauri::State<'_, Sessions>) -> Result<Option<String>, String> {
+    let request: oid4vp::AuthorizationRequest = serde_json::from_str(&authorizationRequest).map_err(|e| e.to_string())?;
+    let requested = oid4vp::match_credential(&request, &messagesArray, &SystemClock)?;
+
+    let holder_secret = load_holder_secret(&sessions, &surname, &name)?;
+
+    let verification_method = card_verification_method(&surname, &name, &publicKeyHex)?;
+    let public_key = zkp::signature::PublicKey::from_bytes(&hex::decode(publicKeyHex).map_err(|e| e.to_string())?)?;
+    let signature = zkp::signature::Signature::from_bytes(&hex::decode(&signatureHex).map_err(|e| e.to_string())?)?;
+    let prover_blind = zkp::scalar_from_bytes(&hex::decode(proverBlindHex).map_err(|e| e.to_string())?)?;
+
+    let response = oid4vp::create_response(&request, &requested, &BoundCredential {
+        public_key,
+        signature,
+        messages: &messagesArray,
+        holder_secret,
+        prover_blind,
+        verification_method: Some(verification_method)
+    }, &indicesArray)?;
+
+    record_presentation(&sessions, &surname, &name, history_entry(history::DIRECT_POST_CHANNEL, Some(&request.client_id), &signatureHex, &messagesArray, &indicesArray, &requested.predicates))?;
+
+    let result = oid4vp::post_response(&reqwest::Client::new(), &request, &response).await?;
+
+    Ok(result.redirect_uri)
+}
+
+// Same presentation as `respond_authorization_request`, shown as a QR code for the verifier to scan instead of posted to it.
+#[tauri::command]
+fn authorization_qr_code(surname: String, name: String, authorizationRequest: String, signatureHex: String, publicKeyHex: String, proverBlindHex: String, messagesArray: Vec<String>, indicesArray: Vec<usize>, sessions: tauri::State<Sessions>) -> Result<QrCodeImage, String> {
+    let request: oid4vp::AuthorizationRequest = serde_json::from_str(&authorizationRequest).map_err(|e| e.to_string())?;
+    let requested = oid4vp::match_credential(&request, &messagesArray, &SystemClock)?;
+
+    let holder_secret = load_holder_secret(&sessions, &surname, &name)?;
+
+    let verification_method = card_verification_method(&surname, &name, &publicKeyHex)?;
+    let public_key = zkp::signature::PublicKey::from_bytes(&hex::decode(publicKeyHex).map_err(|e| e.to_string())?)?;
+    let signature = zkp::signature::Signature::from_bytes(&hex::decode(&signatureHex).map_err(|e| e.to_string())?)?;
+    let prover_blind = zkp::scalar_from_bytes(&hex::decode(proverBlindHex).map_err(|e| e.to_string())?)?;
+
+    let presentation = oid4vp::create_request_presentation(&request, &requested, &BoundCredential {
+        public_key,
+        signature,
+        messages: &messagesArray,
+        holder_secret,
+        prover_blind,
+        verification_method: Some(verification_method)
+    }, &indicesArray)?;
+
+    let payload = qr::encode_presentation(&presentation, request.state.as_deref())?;
+    let svg = qr::render_svg(&payload)?;
+
+    record_presentation(&sessions, &surname, &name, history_entry(history::QR_CODE_CHANNEL, Some(&request.client_id), &signatureHex, &messagesArray, &indicesArray, &requested.predicates))?;
+
+    Ok(QrCodeImage { payload, svg })
+}
+
+#[tauri::command]
+async fn verify_presentation(presentation: Presentation, verifierId: Option<String>, predicates: Vec<Predicate>, statusListFile: Option<String>) -> PresentationResult {
+    let status_lists = load_status_lists(statusListFile);
+
+    let published = load_accumulator_state();
+    let accumulator = presentation::enforced_accumulator(&presentation, status_lists.as_deref(), published.as_ref());
+
+    let mut result = presentation::verify_presentation(&presentation, SIGNATURE_PRESENTATION_HEADER, verifierId.as_deref(), &predicates, status_lists.as_deref(), accumulator);
+    presentation::check_presentation_validity(&mut result, &SystemClock);
+    presentation::check_presentation_issuer(&mut result, &presentation, &read_trust_registry(), &SystemClock);
+
+    if presentation::check_verification_method(&presentation, &did_resolver()).await.is_err() {
+        result.verified = false;
+    }
+
+    result
+}
+
+// Link the wallet was opened with, kept until the frontend takes it as the user may first have to log in.
+#[derive(Default)]
+struct PendingDeepLink(Mutex<Option<DeepLink>>);
+
+fn open_deep_link(app: &tauri::AppHandle, uri: &str) {
+    use tauri::{Emitter, Manager};
+
+    match deep_link::parse_deep_link(uri) {
+        Ok(link) => {
+            *app.state::<PendingDeepLink>().0.lock().unwrap() = Some(link.clone());
+            let _ = app.emit(deep_link::DEEP_LINK_EVENT, link);
+        },
+        // Links carry pre-authorized codes and request data, and errors may quote them: only the scheme is logged.
+        Err(_) => eprintln!("Ignoring a malformed or unsupported {} link", uri.trim().split_once(':').map_or("unknown", |(scheme, _)| scheme))
+    }
+}
+
+#[tauri::command]
+fn take_deep_link(pending: tauri::State<PendingDeepLink>) -> Option<DeepLink> {
+    pending.0.lock().unwrap().take()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     check_data_file().expect("Failed to check or create data file");
 
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+
+    // A link clicked while the wallet is running starts a second instance, which hands the link over to the first one.
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}));
+    }
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(PendingDeepLink::default())
+        .manage(Sessions::default())
+        .manage(PendingRecoveryKeys::default())
+        .setup(|app| {
+            use tauri_plugin_deep_link::DeepLinkExt;
+
+            // Installers register the schemes on Windows and Linux, this covers development builds.
+            #[cfg(any(windows, target_os = "linux"))]
+            app.deep_link().register_all()?;
+
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    open_deep_link(&handle, url.as_str());
+                }
+            });
+
+            for url in app.deep_link().get_current()?.unwrap_or_default() {
+                open_deep_link(app.handle(), url.as_str());
+            }
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             login_account,
+            logout_account,
             create_account,
             create_card,
             fetch_wallet_data,
             create_signature,
             verify_signature,
-            verify_signature_indices
+            verify_signature_indices,
+            create_bound_signature,
+            verify_bound_signature,
+            create_presentation,
+            presentation_qr_code,
+            verify_presentation,
+            revoke_card,
+            suspend_card,
+            export_status_lists,
+            update_accumulator_witness,
+            fetch_accumulator,
+            receive_credential,
+            load_authorization_request,
+            scan_qr_code_image,
+            take_deep_link,
+            respond_authorization_request,
+            authorization_qr_code,
+            fetch_trusted_issuers,
+            add_trusted_issuer,
+            remove_trusted_issuer,
+            fetch_history,
+            set_history_values,
+            export_history,
+            verify_audit_log,
+            change_password,
+            create_recovery_phrase,
+            confirm_recovery_phrase,
+            recover_account,
+            password_strength,
+            delete_card,
+            export_public_key,
+            import_public_key
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::validity::Clock;
+
+// Failures allowed before any delay.
+pub const FREE_ATTEMPTS: u32 = 3;
+// Delay after the first delayed failure, doubled by each following one.
+pub const BASE_DELAY_SECONDS: i64 = 2;
+pub const MAX_DELAY_SECONDS: i64 = 300;
+// Failures in a row that lock the account.
+pub const LOCKOUT_ATTEMPTS: u32 = 10;
+pub const LOCKOUT_MINUTES: i64 = 15;
+
+// Failed logins of an account, stored with it so that restarting the wallet does not reset them.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct LoginAttempts {
+    pub failed_attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_until: Option<DateTime<Utc>>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoginError {
+    WrongCredentials { remaining_attempts: Option<u32> },
+    Locked { until: DateTime<Utc>, retry_after_seconds: i64 },
+    Failed { error: String }
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoginError::WrongCredentials { .. } => write!(f, "Incorrect username or password"),
+            LoginError::Locked { until, .. } => write!(f, "Too many failed attempts, locked until {}", until.format("%Y-%m-%d %H:%M:%S UTC")),
+            LoginError::Failed { error } => write!(f, "{}", error)
+        }
+    }
+}
+
+impl LoginAttempts {
+    pub fn is_default(&self) -> bool {
+        *self == LoginAttempts::default()
+    }
+
+    // Checked before the password, a locked account refuses even the right one.
+    pub fn check(&self, clock: &dyn Clock) -> Result<(), LoginError> {
+        let now = clock.now();
+
+        match self.locked_until {
+            Some(until) if until > now => Err(LoginError::Locked { until, retry_after_seconds: (until - now).num_seconds().max(1) }),
+            _ => Ok(())
+        }
+    }
+
+    pub fn record_failure(&mut self, clock: &dyn Clock) -> LoginError {
+        let now = clock.now();
+
+        self.failed_attempts += 1;
+
+        if self.failed_attempts >= LOCKOUT_ATTEMPTS {
+            let until = now + Duration::minutes(LOCKOUT_MINUTES);
+
+            self.failed_attempts = 0;
+            self.locked_until = Some(until);
+
+            return LoginError::Locked { until, retry_after_seconds: LOCKOUT_MINUTES * 60 };
+        }
+
+        if self.failed_attempts >= FREE_ATTEMPTS {
+            let delay = BASE_DELAY_SECONDS.saturating_mul(1 << (self.failed_attempts - FREE_ATTEMPTS).min(16)).min(MAX_DELAY_SECONDS);
+            let until = now + Duration::seconds(delay);
+
+            self.locked_until = Some(until);
+
+            return LoginError::Locked { until, retry_after_seconds: delay };
+        }
+
+        self.locked_until = None;
+
+        LoginError::WrongCredentials { remaining_attempts: Some(FREE_ATTEMPTS - self.failed_attempts) }
+    }
+
+    pub fn record_success(&mut self) {
+        *self = LoginAttempts::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validity::FixedClock;
+    use chrono::TimeZone;
+
+    fn clock(seconds: i64) -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap() + Duration::seconds(seconds))
+    }
+
+    #[test]
+    fn failures_are_delayed_exponentially_then_locked_out() {
+        let mut attempts = LoginAttempts::default();
+
+        assert_eq!(attempts.record_failure(&clock(0)), LoginError::WrongCredentials { remaining_attempts: Some(2) });
+        assert_eq!(attempts.record_failure(&clock(0)), LoginError::WrongCredentials { remaining_attempts: Some(1) });
+        assert!(attempts.check(&clock(0)).is_ok());
+
+        // From the third failure on, each one doubles the wait.
+        let mut now = 0;
+        for delay in [2, 4, 8, 16, 32, 64, 128] {
+            assert_eq!(attempts.record_failure(&clock(now)), LoginError::Locked { until: clock(now + delay).0, retry_after_seconds: delay });
+            assert_eq!(attempts.check(&clock(now + delay - 1)), Err(LoginError::Locked { until: clock(now + delay).0, retry_after_seconds: 1 }));
+            assert!(attempts.check(&clock(now + delay)).is_ok());
+
+            now += delay;
+        }
+
+        assert_eq!(attempts.failed_attempts, LOCKOUT_ATTEMPTS - 1);
+
+        let until = clock(now + LOCKOUT_MINUTES * 60).0;
+        assert_eq!(attempts.record_failure(&clock(now)), LoginError::Locked { until, retry_after_seconds: LOCKOUT_MINUTES * 60 });
+        assert_eq!(attempts.check(&clock(now + 60)), Err(LoginError::Locked { until, retry_after_seconds: LOCKOUT_MINUTES * 60 - 60 }));
+        assert!(attempts.check(&clock(now + LOCKOUT_MINUTES * 60)).is_ok());
+
+        // The count restarts once the lockout is over.
+        assert_eq!(attempts.failed_attempts, 0);
+    }
+
+    #[test]
+    fn a_successful_login_resets_the_counter() {
+        let mut attempts = LoginAttempts::default();
+
+        for _ in 0..FREE_ATTEMPTS {
+            attempts.record_failure(&clock(0));
+        }
+
+        assert!(attempts.check(&clock(1)).is_err());
+
+        attempts.record_success();
+        assert!(attempts.is_default());
+        assert_eq!(attempts.record_failure(&clock(1)), LoginError::WrongCredentials { remaining_attempts: Some(FREE_ATTEMPTS - 1) });
+
+        assert_eq!(serde_json::to_value(LoginError::Locked { until: clock(0).0, retry_after_seconds: 2 }).unwrap(), serde_json::json!({"type": "locked", "until": "2026-10-19T12:00:00Z", "retry_after_seconds": 2}));
+    }
+}
@@ -0,0 +1,579 @@
+use std::collections::HashMap;
+
+use blstrs::Scalar;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use crate::did::DidResolver;
+use crate::zkp::{self, commitment::{self, HolderCommitment}, signature::{PublicKey, Signature}};
+use crate::SIGNATURE_HEADER;
+
+pub const PRE_AUTHORIZED_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:pre-authorized_code";
+pub const CREDENTIAL_FORMAT: &str = "wallet_bbs_blind";
+pub const PROOF_TYPE: &str = "bbs_holder_commitment";
+
+// Claim names of the card, at the index of the matching signed message.
+pub const CLAIM_NAMES: [&str; 9] = ["surname", "name", "country", "sex", "dateOfBirth", "placeOfBirth", "documentNo", "expiryDate", "statusListIndex"];
+
+const ISSUER_METADATA_PATH: &str = "/.well-known/openid-credential-issuer";
+const AUTHORIZATION_SERVER_METADATA_PATH: &str = "/.well-known/oauth-authorization-server";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TxCode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PreAuthorizedCodeGrant {
+    #[serde(rename = "pre-authorized_code")]
+    pub pre_authorized_code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_code: Option<TxCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_server: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Grants {
+    #[serde(rename = "urn:ietf:params:oauth:grant-type:pre-authorized_code", default, skip_serializing_if = "Option::is_none")]
+    pub pre_authorized_code: Option<PreAuthorizedCodeGrant>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CredentialOffer {
+    pub credential_issuer: String,
+    pub credential_configuration_ids: Vec<String>,
+    #[serde(default)]
+    pub grants: Grants
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub enum CredentialOfferReference {
+    Value(CredentialOffer),
+    Uri(String)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CredentialConfiguration {
+    pub format: String,
+    #[serde(default)]
+    pub proof_types_supported: HashMap<String, serde_json::Value>,
+    // Hex encoded BBS public key the issuer signs this configuration with, the holder commitment is bound to it.
+    pub public_key: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IssuerMetadata {
+    pub credential_issuer: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authorization_servers: Vec<String>,
+    pub credential_endpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_endpoint: Option<String>,
+    pub credential_configurations_supported: HashMap<String, CredentialConfiguration>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuthorizationServerMetadata {
+    pub issuer: String,
+    pub token_endpoint: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub c_nonce: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NonceResponse {
+    pub c_nonce: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Proofs {
+    pub bbs_holder_commitment: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CredentialRequest {
+    pub credential_configuration_id: String,
+    pub proofs: Proofs
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CardClaims {
+    pub surname: String,
+    pub name: String,
+    pub country: String,
+    pub sex: String,
+    pub date_of_birth: String,
+    pub place_of_birth: String,
+    pub document_no: String,
+    pub expiry_date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_list_index: Option<usize>
+}
+
+pub fn claim_index(name: &str) -> Option<usize> {
+    CLAIM_NAMES.iter().position(|claim| *claim == name)
+}
+
+impl CardClaims {
+    // Same message order as the card fields in the wallet UI.
+    pub fn messages(&self) -> Vec<String> {
+        vec![
+            self.surname.clone(),
+            self.name.clone(),
+            self.country.clone(),
+            self.sex.clone(),
+            self.date_of_birth.clone(),
+            self.place_of_birth.clone(),
+            self.document_no.clone(),
+            self.expiry_date.clone()
+        ].into_iter().chain(self.status_list_index.map(|index| index.to_string())).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BbsCredential {
+    pub issuer: String,
+    pub public_key: String,
+    // DID URL of the issuer key, `did:key` or `did:web`, resolved by the wallet before storing the card.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_method: Option<String>,
+    pub signature: String,
+    pub credential_subject: CardClaims
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IssuedCredential {
+    pub credential: BbsCredential
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CredentialResponse {
+    #[serde(default)]
+    pub credentials: Vec<IssuedCredential>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ErrorResponse {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>
+}
+
+#[derive(Clone, Debug)]
+pub struct ReceivedCredential {
+    pub issuer: String,
+    pub configuration_id: String,
+    pub claims: CardClaims,
+    pub signature: String,
+    pub public_key: String,
+    pub verification_method: Option<String>,
+    pub prover_blind: String
+}
+
+// Accepts the offer as raw JSON or as an `openid-credential-offer://` (or https) link carrying it by value or by reference.
+pub fn parse_credential_offer(input: &str) -> Result<CredentialOfferReference, String> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        return serde_json::from_str(input).map(CredentialOfferReference::Value).map_err(|e| e.to_string());
+    }
+
+    let url = Url::parse(input).map_err(|e| e.to_string())?;
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "credential_offer" => return serde_json::from_str(&value).map(CredentialOfferReference::Value).map_err(|e| e.to_string()),
+            "credential_offer_uri" => return Ok(CredentialOfferReference::Uri(value.into_owned())),
+            _ => {}
+        }
+    }
+
+    Err("Missing credential_offer or credential_offer_uri parameter".to_string())
+}
+
+pub(crate) async fn read_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, String> {
+    let status = response.status();
+
+    if !status.is_success() {
+        return Err(match response.json::<ErrorResponse>().await {
+            Ok(ErrorResponse { error, error_description: Some(description) }) => format!("{}: {}", error, description),
+            Ok(ErrorResponse { error, error_description: None }) => error,
+            Err(_) => format!("Request failed with status {}", status)
+        });
+    }
+
+    response.json().await.map_err(|e| e.to_string())
+}
+
+pub(crate) async fn get_json<T: DeserializeOwned>(client: &reqwest::Client, url: &str) -> Result<T, String> {
+    read_json(client.get(url).send().await.map_err(|e| e.to_string())?).await
+}
+
+fn well_known_url(base: &str, path: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
+
+pub async fn resolve_credential_offer(client: &reqwest::Client, input: &str) -> Result<CredentialOffer, String> {
+    match parse_credential_offer(input)? {
+        CredentialOfferReference::Value(offer) => Ok(offer),
+        CredentialOfferReference::Uri(uri) => get_json(client, &uri).await
+    }
+}
+
+pub async fn fetch_issuer_metadata(client: &reqwest::Client, credential_issuer: &str) -> Result<IssuerMetadata, String> {
+    let metadata: IssuerMetadata = get_json(client, &well_known_url(credential_issuer, ISSUER_METADATA_PATH)).await?;
+
+    if metadata.credential_issuer != credential_issuer {
+        return Err("Issuer metadata does not match the credential issuer".to_string());
+    }
+
+    Ok(metadata)
+}
+
+// The proof of possession nonce covers both the issuer challenge and its identifier, as the `aud` and `nonce` of a JWT proof would.
+pub fn proof_nonce(credential_issuer: &str, c_nonce: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for part in [credential_issuer, c_nonce] {
+        bytes.extend_from_slice(&(part.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(part.as_bytes());
+    }
+
+    bytes
+}
+
+pub async fn receive_credential(client: &reqwest::Client, offer: &CredentialOffer, tx_code: Option<&str>, holder_secret: &Scalar) -> Result<ReceivedCredential, String> {
+    let grant = offer.grants.pre_authorized_code.as_ref().ok_or("Only the pre-authorized code flow is supported")?;
+
+    if grant.tx_code.is_some() && tx_code.is_none() {
+        return Err("A transaction code is required for this offer".to_string());
+    }
+
+    let metadata = fetch_issuer_metadata(client, &offer.credential_issuer).await?;
+
+    let (configuration_id, configuration) = offer.credential_configuration_ids.iter()
+        .find_map(|id| metadata.credential_configurations_supported.get(id).map(|configuration| (id, configuration)))
+        .ok_or("Offered credential configuration is not supported by the issuer")?;
+
+    if configuration.format != CREDENTIAL_FORMAT || !configuration.proof_types_supported.contains_key(PROOF_TYPE) {
+        return Err(format!("Unsupported credential format {}", configuration.format));
+    }
+
+    let public_key = hex::decode(&configuration.public_key).map_err(|e| e.to_string()).and_then(|bytes| PublicKey::from_bytes(&bytes))?;
+
+    let authorization_server = grant.authorization_server.as_ref()
+        .or(metadata.authorization_servers.first())
+        .unwrap_or(&offer.credential_issuer);
+
+    let authorization_server_metadata: AuthorizationServerMetadata = get_json(client, &well_known_url(authorization_server, AUTHORIZATION_SERVER_METADATA_PATH)).await?;
+
+    let mut token_form = vec![("grant_type", PRE_AUTHORIZED_CODE_GRANT), ("pre-authorized_code", grant.pre_authorized_code.as_str())];
+
+    if let Some(tx_code) = tx_code {
+        token_form.push(("tx_code", tx_code));
+    }
+
+    let token: TokenResponse = read_json(client.post(&authorization_server_metadata.token_endpoint).form(&token_form).send().await.map_err(|e| e.to_string())?).await?;
+
+    let c_nonce = match &metadata.nonce_endpoint {
+        Some(nonce_endpoint) => read_json::<NonceResponse>(client.post(nonce_endpoint).send().await.map_err(|e| e.to_string())?).await?.c_nonce,
+        None => token.c_nonce.clone().ok_or("Issuer did not provide a c_nonce")?
+    };
+
+    let (holder_commitment, prover_blind) = commitment::commit_with_nonce(&public_key, SIGNATURE_HEADER, &proof_nonce(&metadata.credential_issuer, &c_nonce), holder_secret);
+
+    let request = CredentialRequest {
+        credential_configuration_id: configuration_id.clone(),
+        proofs: Proofs { bbs_holder_commitment: vec![hex::encode(holder_commitment.to_bytes())] }
+    };
+
+    let response: CredentialResponse = read_json(client.post(&metadata.credential_endpoint).bearer_auth(&token.access_token).json(&request).send().await.map_err(|e| e.to_string())?).await?;
+
+    let credential = response.credentials.into_iter().next().ok_or("Credential response is empty")?.credential;
+
+    if credential.public_key != configuration.public_key {
+        return Err("Credential is not signed with the advertised issuer key".to_string());
+    }
+
+    if let Some(verification_method) = &credential.verification_method {
+        let resolved = DidResolver::new(client.clone(), None).resolve_public_key(verification_method).await?;
+
+        if !resolved.eq_ignore_ascii_case(&credential.public_key) {
+            return Err(format!("Credential key does not match {}", verification_method));
+        }
+    }
+
+    let signature = hex::decode(&credential.signature).map_err(|e| e.to_string()).and_then(|bytes| Signature::from_bytes(&bytes))?;
+
+    if !zkp::signature::verify(&public_key, SIGNATURE_HEADER, &zkp::messages_to_scalars(&credential.credential_subject.messages()), &[*holder_secret, prover_blind], &signature) {
+        return Err("Issued credential signature is invalid".to_string());
+    }
+
+    Ok(ReceivedCredential {
+        issuer: metadata.credential_issuer,
+        configuration_id: configuration_id.clone(),
+        claims: credential.credential_subject,
+        signature: credential.signature,
+        public_key: credential.public_key,
+        verification_method: credential.verification_method,
+        prover_blind: hex::encode(prover_blind.to_bytes_be())
+    })
+}
+
+// Issuer side of the proof of possession, shared with the mock issuer used in tests.
+pub fn parse_holder_commitment(proofs: &Proofs) -> Result<HolderCommitment, String> {
+    let [proof] = proofs.bbs_holder_commitment.as_slice() else {
+        return Err("Exactly one proof is expected".to_string());
+    };
+
+    hex::decode(proof).map_err(|e| e.to_string()).and_then(|bytes| HolderCommitment::from_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use axum::{extract::State, http::{HeaderMap, StatusCode}, routing::{get, post}, Form, Json, Router};
+    use crate::did;
+    use crate::zkp::signature::SecretKey;
+
+    const CONFIGURATION_ID: &str = "IdentityCard";
+
+    struct MockIssuer {
+        url: String,
+        secret_key: SecretKey,
+        pre_authorized_code: String,
+        tx_code: Option<String>,
+        access_token: Option<String>,
+        c_nonce: Option<String>
+    }
+
+    type SharedIssuer = Arc<Mutex<MockIssuer>>;
+    type ErrorReply = (StatusCode, Json<ErrorResponse>);
+
+    fn error(error: &str) -> ErrorReply {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: error.to_string(), error_description: None }))
+    }
+
+    fn claims() -> CardClaims {
+        CardClaims {
+            surname: "Doe".to_string(),
+            name: "John".to_string(),
+            country: "FR".to_string(),
+            sex: "M".to_string(),
+            date_of_birth: "1990-01-01".to_string(),
+            place_of_birth: "Paris".to_string(),
+            document_no: "X1234567".to_string(),
+            expiry_date: "2030-01-01".to_string(),
+            status_list_index: Some(42)
+        }
+    }
+
+    fn offer(issuer: &MockIssuer) -> CredentialOffer {
+        CredentialOffer {
+            credential_issuer: issuer.url.clone(),
+            credential_configuration_ids: vec![CONFIGURATION_ID.to_string()],
+            grants: Grants {
+                pre_authorized_code: Some(PreAuthorizedCodeGrant {
+                    pre_authorized_code: issuer.pre_authorized_code.clone(),
+                    tx_code: issuer.tx_code.as_ref().map(|code| TxCode { input_mode: Some("numeric".to_string()), length: Some(code.len()), description: None }),
+                    authorization_server: None
+                })
+            }
+        }
+    }
+
+    async fn credential_offer(State(issuer): State<SharedIssuer>) -> Json<CredentialOffer> {
+        Json(offer(&issuer.lock().unwrap()))
+    }
+
+    async fn issuer_metadata(State(issuer): State<SharedIssuer>) -> Json<IssuerMetadata> {
+        let issuer = issuer.lock().unwrap();
+
+        Json(IssuerMetadata {
+            credential_issuer: issuer.url.clone(),
+            authorization_servers: vec![],
+            credential_endpoint: format!("{}/credential", issuer.url),
+            nonce_endpoint: Some(format!("{}/nonce", issuer.url)),
+            credential_configurations_supported: HashMap::from([(CONFIGURATION_ID.to_string(), CredentialConfiguration {
+                format: CREDENTIAL_FORMAT.to_string(),
+                proof_types_supported: HashMap::from([(PROOF_TYPE.to_string(), serde_json::json!({}))]),
+                public_key: hex::encode(issuer.secret_key.public_key().to_bytes())
+            })])
+        })
+    }
+
+    async fn authorization_server_metadata(State(issuer): State<SharedIssuer>) -> Json<AuthorizationServerMetadata> {
+        let issuer = issuer.lock().unwrap();
+
+        Json(AuthorizationServerMetadata { issuer: issuer.url.clone(), token_endpoint: format!("{}/token", issuer.url) })
+    }
+
+    async fn token(State(issuer): State<SharedIssuer>, Form(form): Form<HashMap<String, String>>) -> Result<Json<TokenResponse>, ErrorReply> {
+        let mut issuer = issuer.lock().unwrap();
+
+        if form.get("grant_type").map(String::as_str) != Some(PRE_AUTHORIZED_CODE_GRANT) || form.get("pre-authorized_code") != Some(&issuer.pre_authorized_code) {
+            return Err(error("invalid_grant"));
+        }
+
+        if issuer.tx_code.is_some() && form.get("tx_code") != issuer.tx_code.as_ref() {
+            return Err(error("invalid_grant"));
+        }
+
+        let access_token = hex::encode(zkp::random_scalar().to_bytes_be());
+        issuer.access_token = Some(access_token.clone());
+
+        Ok(Json(TokenResponse { access_token, token_type: "Bearer".to_string(), expires_in: Some(300), c_nonce: None }))
+    }
+
+    async fn nonce(State(issuer): State<SharedIssuer>) -> Json<NonceResponse> {
+        let c_nonce = hex::encode(zkp::random_scalar().to_bytes_be());
+        issuer.lock().unwrap().c_nonce = Some(c_nonce.clone());
+
+        Json(NonceResponse { c_nonce })
+    }
+
+    async fn credential(State(issuer): State<SharedIssuer>, headers: HeaderMap, Json(request): Json<CredentialRequest>) -> Result<Json<CredentialResponse>, ErrorReply> {
+        let mut issuer = issuer.lock().unwrap();
+
+        let bearer = headers.get("authorization").and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+
+        if bearer.is_none() || bearer != issuer.access_token.as_deref() {
+            return Err((StatusCode::UNAUTHORIZED, Json(ErrorResponse { error: "invalid_token".to_string(), error_description: None })));
+        }
+
+        if request.credential_configuration_id != CONFIGURATION_ID {
+            return Err(error("unknown_credential_configuration"));
+        }
+
+        let c_nonce = issuer.c_nonce.take().ok_or_else(|| error("invalid_nonce"))?;
+        let holder_commitment = parse_holder_commitment(&request.proofs).map_err(|_| error("invalid_proof"))?;
+
+        let public_key = issuer.secret_key.public_key();
+        let claims = claims();
+
+        let signature = commitment::blind_sign_with_nonce(&issuer.secret_key, &public_key, SIGNATURE_HEADER, &proof_nonce(&issuer.url, &c_nonce), &zkp::messages_to_scalars(&claims.messages()), &holder_commitment)
+            .map_err(|_| error("invalid_proof"))?;
+
+        Ok(Json(CredentialResponse {
+            credentials: vec![IssuedCredential {
+                credential: BbsCredential {
+                    issuer: issuer.url.clone(),
+                    public_key: hex::encode(public_key.to_bytes()),
+                    verification_method: Some(did::did_key_verification_method(&public_key.to_bytes())),
+                    signature: hex::encode(signature.to_bytes()),
+                    credential_subject: claims
+                }
+            }]
+        }))
+    }
+
+    async fn start_issuer(tx_code: Option<&str>) -> SharedIssuer {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let issuer = Arc::new(Mutex::new(MockIssuer {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            secret_key: zkp::testing::issuer_key(),
+            pre_authorized_code: "pre-authorized-code".to_string(),
+            tx_code: tx_code.map(str::to_string),
+            access_token: None,
+            c_nonce: None
+        }));
+
+        let app = Router::new()
+            .route("/offer", get(credential_offer))
+            .route(ISSUER_METADATA_PATH, get(issuer_metadata))
+            .route(AUTHORIZATION_SERVER_METADATA_PATH, get(authorization_server_metadata))
+            .route("/token", post(token))
+            .route("/nonce", post(nonce))
+            .route("/credential", post(credential))
+            .with_state(issuer.clone());
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        issuer
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    #[test]
+    fn credential_offer_is_parsed_by_value_and_by_reference() {
+        let by_value = "openid-credential-offer://?credential_offer=%7B%22credential_issuer%22%3A%22https%3A%2F%2Fissuer.example%22%2C%22credential_configuration_ids%22%3A%5B%22IdentityCard%22%5D%2C%22grants%22%3A%7B%22urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Apre-authorized_code%22%3A%7B%22pre-authorized_code%22%3A%22abc%22%7D%7D%7D";
+
+        let CredentialOfferReference::Value(offer) = parse_credential_offer(by_value).unwrap() else {
+            panic!("Offer should be passed by value");
+        };
+
+        assert_eq!(offer.credential_issuer, "https://issuer.example");
+        assert_eq!(offer.credential_configuration_ids, vec!["IdentityCard".to_string()]);
+        assert_eq!(offer.grants.pre_authorized_code.unwrap().pre_authorized_code, "abc");
+
+        assert_eq!(
+            parse_credential_offer("openid-credential-offer://?credential_offer_uri=https%3A%2F%2Fissuer.example%2Foffer%2F1"),
+            Ok(CredentialOfferReference::Uri("https://issuer.example/offer/1".to_string()))
+        );
+
+        assert!(parse_credential_offer("openid-credential-offer://?other=1").is_err());
+    }
+
+    #[tokio::test]
+    async fn credential_is_issued_through_pre_authorized_code_flow() {
+        let issuer = start_issuer(Some("493536")).await;
+        let url = issuer.lock().unwrap().url.clone();
+
+        let client = client();
+        let holder_secret = zkp::random_scalar();
+
+        let offer = resolve_credential_offer(&client, &format!("openid-credential-offer://?credential_offer_uri={}/offer", url)).await.unwrap();
+        let received = receive_credential(&client, &offer, Some("493536"), &holder_secret).await.unwrap();
+
+        assert_eq!(received.issuer, url);
+        assert_eq!(received.configuration_id, CONFIGURATION_ID);
+        assert_eq!(received.claims, claims());
+        assert_eq!(received.verification_method, Some(did::did_key_verification_method(&hex::decode(&received.public_key).unwrap())));
+
+        // The credential is bound to the holder secret and cannot be verified with another one.
+        let public_key = PublicKey::from_bytes(&hex::decode(&received.public_key).unwrap()).unwrap();
+        let signature = Signature::from_bytes(&hex::decode(&received.signature).unwrap()).unwrap();
+        let prover_blind = zkp::scalar_from_bytes(&hex::decode(&received.prover_blind).unwrap()).unwrap();
+        let messages = zkp::messages_to_scalars(&received.claims.messages());
+
+        assert!(zkp::signature::verify(&public_key, SIGNATURE_HEADER, &messages, &[holder_secret, prover_blind], &signature));
+        assert!(!zkp::signature::verify(&public_key, SIGNATURE_HEADER, &messages, &[zkp::random_scalar(), prover_blind], &signature));
+
+        // The c_nonce is single use.
+        assert!(issuer.lock().unwrap().c_nonce.is_none());
+    }
+
+    #[tokio::test]
+    async fn wrong_transaction_code_is_rejected() {
+        let issuer = start_issuer(Some("493536")).await;
+        let offer = offer(&issuer.lock().unwrap());
+
+        let client = client();
+        let holder_secret = zkp::random_scalar();
+
+        assert_eq!(receive_credential(&client, &offer, Some("000000"), &holder_secret).await.unwrap_err(), "invalid_grant");
+        assert!(receive_credential(&client, &offer, None, &holder_secret).await.is_err());
+    }
+}
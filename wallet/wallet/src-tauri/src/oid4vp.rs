@@ -0,0 +1,636 @@
+use chrono::{Datelike, Days};
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::oid4vci::{self, claim_index, CLAIM_NAMES, CREDENTIAL_FORMAT};
+use crate::presentation::{self, BoundCredential, Predicate, Presentation, COUNTRY_INDEX, DATE_OF_BIRTH_INDEX, EXPIRY_DATE_INDEX};
+use crate::presentation_exchange::{self, PresentationDefinition};
+use crate::validity::{self, Clock, DATE_FORMAT};
+
+pub const RESPONSE_TYPE: &str = "vp_token";
+pub const DIRECT_POST: &str = "direct_post";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ClaimsQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub path: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<Value>>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CredentialQuery {
+    pub id: String,
+    pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<Vec<ClaimsQuery>>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DcqlQuery {
+    pub credentials: Vec<CredentialQuery>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AuthorizationRequest {
+    pub client_id: String,
+    pub response_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_uri: Option<String>,
+    pub nonce: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_definition: Option<PresentationDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_definition_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dcql_query: Option<DcqlQuery>
+}
+
+#[derive(PartialEq, Debug)]
+pub enum AuthorizationRequestReference {
+    Value(Box<AuthorizationRequest>),
+    Uri(String)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RequestedCredential {
+    // Input descriptor id or DCQL credential query id the presentation answers.
+    pub id: String,
+    pub required: Vec<usize>,
+    pub optional: Vec<usize>,
+    // Proven rather than disclosed, for the fields a presentation definition asks as predicates.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predicates: Vec<Predicate>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct DescriptorMapEntry {
+    pub id: String,
+    pub format: String,
+    pub path: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PresentationSubmission {
+    pub id: String,
+    pub definition_id: String,
+    pub descriptor_map: Vec<DescriptorMapEntry>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuthorizationResponse {
+    pub vp_token: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_submission: Option<PresentationSubmission>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ResponseResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>
+}
+
+// Query parameters are strings, except the presentation definition and DCQL query which are JSON encoded.
+fn request_from_query<'a>(pairs: impl Iterator<Item = (std::borrow::Cow<'a, str>, std::borrow::Cow<'a, str>)>) -> Result<AuthorizationRequest, String> {
+    let mut object = Map::new();
+
+    for (key, value) in pairs {
+        let value = match key.as_ref() {
+            "presentation_definition" | "dcql_query" => serde_json::from_str(&value).map_err(|e| e.to_string())?,
+            _ => Value::String(value.into_owned())
+        };
+
+        object.insert(key.into_owned(), value);
+    }
+
+    serde_json::from_value(Value::Object(object)).map_err(|e| e.to_string())
+}
+
+fn request_from_object(object: &str) -> Result<AuthorizationRequest, String> {
+    if !object.trim_start().starts_with('{') {
+        return Err("Signed request objects are not supported".to_string());
+    }
+
+    serde_json::from_str(object).map_err(|e| e.to_string())
+}
+
+// Accepts an `openid4vp://` (or https) link carrying the request by value, as a `request` object or by `request_uri`.
+pub fn parse_authorization_request(input: &str) -> Result<AuthorizationRequestReference, String> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        return request_from_object(input).map(|request| AuthorizationRequestReference::Value(Box::new(request)));
+    }
+
+    let url = Url::parse(input).map_err(|e| e.to_string())?;
+
+    if let Some((_, request_uri)) = url.query_pairs().find(|(key, _)| key == "request_uri") {
+        return Ok(AuthorizationRequestReference::Uri(request_uri.into_owned()));
+    }
+
+    if let Some((_, request)) = url.query_pairs().find(|(key, _)| key == "request") {
+        return request_from_object(&request).map(|request| AuthorizationRequestReference::Value(Box::new(request)));
+    }
+
+    request_from_query(url.query_pairs()).map(|request| AuthorizationRequestReference::Value(Box::new(request)))
+}
+
+pub fn validate_request(request: &AuthorizationRequest) -> Result<(), String> {
+    if request.response_type != RESPONSE_TYPE {
+        return Err(format!("Unsupported response type {}", request.response_type));
+    }
+
+    let (Some(response_uri), DIRECT_POST) = (&request.response_uri, request.response_mode.as_deref().unwrap_or(DIRECT_POST)) else {
+        return Err("Only the direct_post response mode is supported".to_string());
+    };
+
+    // The consent screen, the pseudonym and the proof all name the client_id, so the response may only be posted back to its origin.
+    if !Url::parse(&request.client_id).ok().zip(Url::parse(response_uri).ok()).is_some_and(|(client_id, response_uri)| client_id.origin() == response_uri.origin()) {
+        return Err(format!("Response URI {} does not belong to {}", response_uri, request.client_id));
+    }
+
+    if request.presentation_definition.is_some() == request.dcql_query.is_some() {
+        return Err("Request must contain exactly one of presentation_definition or dcql_query".to_string());
+    }
+
+    Ok(())
+}
+
+pub async fn resolve_authorization_request(client: &reqwest::Client, input: &str) -> Result<AuthorizationRequest, String> {
+    let mut request = match parse_authorization_request(input)? {
+        AuthorizationRequestReference::Value(request) => *request,
+        AuthorizationRequestReference::Uri(uri) => {
+            let response = client.get(&uri).send().await.map_err(|e| e.to_string())?;
+
+            if !response.status().is_success() {
+                return Err(format!("Request object could not be fetched ({})", response.status()));
+            }
+
+            request_from_object(&response.text().await.map_err(|e| e.to_string())?)?
+        }
+    };
+
+    if let Some(uri) = request.presentation_definition_uri.take() {
+        request.presentation_definition = Some(oid4vci::get_json(client, &uri).await?);
+    }
+
+    validate_request(&request)?;
+
+    Ok(request)
+}
+
+fn claims_path_claim(path: &[Value]) -> Option<usize> {
+    match path {
+        [Value::String(subject), Value::String(name)] if subject == "credentialSubject" => claim_index(name),
+        [Value::String(vc), Value::String(subject), Value::String(name)] if vc == "vc" && subject == "credentialSubject" => claim_index(name),
+        [Value::String(name)] => claim_index(name),
+        _ => None
+    }
+}
+
+// The card as a JSON credential, for presentation definitions to be evaluated against.
+pub fn card_credential(messages: &[String]) -> Value {
+    let subject: Map<String, Value> = CLAIM_NAMES.iter().zip(messages).map(|(name, message)| (name.to_string(), Value::String(message.clone()))).collect();
+
+    serde_json::json!({
+        "type": ["VerifiableCredential", "IdentityCard"],
+        "credentialSubject": subject
+    })
+}
+
+// Born on or before `date_of_birth`, stated as the age reached on this year's anniversary.
+fn age_over(date_of_birth: &str, clock: &dyn Clock) -> Option<Predicate> {
+    let date_of_birth = validity::parse_date(date_of_birth).ok()?;
+    let years = clock.today().year() - date_of_birth.year();
+
+    Some(Predicate::AgeOver { years: u32::try_from(years).ok()?, date: format!("{:04}{}", date_of_birth.year() + years, date_of_birth.format("-%m-%d")) })
+}
+
+// A predicate field asks whether the claim passes the filter, which only the card predicates can answer without disclosing it.
+fn field_predicate(index: usize, filter: Option<&Value>, clock: &dyn Clock) -> Result<Predicate, String> {
+    let keyword = |name: &str| filter.and_then(|filter| filter.get(name));
+    let date = |name: &str| keyword(name).and_then(Value::as_str).and_then(|date| validity::parse_date(date).ok());
+
+    let predicate = match index {
+        DATE_OF_BIRTH_INDEX => keyword("formatMaximum").and_then(Value::as_str).and_then(|date| age_over(date, clock)),
+        // Not expired on a date means an expiry date after it.
+        EXPIRY_DATE_INDEX => date("formatExclusiveMinimum")
+            .or_else(|| date("formatMinimum").and_then(|date| date.checked_sub_days(Days::new(1))))
+            .map(|date| Predicate::NotExpired { date: date.format(DATE_FORMAT).to_string() }),
+        COUNTRY_INDEX => match (keyword("enum").and_then(Value::as_array), keyword("const").and_then(Value::as_str)) {
+            (Some(countries), _) => Some(Predicate::CountryIn { countries: countries.iter().filter_map(Value::as_str).map(String::from).collect() }),
+            (None, Some(country)) => Some(Predicate::CountryIn { countries: vec![country.to_string()] }),
+            (None, None) => None
+        },
+        _ => None
+    };
+
+    predicate.ok_or_else(|| format!("The verifier asks for a proof about {} that the wallet cannot make without disclosing it", CLAIM_NAMES[index]))
+}
+
+fn requested_from_definition(definition: &PresentationDefinition, messages: &[String], clock: &dyn Clock) -> Result<RequestedCredential, String> {
+    let matches = presentation_exchange::evaluate(definition, &[card_credential(messages)])?;
+
+    let [descriptor] = matches.as_slice() else {
+        return Err("Only requests for a single credential are supported".to_string());
+    };
+
+    let mut requested = RequestedCredential { id: descriptor.descriptor_id.clone(), required: vec![], optional: vec![], predicates: vec![] };
+
+    // Fields outside the signed claims, such as the credential type, are satisfied without being disclosed.
+    // Optional predicate fields are left out, the value was only asked about.
+    for field in &descriptor.fields {
+        match claims_path_claim(&field.path) {
+            Some(_) if field.predicate && field.optional => {},
+            Some(index) if field.predicate => requested.predicates.push(field_predicate(index, field.filter.as_ref(), clock)?),
+            Some(index) if field.optional => requested.optional.push(index),
+            Some(index) => requested.required.push(index),
+            None => {}
+        }
+    }
+
+    Ok(requested)
+}
+
+fn requested_from_query(query: &DcqlQuery, messages: &[String]) -> Result<RequestedCredential, String> {
+    let [credential] = query.credentials.as_slice() else {
+        return Err("Only requests for a single credential are supported".to_string());
+    };
+
+    if credential.format != CREDENTIAL_FORMAT {
+        return Err(format!("Unsupported credential format {}", credential.format));
+    }
+
+    let mut requested = RequestedCredential { id: credential.id.clone(), required: vec![], optional: vec![], predicates: vec![] };
+
+    for claim in credential.claims.iter().flatten() {
+        let index = claims_path_claim(&claim.path).ok_or_else(|| format!("Requested claim {} is not available", Value::Array(claim.path.clone())))?;
+
+        if let Some(values) = &claim.values {
+            let value = messages.get(index).ok_or("Requested claim is missing from the card")?;
+
+            if !values.iter().any(|expected| expected.as_str() == Some(value)) {
+                return Err(format!("Card does not match the requested {}", CLAIM_NAMES[index]));
+            }
+        }
+
+        requested.required.push(index);
+    }
+
+    Ok(requested)
+}
+
+// Computes what a card has to disclose to answer the request, or why it cannot.
+pub fn match_credential(request: &AuthorizationRequest, messages: &[String], clock: &dyn Clock) -> Result<RequestedCredential, String> {
+    let mut requested = match (&request.presentation_definition, &request.dcql_query) {
+        (Some(definition), None) => requested_from_definition(definition, messages, clock)?,
+        (None, Some(query)) => requested_from_query(query, messages)?,
+        _ => return Err("Request must contain exactly one of presentation_definition or dcql_query".to_string())
+    };
+
+    if let Some(index) = requested.required.iter().find(|index| **index >= messages.len()) {
+        return Err(format!("Card has no {}", CLAIM_NAMES[*index]));
+    }
+
+    // Verifiers reject a card whose expiry date is neither disclosed nor proven, and range proofs do not fit in a QR code.
+    if !requested.required.contains(&EXPIRY_DATE_INDEX) && !requested.predicates.iter().any(|predicate| matches!(predicate, Predicate::NotExpired { .. })) {
+        requested.optional.retain(|index| *index != EXPIRY_DATE_INDEX);
+        requested.required.push(EXPIRY_DATE_INDEX);
+    }
+
+    Ok(requested)
+}
+
+// The nonce and client identifier are bound into the proof, so a response cannot be replayed to another verifier or session.
+pub fn presentation_header(client_id: &str, nonce: &str) -> Vec<u8> {
+    oid4vci::proof_nonce(client_id, nonce)
+}
+
+// Presentation bound to the request nonce, also shown as a QR code when the verifier cannot be reached.
+pub fn create_request_presentation(request: &AuthorizationRequest, requested: &RequestedCredential, credential: &BoundCredential, disclosed_indices: &[usize]) -> Result<Presentation, String> {
+    if let Some(index) = requested.required.iter().find(|index| !disclosed_indices.contains(index)) {
+        return Err(format!("The verifier requires {}", CLAIM_NAMES[*index]));
+    }
+
+    presentation::create_presentation(credential, disclosed_indices, &presentation_header(&request.client_id, &request.nonce), Some(&request.client_id), &requested.predicates, None)
+}
+
+pub fn create_response(request: &AuthorizationRequest, requested: &RequestedCredential, credential: &BoundCredential, disclosed_indices: &[usize]) -> Result<AuthorizationResponse, String> {
+    let presentation = create_request_presentation(request, requested, credential, disclosed_indices)?;
+    let presentation = serde_json::to_value(presentation).map_err(|e| e.to_string())?;
+
+    Ok(match &request.presentation_definition {
+        Some(definition) => AuthorizationResponse {
+            vp_token: presentation,
+            presentation_submission: Some(PresentationSubmission {
+                id: hex::encode(crate::zkp::random_scalar().to_bytes_be()),
+                definition_id: definition.id.clone(),
+                descriptor_map: vec![DescriptorMapEntry { id: requested.id.clone(), format: CREDENTIAL_FORMAT.to_string(), path: "$".to_string() }]
+            }),
+            state: request.state.clone()
+        },
+        None => AuthorizationResponse {
+            vp_token: Value::Object(Map::from_iter([(requested.id.clone(), Value::Array(vec![presentation]))])),
+            presentation_submission: None,
+            state: request.state.clone()
+        }
+    })
+}
+
+pub async fn post_response(client: &reqwest::Client, request: &AuthorizationRequest, response: &AuthorizationResponse) -> Result<ResponseResult, String> {
+    let response_uri = request.response_uri.as_ref().ok_or("Request has no response_uri")?;
+
+    let mut form = vec![("vp_token", serde_json::to_string(&response.vp_token).map_err(|e| e.to_string())?)];
+
+    if let Some(submission) = &response.presentation_submission {
+        form.push(("presentation_submission", serde_json::to_string(submission).map_err(|e| e.to_string())?));
+    }
+
+    if let Some(state) = &response.state {
+        form.push(("state", state.clone()));
+    }
+
+    let reply = client.post(response_uri).form(&form).send().await.map_err(|e| e.to_string())?;
+
+    if !reply.status().is_success() {
+        return oid4vci::read_json(reply).await;
+    }
+
+    // The verifier may answer with an empty body when it has no redirect for the wallet.
+    Ok(serde_json::from_str(&reply.text().await.map_err(|e| e.to_string())?).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use axum::{extract::State, http::StatusCode, routing::{get, post}, Form, Json, Router};
+    use chrono::{TimeZone, Utc};
+    use crate::presentation::{Presentation, PresentationResult};
+    use crate::validity::FixedClock;
+    use crate::zkp::{self, testing::{self, card_messages}};
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())
+    }
+
+    struct StandInVerifier {
+        request: AuthorizationRequest,
+        result: Option<PresentationResult>
+    }
+
+    type SharedVerifier = Arc<Mutex<StandInVerifier>>;
+
+    fn definition() -> PresentationDefinition {
+        serde_json::from_value(serde_json::json!({
+            "id": "age-check",
+            "input_descriptors": [{
+                "id": "identity_card",
+                "constraints": {
+                    "limit_disclosure": "required",
+                    "fields": [
+                        { "path": ["$.credentialSubject.dateOfBirth"] },
+                        { "path": ["$['credentialSubject']['country']"] },
+                        { "path": ["$.credentialSubject.placeOfBirth"], "optional": true }
+                    ]
+                }
+            }]
+        })).unwrap()
+    }
+
+    fn query() -> DcqlQuery {
+        serde_json::from_value(serde_json::json!({
+            "credentials": [{
+                "id": "card",
+                "format": CREDENTIAL_FORMAT,
+                "claims": [
+                    { "path": ["credentialSubject", "surname"] },
+                    { "path": ["credentialSubject", "country"], "values": ["FR", "DE"] }
+                ]
+            }]
+        })).unwrap()
+    }
+
+    async fn request_object(State(verifier): State<SharedVerifier>) -> Json<AuthorizationRequest> {
+        Json(verifier.lock().unwrap().request.clone())
+    }
+
+    async fn direct_post(State(verifier): State<SharedVerifier>, Form(form): Form<HashMap<String, String>>) -> Result<Json<ResponseResult>, StatusCode> {
+        let mut verifier = verifier.lock().unwrap();
+        let request = verifier.request.clone();
+
+        if form.get("state") != request.state.as_ref() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let vp_token: Value = form.get("vp_token").and_then(|token| serde_json::from_str(token).ok()).ok_or(StatusCode::BAD_REQUEST)?;
+
+        let presentation = match &request.dcql_query {
+            Some(query) => vp_token.get(&query.credentials[0].id).and_then(|presentations| presentations.get(0)).cloned(),
+            None => {
+                let submission: PresentationSubmission = form.get("presentation_submission").and_then(|submission| serde_json::from_str(submission).ok()).ok_or(StatusCode::BAD_REQUEST)?;
+
+                (submission.definition_id == request.presentation_definition.as_ref().unwrap().id).then_some(vp_token)
+            }
+        };
+
+        let presentation: Presentation = presentation.and_then(|p| serde_json::from_value(p).ok()).ok_or(StatusCode::BAD_REQUEST)?;
+
+        verifier.result = Some(presentation::verify_presentation(&presentation, &presentation_header(&request.client_id, &request.nonce), Some(&request.client_id), &[], None, None));
+
+        Ok(Json(ResponseResult { redirect_uri: Some(format!("{}/done", request.client_id)) }))
+    }
+
+    async fn start_verifier(presentation_definition: Option<PresentationDefinition>, dcql_query: Option<DcqlQuery>) -> (String, SharedVerifier) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let verifier = Arc::new(Mutex::new(StandInVerifier {
+            request: AuthorizationRequest {
+                client_id: url.clone(),
+                response_type: RESPONSE_TYPE.to_string(),
+                response_mode: Some(DIRECT_POST.to_string()),
+                response_uri: Some(format!("{}/response", url)),
+                nonce: hex::encode(zkp::random_scalar().to_bytes_be()),
+                state: Some("session-1".to_string()),
+                presentation_definition,
+                presentation_definition_uri: None,
+                dcql_query
+            },
+            result: None
+        }));
+
+        let app = Router::new()
+            .route("/request", get(request_object))
+            .route("/response", post(direct_post))
+            .with_state(verifier.clone());
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (url, verifier)
+    }
+
+    async fn respond(url: &str, disclosed_indices: &[usize]) -> Result<ResponseResult, String> {
+        let messages = card_messages();
+        let credential = BoundCredential::issue_for_tests(&testing::issuer_key(), &messages);
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+
+        let request = resolve_authorization_request(&client, &format!("openid4vp://?client_id={}&request_uri={}/request", url, url)).await?;
+        let requested = match_credential(&request, &messages, &clock())?;
+
+        let response = create_response(&request, &requested, &credential, disclosed_indices)?;
+
+        post_response(&client, &request, &response).await
+    }
+
+    #[test]
+    fn authorization_request_is_parsed_by_value_and_by_reference() {
+        let by_value = format!(
+            "openid4vp://?client_id=https%3A%2F%2Fverifier.example&response_type=vp_token&response_mode=direct_post&response_uri=https%3A%2F%2Fverifier.example%2Fresponse&nonce=n-0S6&dcql_query={}",
+            url::form_urlencoded::byte_serialize(serde_json::to_string(&query()).unwrap().as_bytes()).collect::<String>()
+        );
+
+        let AuthorizationRequestReference::Value(request) = parse_authorization_request(&by_value).unwrap() else {
+            panic!("Request should be passed by value");
+        };
+
+        assert_eq!(request.client_id, "https://verifier.example");
+        assert_eq!(request.dcql_query, Some(query()));
+        assert_eq!(validate_request(&request), Ok(()));
+
+        // A relayed request must not get a proof for the verifier posted somewhere else.
+        let relayed = AuthorizationRequest { response_uri: Some("https://attacker.example/response".to_string()), ..*request.clone() };
+        assert_eq!(validate_request(&relayed), Err("Response URI https://attacker.example/response does not belong to https://verifier.example".to_string()));
+
+        let not_a_url = AuthorizationRequest { client_id: "verifier".to_string(), ..*request.clone() };
+        assert!(validate_request(&not_a_url).is_err());
+
+        assert_eq!(
+            parse_authorization_request("openid4vp://?client_id=x&request_uri=https%3A%2F%2Fverifier.example%2Frequest%2F1"),
+            Ok(AuthorizationRequestReference::Uri("https://verifier.example/request/1".to_string()))
+        );
+
+        assert!(parse_authorization_request("openid4vp://?client_id=x&request=eyJhbGciOiJFUzI1NiJ9.e30.c2ln").is_err());
+    }
+
+    #[test]
+    fn requested_fields_are_matched_against_the_card() {
+        let mut request = AuthorizationRequest {
+            client_id: "https://verifier.example".to_string(),
+            response_type: RESPONSE_TYPE.to_string(),
+            response_mode: None,
+            response_uri: Some("https://verifier.example/response".to_string()),
+            nonce: "n-0S6".to_string(),
+            state: None,
+            presentation_definition: Some(definition()),
+            presentation_definition_uri: None,
+            dcql_query: None
+        };
+
+        assert_eq!(match_credential(&request, &card_messages(), &clock()), Ok(RequestedCredential { id: "identity_card".to_string(), required: vec![4, 2, 7], optional: vec![5], predicates: vec![] }));
+
+        request.presentation_definition = None;
+        request.dcql_query = Some(query());
+
+        assert_eq!(match_credential(&request, &card_messages(), &clock()), Ok(RequestedCredential { id: "card".to_string(), required: vec![0, 2, 7], optional: vec![], predicates: vec![] }));
+
+        let mut other_country = card_messages();
+        other_country[2] = "US".to_string();
+
+        assert!(match_credential(&request, &other_country, &clock()).is_err());
+    }
+
+    #[test]
+    fn predicate_fields_are_proven_instead_of_disclosed() {
+        let definition = |fields: Value| -> PresentationDefinition {
+            serde_json::from_value(serde_json::json!({
+                "id": "age-check",
+                "input_descriptors": [{ "id": "identity_card", "constraints": { "limit_disclosure": "required", "fields": fields } }]
+            })).unwrap()
+        };
+
+        let mut request = AuthorizationRequest {
+            client_id: "https://verifier.example".to_string(),
+            response_type: RESPONSE_TYPE.to_string(),
+            response_mode: None,
+            response_uri: Some("https://verifier.example/response".to_string()),
+            nonce: "n-0S6".to_string(),
+            state: None,
+            presentation_definition: Some(definition(serde_json::json!([
+                { "path": ["$.credentialSubject.dateOfBirth"], "filter": { "type": "string", "format": "date", "formatMaximum": "2008-10-19" }, "predicate": "required" },
+                { "path": ["$.credentialSubject.expiryDate"], "filter": { "type": "string", "formatExclusiveMinimum": "2026-10-19" }, "predicate": "required" },
+                { "path": ["$.credentialSubject.country"], "filter": { "enum": ["FR", "DE"] }, "predicate": "preferred" },
+                { "path": ["$.credentialSubject.placeOfBirth"], "predicate": "required", "optional": true }
+            ]))),
+            presentation_definition_uri: None,
+            dcql_query: None
+        };
+
+        let requested = match_credential(&request, &card_messages(), &clock()).unwrap();
+
+        assert_eq!(requested.required, Vec::<usize>::new());
+        assert_eq!(requested.optional, Vec::<usize>::new());
+        assert_eq!(requested.predicates, vec![
+            Predicate::AgeOver { years: 18, date: "2026-10-19".to_string() },
+            Predicate::NotExpired { date: "2026-10-19".to_string() },
+            Predicate::CountryIn { countries: vec!["FR".to_string(), "DE".to_string()] }
+        ]);
+
+        let presentation = create_request_presentation(&request, &requested, &BoundCredential::issue_for_tests(&testing::issuer_key(), &card_messages()), &[]).unwrap();
+        let result = presentation::verify_presentation(&presentation, &presentation_header(&request.client_id, &request.nonce), Some(&request.client_id), &requested.predicates, None, None);
+
+        assert!(result.verified);
+        assert!(result.disclosed_messages.is_empty());
+
+        // The raw value is never disclosed for a predicate the wallet cannot prove.
+        request.presentation_definition = Some(definition(serde_json::json!([{ "path": ["$.credentialSubject.sex"], "filter": { "const": "M" }, "predicate": "required" }])));
+
+        assert_eq!(match_credential(&request, &card_messages(), &clock()), Err("The verifier asks for a proof about sex that the wallet cannot make without disclosing it".to_string()));
+    }
+
+    #[tokio::test]
+    async fn vp_token_is_posted_to_verifier_for_presentation_definition() {
+        let (url, verifier) = start_verifier(Some(definition()), None).await;
+
+        let result = respond(&url, &[2, 4, 7]).await.unwrap();
+        assert_eq!(result.redirect_uri, Some(format!("{}/done", url)));
+
+        let verified = verifier.lock().unwrap().result.take().unwrap();
+
+        assert!(verified.verified);
+        assert!(verified.pseudonym.is_some());
+        assert_eq!(verified.disclosed_messages.iter().map(|dm| dm.value.as_str()).collect::<Vec<&str>>(), vec!["FR", "1990-01-01", "2030-01-01"]);
+    }
+
+    #[tokio::test]
+    async fn vp_token_is_posted_to_verifier_for_dcql_query() {
+        let (url, verifier) = start_verifier(None, Some(query())).await;
+
+        respond(&url, &[0, 2, 7]).await.unwrap();
+
+        let verified = verifier.lock().unwrap().result.take().unwrap();
+
+        assert!(verified.verified);
+        assert_eq!(verified.disclosed_messages.iter().map(|dm| dm.value.as_str()).collect::<Vec<&str>>(), vec!["Doe", "FR", "2030-01-01"]);
+    }
+
+    #[tokio::test]
+    async fn holder_cannot_withhold_required_field() {
+        let (url, verifier) = start_verifier(None, Some(query())).await;
+
+        assert_eq!(respond(&url, &[0, 7]).await.unwrap_err(), "The verifier requires country");
+        assert!(verifier.lock().unwrap().result.is_none());
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::{Serialize, Deserialize};
+
+// Most used passwords first, the rank of a password is its line number.
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+pub const MIN_LENGTH: usize = 10;
+pub const MIN_SCORE: u8 = 3;
+
+// Characters past this length are not analysed, as in zxcvbn, so that the estimate stays fast on every keystroke.
+const MAX_ANALYSED_LENGTH: usize = 100;
+
+const KEYBOARD_ROWS: [&str; 8] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm", "azertyuiop", "qsdfghjklm", "wxcvbn", "qwertzuiop"];
+
+// Guesses below which a pattern is not worth telling apart, as in zxcvbn.
+const MIN_MATCH_GUESSES_LOG10: f64 = 1.0;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PasswordStrength {
+    // 0 (guessed in under a thousand tries) to 4 (more than ten billion).
+    pub score: u8,
+    pub guesses_log10: f64,
+    pub acceptable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Pattern {
+    Dictionary { rank: usize, exact: bool, substituted: bool },
+    UserInput,
+    Sequence,
+    Repeat,
+    Keyboard,
+    Date
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Match {
+    start: usize,
+    end: usize,
+    guesses_log10: f64,
+    pattern: Pattern
+}
+
+fn common_password_ranks() -> &'static HashMap<&'static str, usize> {
+    static RANKS: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+
+    RANKS.get_or_init(|| COMMON_PASSWORDS.lines().map(str::trim).filter(|line| !line.is_empty()).enumerate().map(|(i, line)| (line, i + 1)).collect())
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '@' | '4' => 'a',
+        '3' => 'e',
+        '1' | '!' => 'i',
+        '0' => 'o',
+        '$' | '5' => 's',
+        '7' => 't',
+        c => c
+    }
+}
+
+fn cardinality(c: char) -> f64 {
+    if c.is_ascii_digit() {
+        10.0
+    } else if c.is_ascii_alphabetic() {
+        26.0
+    } else {
+        33.0
+    }
+}
+
+// Only a capital first letter or all capitals barely add anything, other mixes count once per letter.
+fn uppercase_variations(chars: &[char]) -> f64 {
+    let upper = chars.iter().filter(|c| c.is_uppercase()).count();
+    let letters = chars.iter().filter(|c| c.is_alphabetic()).count();
+
+    if upper == 0 {
+        1.0
+    } else if upper == letters || (upper == 1 && chars[0].is_uppercase()) {
+        2.0
+    } else {
+        letters as f64
+    }
+}
+
+fn dictionary_matches(chars: &[char], ranks: &HashMap<&str, usize>, user_inputs: &[String], matches: &mut Vec<Match>) {
+    // One lowercase character per character, so that matches keep the positions of the password.
+    let lower: Vec<char> = chars.iter().map(|&c| c.to_lowercase().next().unwrap_or(c)).collect();
+
+    for start in 0..chars.len() {
+        for end in start + 3..=chars.len() {
+            let word: String = lower[start..end].iter().collect();
+            let unleeted: String = lower[start..end].iter().map(|&c| unleet(c)).collect();
+            let variations = uppercase_variations(&chars[start..end]);
+
+            if user_inputs.iter().any(|input| *input == word || *input == unleeted) {
+                matches.push(Match { start, end, guesses_log10: variations.log10(), pattern: Pattern::UserInput });
+            }
+
+            let exact = start == 0 && end == chars.len();
+
+            if let Some(&rank) = ranks.get(word.as_str()) {
+                matches.push(Match { start, end, guesses_log10: (rank as f64 * variations).log10(), pattern: Pattern::Dictionary { rank, exact, substituted: false } });
+            } else if let Some(&rank) = ranks.get(unleeted.as_str()) {
+                matches.push(Match { start, end, guesses_log10: (rank as f64 * variations * 2.0).log10(), pattern: Pattern::Dictionary { rank, exact, substituted: true } });
+            }
+        }
+    }
+}
+
+// Runs of three or more characters each one step from the previous, such as abc, 4321 or zyx.
+fn sequence_matches(chars: &[char], matches: &mut Vec<Match>) {
+    let mut start = 0;
+
+    while start + 2 < chars.len() {
+        let delta = chars[start + 1] as i32 - chars[start] as i32;
+        let mut end = start + 1;
+
+        if delta == 1 || delta == -1 {
+            while end < chars.len() && chars[end] as i32 - chars[end - 1] as i32 == delta {
+                end += 1;
+            }
+        }
+
+        if end - start >= 3 {
+            let base: f64 = if "aAzZ019".contains(chars[start]) {
+                4.0
+            } else if chars[start].is_ascii_digit() {
+                10.0
+            } else {
+                26.0
+            };
+            let direction = if delta < 0 { 2.0 } else { 1.0 };
+
+            matches.push(Match { start, end, guesses_log10: (base * (end - start) as f64 * direction).log10(), pattern: Pattern::Sequence });
+            start = end - 1;
+        } else {
+            start += 1;
+        }
+    }
+}
+
+fn repeat_matches(chars: &[char], matches: &mut Vec<Match>) {
+    let mut start = 0;
+
+    while start < chars.len() {
+        let end = start + chars[start..].iter().take_while(|&&c| c == chars[start]).count();
+
+        if end - start >= 3 {
+            matches.push(Match { start, end, guesses_log10: (cardinality(chars[start]) * (end - start) as f64).log10(), pattern: Pattern::Repeat });
+        }
+
+        start = end;
+    }
+}
+
+// Four or more adjacent keys of one row, in either direction.
+fn keyboard_matches(chars: &[char], matches: &mut Vec<Match>) {
+    let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let rows: Vec<Vec<char>> = KEYBOARD_ROWS.iter().flat_map(|row| [row.chars().collect(), row.chars().rev().collect()]).collect();
+
+    for start in 0..chars.len() {
+        let longest = (start + 4..=chars.len()).rev().find(|&end| rows.iter().any(|row| row.windows(end - start).any(|keys| keys == &lower[start..end])));
+
+        if let Some(end) = longest {
+            matches.push(Match { start, end, guesses_log10: (rows.len() as f64 * 10.0 * (end - start) as f64).log10(), pattern: Pattern::Keyboard });
+        }
+    }
+}
+
+fn is_year(digits: &str) -> bool {
+    digits.parse::<u32>().is_ok_and(|year| (1900..=2099).contains(&year))
+}
+
+fn is_day_month(day: &str, month: &str) -> bool {
+    matches!((day.parse::<u32>(), month.parse::<u32>()), (Ok(1..=31), Ok(1..=12)))
+}
+
+// Years, and birthdays written ddmmyy, ddmmyyyy or yyyymmdd.
+fn date_matches(chars: &[char], matches: &mut Vec<Match>) {
+    for start in 0..chars.len() {
+        for length in [4, 6, 8] {
+            let end = start + length;
+
+            if end > chars.len() || !chars[start..end].iter().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+
+            let digits: String = chars[start..end].iter().collect();
+
+            let guesses: f64 = match length {
+                4 if is_year(&digits) => 120.0,
+                6 if is_day_month(&digits[0..2], &digits[2..4]) => 365.0 * 100.0,
+                8 if is_day_month(&digits[0..2], &digits[2..4]) && is_year(&digits[4..8]) => 365.0 * 200.0,
+                8 if is_year(&digits[0..4]) && is_day_month(&digits[6..8], &digits[4..6]) => 365.0 * 200.0,
+                _ => continue
+            };
+
+            matches.push(Match { start, end, guesses_log10: guesses.log10(), pattern: Pattern::Date });
+        }
+    }
+}
+
+fn score(guesses_log10: f64) -> u8 {
+    match guesses_log10 {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4
+    }
+}
+
+// Estimates the guesses an attacker needs, the way zxcvbn does: the password is split into the cheapest sequence of known
+// patterns (common passwords, the account name, sequences, repeats, keyboard walks, dates), with unmatched characters guessed one by one.
+pub fn estimate_strength(password: &str, user_inputs: &[&str]) -> PasswordStrength {
+    let length = password.chars().count();
+    let chars: Vec<char> = password.chars().take(MAX_ANALYSED_LENGTH).collect();
+    let user_inputs: Vec<String> = user_inputs.iter().map(|input| input.trim().to_lowercase()).filter(|input| input.chars().count() >= 3).collect();
+
+    let mut matches = vec![];
+    dictionary_matches(&chars, common_password_ranks(), &user_inputs, &mut matches);
+    sequence_matches(&chars, &mut matches);
+    repeat_matches(&chars, &mut matches);
+    keyboard_matches(&chars, &mut matches);
+    date_matches(&chars, &mut matches);
+
+    // Cheapest guesses for the first k characters, and the pattern that ends there.
+    let mut best = vec![0.0_f64; chars.len() + 1];
+    let mut last: Vec<Option<Match>> = vec![None; chars.len() + 1];
+
+    for k in 1..=chars.len() {
+        best[k] = best[k - 1] + cardinality(chars[k - 1]).log10();
+        last[k] = None;
+
+        for m in matches.iter().filter(|m| m.end == k) {
+            let guesses = best[m.start] + m.guesses_log10.max(MIN_MATCH_GUESSES_LOG10);
+
+            if guesses < best[k] {
+                best[k] = guesses;
+                last[k] = Some(*m);
+            }
+        }
+    }
+
+    let mut patterns = vec![];
+    let mut k = chars.len();
+
+    while k > 0 {
+        match last[k] {
+            Some(m) => {
+                patterns.push(m.pattern);
+                k = m.start;
+            },
+            None => k -= 1
+        }
+    }
+
+    let guesses_log10 = best[chars.len()];
+    let score = score(guesses_log10);
+    let (warning, mut suggestions) = feedback(&patterns);
+
+    if length < MIN_LENGTH {
+        suggestions.insert(0, format!("Use at least {} characters.", MIN_LENGTH));
+    }
+
+    let exact_common = patterns.iter().any(|pattern| matches!(pattern, Pattern::Dictionary { exact: true, .. }));
+
+    PasswordStrength {
+        score,
+        guesses_log10,
+        acceptable: length >= MIN_LENGTH && score >= MIN_SCORE && !exact_common,
+        warning: warning.or_else(|| (score < MIN_SCORE).then(|| String::from("This password is easy to guess."))),
+        suggestions
+    }
+}
+
+fn feedback(patterns: &[Pattern]) -> (Option<String>, Vec<String>) {
+    let mut suggestions = vec![];
+
+    let warning = patterns.first().map(|pattern| match pattern {
+        Pattern::Dictionary { exact: true, rank, .. } if *rank <= 10 => "This is a top-10 common password.",
+        Pattern::Dictionary { exact: true, .. } => "This is a very common password.",
+        Pattern::Dictionary { .. } => "Contains a common password.",
+        Pattern::UserInput => "Contains your name.",
+        Pattern::Keyboard => "Keyboard patterns like qwerty are easy to guess.",
+        Pattern::Sequence => "Sequences like abc or 6543 are easy to guess.",
+        Pattern::Repeat => "Repeated characters like aaa are easy to guess.",
+        Pattern::Date => "Dates and years are easy to guess."
+    }).map(str::to_string);
+
+    if patterns.iter().any(|pattern| matches!(pattern, Pattern::Dictionary { substituted: true, .. })) {
+        suggestions.push(String::from("Predictable substitutions like @ instead of a don't help much."));
+    }
+
+    if patterns.contains(&Pattern::UserInput) {
+        suggestions.push(String::from("Avoid your name and surname."));
+    }
+
+    if patterns.contains(&Pattern::Date) {
+        suggestions.push(String::from("Avoid dates and years that are associated with you."));
+    }
+
+    if warning.is_some() {
+        suggestions.push(String::from("Add another word or two. Uncommon words are better."));
+    }
+
+    (warning, suggestions)
+}
+
+// Rules applied by the backend to every new password, whatever the UI checked.
+pub fn check_password_policy(password: &str, user_inputs: &[&str]) -> Result<(), String> {
+    let strength = estimate_strength(password, user_inputs);
+
+    if strength.acceptable {
+        return Ok(());
+    }
+
+    Err(std::iter::once(String::from("Password is too weak."))
+        .chain(strength.warning)
+        .chain(strength.suggestions)
+        .collect::<Vec<String>>()
+        .join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_and_patterned_passwords_are_rejected() {
+        let common = estimate_strength("password", &[]);
+        assert_eq!(common.score, 0);
+        assert_eq!(common.warning.as_deref(), Some("This is a top-10 common password."));
+
+        assert!(!estimate_strength("motdepasse123", &[]).acceptable);
+        assert!(estimate_strength("Dr@g0n", &[]).suggestions.contains(&String::from("Predictable substitutions like @ instead of a don't help much.")));
+        assert!(!estimate_strength("Azertyuiop", &[]).acceptable);
+
+        assert_eq!(estimate_strength("mnbvcxzlkjhgfd", &[]).warning.as_deref(), Some("Keyboard patterns like qwerty are easy to guess."));
+        assert_eq!(estimate_strength("abcdefghijklmnop", &[]).warning.as_deref(), Some("Sequences like abc or 6543 are easy to guess."));
+        assert_eq!(estimate_strength("zzzzzzzzzzzzzzz", &[]).warning.as_deref(), Some("Repeated characters like aaa are easy to guess."));
+        assert!(!estimate_strength("Summer2024!", &[]).acceptable);
+
+        let named = estimate_strength("Dupont14071989", &["Dupont", "Jean"]);
+        assert!(!named.acceptable);
+        assert!(named.suggestions.contains(&String::from("Avoid your name and surname.")));
+        assert!(named.suggestions.contains(&String::from("Avoid dates and years that are associated with you.")));
+
+        // Characters whose lowercase form is longer do not hide the rest of the password.
+        assert_eq!(estimate_strength("İpassword", &[]).warning.as_deref(), Some("Contains a common password."));
+        assert!(estimate_strength("İDupont-Dupont", &["Dupont"]).suggestions.contains(&String::from("Avoid your name and surname.")));
+
+        assert_eq!(check_password_policy("sunshine", &[]).unwrap_err(), "Password is too weak. This is a very common password. Use at least 10 characters. Add another word or two. Uncommon words are better.");
+    }
+
+    #[test]
+    fn long_unpredictable_passwords_are_accepted() {
+        let passphrase = estimate_strength("velours grenier tambour", &[]);
+        assert_eq!(passphrase.score, 4);
+        assert!(passphrase.acceptable);
+        assert_eq!(passphrase.warning, None);
+
+        assert!(check_password_policy("k7#Vq9!mT2xLp", &["Dupont", "Jean"]).is_ok());
+
+        // Too short, however random.
+        let short = estimate_strength("k7#Vq9!m", &[]);
+        assert!(!short.acceptable);
+        assert_eq!(short.suggestions[0], "Use at least 10 characters.");
+
+        // Strength grows with each unpredictable character.
+        let scores: Vec<u8> = ["a", "a7#V", "a7#Vq9!m", "a7#Vq9!mT2xL"].iter().map(|p| estimate_strength(p, &[]).score).collect();
+        assert!(scores.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(scores.last(), Some(&4));
+
+        // Only the start of a very long password is analysed.
+        let long = estimate_strength(&"velours grenier tambour ".repeat(1000), &[]);
+        assert_eq!(long.score, 4);
+        assert!(long.acceptable);
+    }
+}
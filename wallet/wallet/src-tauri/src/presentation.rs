@@ -0,0 +1,629 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+use blstrs::Scalar;
+
+use crate::zkp::{
+    self,
+    accumulator::{Accumulator, NonRevocationProof, NonRevocationProver, NonRevocationVerifier, Witness},
+    commitment::COMMITTED_MESSAGE_COUNT,
+    membership::{MembershipProof, MembershipProver, MembershipVerifier},
+    proof::{proof_gen, proof_verify, LinkedProver, LinkedVerifier, MessageRef, Proof, ProofGenRequest, ProofVerifyRequest},
+    pseudonym::{Pseudonym, PseudonymProver, PseudonymVerifier},
+    range::{Bound, RangeProof, RangeProver, RangeVerifier},
+    signature::{PublicKey, Signature}
+};
+use crate::did::DidResolver;
+use crate::secret::SecretScalar;
+use crate::status_list::{self, CredentialStatus, StatusListCredential};
+use crate::trust::{IssuerTrust, TrustRegistry, IDENTITY_CARD_TYPE};
+use crate::validity::{self, Clock, ValidityError};
+use crate::{DisclosedMessage, SIGNATURE_HEADER};
+
+pub const COUNTRY_INDEX: usize = 2;
+pub const DATE_OF_BIRTH_INDEX: usize = 4;
+pub const EXPIRY_DATE_INDEX: usize = 7;
+pub const STATUS_INDEX: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Predicate {
+    AgeOver { years: u32, date: String },
+    NotExpired { date: String },
+    CountryIn { countries: Vec<String> }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::AgeOver { years, date } => write!(f, "age over {} on {}", years, date),
+            Predicate::NotExpired { date } => write!(f, "not expired on {}", date),
+            Predicate::CountryIn { countries } => write!(f, "country in {}", countries.join(", "))
+        }
+    }
+}
+
+enum PredicateStatement {
+    Range(Bound),
+    Membership(Vec<Scalar>)
+}
+
+impl Predicate {
+    fn message(&self) -> MessageRef {
+        MessageRef::Signed(match self {
+            Predicate::AgeOver { .. } => DATE_OF_BIRTH_INDEX,
+            Predicate::NotExpired { .. } => EXPIRY_DATE_INDEX,
+            Predicate::CountryIn { .. } => COUNTRY_INDEX
+        })
+    }
+
+    fn statement(&self) -> Result<PredicateStatement, String> {
+        match self {
+            Predicate::AgeOver { years, date } => {
+                let date = zkp::date_to_integer(date).ok_or("Invalid predicate date")?;
+
+                // Born on or before the same day `years` years earlier.
+                date.checked_sub(*years as u64 * 10000).map(|bound| PredicateStatement::Range(Bound::AtMost(bound))).ok_or_else(|| "Invalid age".to_string())
+            },
+            Predicate::NotExpired { date } => {
+                let date = zkp::date_to_integer(date).ok_or("Invalid predicate date")?;
+
+                Ok(PredicateStatement::Range(Bound::AtLeast(date + 1)))
+            },
+            Predicate::CountryIn { countries } => {
+                if countries.is_empty() {
+                    return Err("Country set must not be empty".to_string());
+                }
+
+                Ok(PredicateStatement::Membership(zkp::messages_to_scalars(countries)))
+            }
+        }
+    }
+}
+
+enum PredicateProver {
+    Range(RangeProver),
+    Membership(MembershipProver)
+}
+
+impl PredicateProver {
+    fn new(predicate: &Predicate, messages: &[String]) -> Result<Self, String> {
+        let message = predicate.message();
+
+        let MessageRef::Signed(index) = message else {
+            return Err("Predicate must target a signed attribute".to_string());
+        };
+
+        let value = messages.get(index).ok_or("Predicate attribute is missing")?;
+
+        match predicate.statement()? {
+            PredicateStatement::Range(bound) => {
+                let value = zkp::date_to_integer(value).ok_or("Predicate attribute is not a date")?;
+
+                Ok(PredicateProver::Range(RangeProver::new(message, value, bound)?))
+            },
+            PredicateStatement::Membership(set) => {
+                Ok(PredicateProver::Membership(MembershipProver::new(message, zkp::message_to_scalar(value.as_bytes()), set)?))
+            }
+        }
+    }
+
+    fn linked(&mut self) -> &mut dyn LinkedProver {
+        match self {
+            PredicateProver::Range(prover) => prover,
+            PredicateProver::Membership(prover) => prover
+        }
+    }
+
+    fn proof_bytes(&self) -> Vec<u8> {
+        match self {
+            PredicateProver::Range(prover) => prover.proof().to_bytes(),
+            PredicateProver::Membership(prover) => prover.proof().to_bytes()
+        }
+    }
+}
+
+enum PredicateProofData {
+    Range(Bound, RangeProof),
+    Membership(Vec<Scalar>, MembershipProof)
+}
+
+impl PredicateProofData {
+    fn new(predicate_proof: &PredicateProof) -> Result<Self, String> {
+        let bytes = hex::decode(&predicate_proof.proof).map_err(|e| e.to_string())?;
+
+        match predicate_proof.predicate.statement()? {
+            PredicateStatement::Range(bound) => Ok(PredicateProofData::Range(bound, RangeProof::from_bytes(&bytes)?)),
+            PredicateStatement::Membership(set) => {
+                let proof = MembershipProof::from_bytes(&bytes, set.len())?;
+
+                Ok(PredicateProofData::Membership(set, proof))
+            }
+        }
+    }
+
+    fn verifier(&self, message: MessageRef) -> Box<dyn LinkedVerifier + '_> {
+        match self {
+            PredicateProofData::Range(bound, proof) => Box::new(RangeVerifier::new(message, *bound, proof)),
+            PredicateProofData::Membership(set, proof) => Box::new(MembershipVerifier::new(message, set.clone(), proof))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PredicateProof {
+    pub predicate: Predicate,
+    pub proof: String
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NonRevocation {
+    pub epoch: u64,
+    pub proof: String
+}
+
+pub struct NonRevocationWitness {
+    pub witness: Witness,
+    pub accumulator: Accumulator,
+    pub epoch: u64
+}
+
+pub struct AccumulatorState {
+    pub public_key: PublicKey,
+    pub accumulator: Accumulator,
+    pub epoch: u64
+}
+
+pub struct BoundCredential<'a> {
+    pub public_key: PublicKey,
+    pub signature: Signature,
+    pub messages: &'a [String],
+    pub holder_secret: SecretScalar,
+    pub prover_blind: Scalar,
+    // DID URL of the issuer key the presentation refers to.
+    pub verification_method: Option<String>
+}
+
+#[cfg(test)]
+impl<'a> BoundCredential<'a> {
+    // Card blind signed by a test issuer key over a fresh holder secret.
+    pub(crate) fn issue_for_tests(secret_key: &zkp::signature::SecretKey, messages: &'a [String]) -> Self {
+        let mut issued = zkp::testing::issue(secret_key, SIGNATURE_HEADER, &zkp::messages_to_scalars(messages), zkp::random_scalar());
+
+        BoundCredential {
+            public_key: issued.public_key,
+            signature: issued.signature,
+            messages,
+            holder_secret: SecretScalar::take(&mut issued.holder_secret),
+            prover_blind: issued.prover_blind,
+            verification_method: None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Presentation {
+    pub proof: String,
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_method: Option<String>,
+    pub message_count: usize,
+    pub disclosed_messages: Vec<DisclosedMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pseudonym: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predicates: Vec<PredicateProof>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub non_revocation: Option<NonRevocation>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PresentationResult {
+    pub verified: bool,
+    pub disclosed_messages: Vec<DisclosedMessage>,
+    pub pseudonym: Option<String>,
+    pub predicates: Vec<Predicate>,
+    pub status: Option<CredentialStatus>,
+    pub non_revocation_epoch: Option<u64>,
+    pub error: Option<ValidityError>,
+    pub issuer: Option<IssuerTrust>
+}
+
+pub fn create_presentation(credential: &BoundCredential, disclosed_indices: &[usize], presentation_header: &[u8], verifier_id: Option<&str>, predicates: &[Predicate], non_revocation: Option<&NonRevocationWitness>) -> Result<Presentation, String> {
+    let messages = zkp::messages_to_scalars(credential.messages);
+
+    let mut pseudonym_prover = verifier_id.map(|id| PseudonymProver::new(id.as_bytes(), credential.holder_secret.expose()));
+
+    if predicates.iter().any(|predicate| matches!(predicate.message(), MessageRef::Signed(index) if disclosed_indices.contains(&index))) {
+        return Err("Predicate attribute must not be disclosed".to_string());
+    }
+
+    let mut predicate_provers = predicates.iter().map(|predicate| PredicateProver::new(predicate, credential.messages)).collect::<Result<Vec<PredicateProver>, String>>()?;
+
+    if non_revocation.is_some() && disclosed_indices.contains(&STATUS_INDEX) {
+        return Err("Status list index must stay hidden when proving non-revocation".to_string());
+    }
+
+    let mut non_revocation_prover = match non_revocation {
+        Some(witness) => {
+            let element = messages.get(STATUS_INDEX).ok_or("Credential has no revocation identifier")?;
+
+            Some(NonRevocationProver::new(MessageRef::Signed(STATUS_INDEX), element, &witness.witness, &witness.accumulator))
+        },
+        None => None
+    };
+
+    let mut linked: Vec<&mut dyn LinkedProver> = vec![];
+
+    if let Some(prover) = pseudonym_prover.as_mut() {
+        linked.push(prover);
+    }
+
+    for prover in predicate_provers.iter_mut() {
+        linked.push(prover.linked());
+    }
+
+    if let Some(prover) = non_revocation_prover.as_mut() {
+        linked.push(prover);
+    }
+
+    let proof = proof_gen(&ProofGenRequest {
+        public_key: &credential.public_key,
+        signature: &credential.signature,
+        header: SIGNATURE_HEADER,
+        presentation_header,
+        messages: &messages,
+        committed_messages: &[*credential.holder_secret.expose(), credential.prover_blind],
+        disclosed_indices
+    }, &mut linked)?;
+
+    Ok(Presentation {
+        proof: hex::encode(proof.to_bytes()),
+        public_key: hex::encode(credential.public_key.to_bytes()),
+        verification_method: credential.verification_method.clone(),
+        message_count: credential.messages.len(),
+        disclosed_messages: credential.messages.iter().enumerate().filter(|(i, _)| disclosed_indices.contains(i)).map(|(i, value)| DisclosedMessage {
+            index: i,
+            value: value.clone()
+        }).collect(),
+        pseudonym: pseudonym_prover.map(|prover| hex::encode(prover.pseudonym().to_bytes())),
+        predicates: predicates.iter().zip(&predicate_provers).map(|(predicate, prover)| PredicateProof {
+            predicate: predicate.clone(),
+            proof: hex::encode(prover.proof_bytes())
+        }).collect(),
+        non_revocation: non_revocation.zip(non_revocation_prover).map(|(witness, prover)| NonRevocation {
+            epoch: witness.epoch,
+            proof: hex::encode(prover.proof().to_bytes())
+        })
+    })
+}
+
+fn check_presentation(presentation: &Presentation, presentation_header: &[u8], verifier_id: Option<&str>, required_predicates: &[Predicate], accumulator: Option<&AccumulatorState>) -> Result<bool, String> {
+    if required_predicates.iter().any(|required| !presentation.predicates.iter().any(|p| &p.predicate == required)) {
+        return Err("Presentation does not prove a required predicate".to_string());
+    }
+
+    let public_key = PublicKey::from_bytes(&hex::decode(&presentation.public_key).map_err(|e| e.to_string())?)?;
+    let proof = Proof::from_bytes(&hex::decode(&presentation.proof).map_err(|e| e.to_string())?)?;
+
+    let pseudonym_verifier = match (&presentation.pseudonym, verifier_id) {
+        (Some(pseudonym_hex), Some(id)) => {
+            let pseudonym = Pseudonym::from_bytes(&hex::decode(pseudonym_hex).map_err(|e| e.to_string())?)?;
+
+            Some(PseudonymVerifier::new(id.as_bytes(), pseudonym))
+        },
+        (None, None) => None,
+        (None, Some(_)) => return Err("Presentation does not contain a pseudonym".to_string()),
+        (Some(_), None) => return Err("A verifier ID is required to check the pseudonym".to_string())
+    };
+
+    let predicate_proofs = presentation.predicates.iter().map(PredicateProofData::new).collect::<Result<Vec<PredicateProofData>, String>>()?;
+
+    let predicate_verifiers: Vec<Box<dyn LinkedVerifier + '_>> = presentation.predicates.iter().zip(&predicate_proofs).map(|(p, data)| data.verifier(p.predicate.message())).collect();
+
+    let mut linked: Vec<&dyn LinkedVerifier> = vec![];
+
+    if let Some(verifier) = pseudonym_verifier.as_ref() {
+        linked.push(verifier);
+    }
+
+    for verifier in predicate_verifiers.iter() {
+        linked.push(verifier.as_ref());
+    }
+
+    let non_revocation_proof = match (&presentation.non_revocation, accumulator) {
+        (Some(non_revocation), Some(state)) if non_revocation.epoch == state.epoch => {
+            Some(NonRevocationProof::from_bytes(&hex::decode(&non_revocation.proof).map_err(|e| e.to_string())?)?)
+        },
+        (Some(_), Some(_)) => return Err("Non-revocation proof is for an outdated accumulator".to_string()),
+        (Some(_), None) => return Err("An accumulator is required to check non-revocation".to_string()),
+        (None, Some(_)) => return Err("Presentation does not prove non-revocation".to_string()),
+        (None, None) => None
+    };
+
+    let non_revocation_verifier = non_revocation_proof.as_ref().zip(accumulator).map(|(proof, state)| {
+        NonRevocationVerifier::new(MessageRef::Signed(STATUS_INDEX), &state.public_key, &state.accumulator, proof)
+    });
+
+    if let Some(verifier) = non_revocation_verifier.as_ref() {
+        linked.push(verifier);
+    }
+
+    let disclosed_messages: Vec<(usize, Scalar)> = presentation.disclosed_messages.iter().map(|dm| (dm.index, zkp::message_to_scalar(dm.value.as_bytes()))).collect();
+
+    Ok(proof_verify(&ProofVerifyRequest {
+        public_key: &public_key,
+        header: SIGNATURE_HEADER,
+        presentation_header,
+        proof: &proof,
+        message_count: presentation.message_count,
+        committed_count: COMMITTED_MESSAGE_COUNT,
+        disclosed_messages: &disclosed_messages
+    }, &linked))
+}
+
+// Only called on verified presentations, so a non-revocation proof present here was checked against the accumulator.
+// Without one, the index has to be disclosed: hiding it must not let a revoked card through.
+fn check_presentation_status(presentation: &Presentation, status_lists: &[StatusListCredential]) -> Result<Option<CredentialStatus>, String> {
+    let Some(status_index) = presentation.disclosed_messages.iter().find(|dm| dm.index == STATUS_INDEX) else {
+        if presentation.non_revocation.is_some() {
+            return Ok(None);
+        }
+
+        return Err("Presentation neither discloses its status list index nor proves non-revocation".to_string());
+    };
+
+    let index: usize = status_index.value.parse().map_err(|_| "Invalid status list index")?;
+    let public_key = PublicKey::from_bytes(&hex::decode(&presentation.public_key).map_err(|e| e.to_string())?)?;
+
+    status_list::check_status(&public_key, index, status_lists).map(Some)
+}
+
+// Nothing to enforce until an accumulator was published. Without a non-revocation proof the card is checked on the status lists when there are some, otherwise the accumulator requires the proof.
+pub fn enforced_accumulator<'a>(presentation: &Presentation, status_lists: Option<&[StatusListCredential]>, published: Option<&'a AccumulatorState>) -> Option<&'a AccumulatorState> {
+    match (status_lists, &presentation.non_revocation) {
+        (Some(_), None) => None,
+        _ => published
+    }
+}
+
+pub fn verify_presentation(presentation: &Presentation, presentation_header: &[u8], verifier_id: Option<&str>, required_predicates: &[Predicate], status_lists: Option<&[StatusListCredential]>, accumulator: Option<&AccumulatorState>) -> PresentationResult {
+    let mut verified = check_presentation(presentation, presentation_header, verifier_id, required_predicates, accumulator).unwrap_or(false);
+
+    let status = match status_lists {
+        Some(lists) if verified => check_presentation_status(presentation, lists).unwrap_or_else(|_| {
+            verified = false;
+            None
+        }),
+        _ => None
+    };
+
+    if status.is_some_and(|status| status.revoked || status.suspended) {
+        verified = false;
+    }
+
+    PresentationResult {
+        verified,
+        status,
+        disclosed_messages: presentation.disclosed_messages.clone(),
+        pseudonym: if verified { presentation.pseudonym.clone() } else { None },
+        predicates: if verified { presentation.predicates.iter().map(|p| p.predicate.clone()).collect() } else { vec![] },
+        non_revocation_epoch: if verified { presentation.non_revocation.as_ref().map(|n| n.epoch) } else { None },
+        error: None,
+        issuer: None
+    }
+}
+
+pub fn check_presentation_validity(result: &mut PresentationResult, clock: &dyn Clock) {
+    if let Err(error) = validity::check_disclosed_validity(&result.disclosed_messages, &result.predicates, clock) {
+        result.verified = false;
+        result.error = Some(error);
+    }
+}
+
+// The proof only shows the card was signed with `public_key`, the registry tells whether that key belongs to a trusted issuer.
+pub fn check_presentation_issuer(result: &mut PresentationResult, presentation: &Presentation, registry: &TrustRegistry, clock: &dyn Clock) {
+    result.issuer = Some(registry.check(&presentation.public_key, IDENTITY_CARD_TYPE, clock));
+}
+
+// The issuer key may be named by a DID URL, which then has to resolve to the key the proof was checked against.
+pub async fn check_verification_method(presentation: &Presentation, resolver: &DidResolver) -> Result<(), String> {
+    let Some(verification_method) = &presentation.verification_method else {
+        return Ok(());
+    };
+
+    if !resolver.resolve_public_key(verification_method).await?.eq_ignore_ascii_case(&presentation.public_key) {
+        return Err(format!("Presentation key does not match {}", verification_method));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::testing::{self, card_messages};
+
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    fn present(messages: &[String], disclosed_indices: &[usize], verifier_id: Option<&str>, predicates: &[Predicate]) -> Result<Presentation, String> {
+        create_presentation(&BoundCredential::issue_for_tests(&testing::issuer_key(), messages), disclosed_indices, PRESENTATION_HEADER, verifier_id, predicates, None)
+    }
+
+    #[test]
+    fn presentation_roundtrip_with_pseudonym() {
+        let presentation = present(&card_messages(), &[2], Some("https://verifier.example"), &[]).unwrap();
+        let presentation: Presentation = serde_json::from_str(&serde_json::to_string(&presentation).unwrap()).unwrap();
+
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, Some("https://verifier.example"), &[], None, None);
+
+        assert!(result.verified);
+        assert_eq!(result.pseudonym, presentation.pseudonym);
+        assert_eq!(result.disclosed_messages.len(), 1);
+        assert_eq!(result.disclosed_messages[0].value, "FR");
+
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, Some("https://other.example"), &[], None, None).verified);
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, None).verified);
+    }
+
+    #[test]
+    fn presentation_rejects_modified_disclosed_value() {
+        let mut presentation = present(&card_messages(), &[2, 7], None, &[]).unwrap();
+        presentation.disclosed_messages[0].value = "DE".to_string();
+
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, None);
+
+        assert!(!result.verified);
+        assert_eq!(result.pseudonym, None);
+    }
+
+    #[test]
+    fn presentation_with_disclosed_expired_date_is_rejected() {
+        use chrono::TimeZone;
+        use crate::validity::FixedClock;
+
+        let presentation = present(&card_messages(), &[2, 7], None, &[]).unwrap();
+
+        let mut result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, None);
+        check_presentation_validity(&mut result, &FixedClock(chrono::Utc.with_ymd_and_hms(2029, 12, 31, 12, 0, 0).unwrap()));
+
+        assert!(result.verified);
+        assert_eq!(result.error, None);
+
+        check_presentation_validity(&mut result, &FixedClock(chrono::Utc.with_ymd_and_hms(2030, 1, 1, 12, 0, 0).unwrap()));
+
+        assert!(!result.verified);
+        assert_eq!(result.error, Some(ValidityError::Expired { expiry_date: "2030-01-01".to_string() }));
+    }
+
+    #[test]
+    fn presentation_proves_age_and_validity_without_disclosing_dates() {
+        let age_over = Predicate::AgeOver { years: 18, date: "2026-10-18".to_string() };
+        let not_expired = Predicate::NotExpired { date: "2026-10-18".to_string() };
+
+        let presentation = present(&card_messages(), &[2], None, &[age_over.clone(), not_expired.clone()]).unwrap();
+        let presentation: Presentation = serde_json::from_str(&serde_json::to_string(&presentation).unwrap()).unwrap();
+
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[age_over.clone(), not_expired.clone()], None, None);
+
+        assert!(result.verified);
+        assert_eq!(result.predicates, vec![age_over.clone(), not_expired]);
+        assert!(result.disclosed_messages.iter().all(|dm| dm.index != DATE_OF_BIRTH_INDEX && dm.index != EXPIRY_DATE_INDEX));
+
+        let older = [Predicate::AgeOver { years: 40, date: "2026-10-18".to_string() }];
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &older, None, None).verified);
+        assert!(present(&card_messages(), &[2], None, &older).is_err());
+        assert!(present(&card_messages(), &[DATE_OF_BIRTH_INDEX], None, &[age_over]).is_err());
+
+        let expired = Predicate::NotExpired { date: "2030-01-01".to_string() };
+        assert!(present(&card_messages(), &[2], None, &[expired]).is_err());
+    }
+
+    #[test]
+    fn presentation_proves_country_membership_without_disclosing_country() {
+        let european_union = Predicate::CountryIn { countries: ["AT", "BE", "DE", "ES", "FR", "IT"].iter().map(|c| c.to_string()).collect() };
+
+        let presentation = present(&card_messages(), &[0], None, std::slice::from_ref(&european_union)).unwrap();
+        let presentation: Presentation = serde_json::from_str(&serde_json::to_string(&presentation).unwrap()).unwrap();
+
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, std::slice::from_ref(&european_union), None, None);
+
+        assert!(result.verified);
+        assert!(result.disclosed_messages.iter().all(|dm| dm.index != COUNTRY_INDEX));
+
+        let mut swapped = presentation.clone();
+        swapped.predicates[0].predicate = Predicate::CountryIn { countries: ["AT", "BE", "DE", "ES", "PT", "IT"].iter().map(|c| c.to_string()).collect() };
+        assert!(!verify_presentation(&swapped, PRESENTATION_HEADER, None, &[], None, None).verified);
+
+        let outside = Predicate::CountryIn { countries: vec!["US".to_string(), "CA".to_string()] };
+        assert!(present(&card_messages(), &[0], None, &[outside]).is_err());
+    }
+
+    #[test]
+    fn presentation_is_rejected_once_revoked_or_suspended() {
+        use crate::status_list::{StatusList, StatusPurpose, STATUS_LIST_LENGTH};
+
+        let secret_key = testing::issuer_key();
+
+        let mut messages = card_messages();
+        messages.push("42".to_string());
+
+        let presentation = present(&messages, &[2, STATUS_INDEX], None, &[]).unwrap();
+
+        let mut revocation = StatusList::new(STATUS_LIST_LENGTH);
+        let mut suspension = StatusList::new(STATUS_LIST_LENGTH);
+
+        let lists = |revocation: &StatusList, suspension: &StatusList| vec![
+            StatusListCredential::issue(&secret_key, StatusPurpose::Revocation, revocation).unwrap(),
+            StatusListCredential::issue(&secret_key, StatusPurpose::Suspension, suspension).unwrap()
+        ];
+
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], Some(&lists(&revocation, &suspension)), None);
+        assert!(result.verified);
+        assert_eq!(result.status, Some(CredentialStatus { revoked: false, suspended: false }));
+
+        suspension.set(42, true).unwrap();
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], Some(&lists(&revocation, &suspension)), None);
+        assert!(!result.verified);
+        assert_eq!(result.status, Some(CredentialStatus { revoked: false, suspended: true }));
+
+        suspension.set(42, false).unwrap();
+        revocation.set(42, true).unwrap();
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], Some(&lists(&revocation, &suspension)), None);
+        assert!(!result.verified);
+        assert_eq!(result.status, Some(CredentialStatus { revoked: true, suspended: false }));
+
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], Some(&[]), None).verified);
+
+        // The same revoked card with its index hidden.
+        let hidden = present(&messages, &[2], None, &[]).unwrap();
+        let result = verify_presentation(&hidden, PRESENTATION_HEADER, None, &[], Some(&lists(&revocation, &suspension)), None);
+        assert!(!result.verified);
+        assert_eq!(result.status, None);
+
+        assert!(verify_presentation(&hidden, PRESENTATION_HEADER, None, &[], None, None).verified);
+    }
+
+    #[test]
+    fn presentation_proves_non_revocation_without_disclosing_status_index() {
+        let accumulator_key = testing::accumulator_key();
+
+        let mut messages = card_messages();
+        messages.push("42".to_string());
+
+        let element = zkp::message_to_scalar(b"42");
+        let accumulator = Accumulator::new().add(&accumulator_key, &element);
+
+        let credential = BoundCredential::issue_for_tests(&testing::issuer_key(), &messages);
+        let witness = NonRevocationWitness { witness: accumulator.witness(&accumulator_key, &element).unwrap(), accumulator, epoch: 1 };
+
+        assert!(create_presentation(&credential, &[2, STATUS_INDEX], PRESENTATION_HEADER, None, &[], Some(&witness)).is_err());
+
+        let presentation = create_presentation(&credential, &[2], PRESENTATION_HEADER, None, &[], Some(&witness)).unwrap();
+
+        let state = AccumulatorState { public_key: accumulator_key.public_key(), accumulator, epoch: 1 };
+        let result = verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, Some(&state));
+
+        assert!(result.verified);
+        assert_eq!(result.non_revocation_epoch, Some(1));
+        assert!(result.disclosed_messages.iter().all(|dm| dm.index != STATUS_INDEX));
+
+        let revoked = AccumulatorState { public_key: accumulator_key.public_key(), accumulator: accumulator.remove(&accumulator_key, &element).unwrap(), epoch: 1 };
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, Some(&revoked)).verified);
+
+        let newer = AccumulatorState { public_key: accumulator_key.public_key(), accumulator, epoch: 2 };
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, Some(&newer)).verified);
+        assert!(!verify_presentation(&presentation, PRESENTATION_HEADER, None, &[], None, None).verified);
+
+        // A verifier holding the accumulator does not take a presentation that skips the proof.
+        let without_proof = create_presentation(&credential, &[2], PRESENTATION_HEADER, None, &[], None).unwrap();
+        assert!(!verify_presentation(&without_proof, PRESENTATION_HEADER, None, &[], None, Some(&state)).verified);
+
+        // Cards without a proof are still taken until an accumulator was published, or when the status lists cover them.
+        assert!(enforced_accumulator(&without_proof, None, None).is_none());
+        assert!(verify_presentation(&without_proof, PRESENTATION_HEADER, None, &[], None, enforced_accumulator(&without_proof, None, None)).verified);
+        assert!(enforced_accumulator(&without_proof, Some(&[]), Some(&state)).is_none());
+        assert!(enforced_accumulator(&without_proof, None, Some(&state)).is_some());
+        assert!(enforced_accumulator(&presentation, Some(&[]), Some(&state)).is_some());
+    }
+}
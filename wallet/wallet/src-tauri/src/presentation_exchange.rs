@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Optionality {
+    Required,
+    Preferred
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Field {
+    pub path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<Optionality>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_disclosure: Option<Optionality>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct InputDescriptor {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<String>,
+    #[serde(default)]
+    pub constraints: Constraints
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Rule {
+    All,
+    Pick
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SubmissionRequirement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    pub rule: Rule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_nested: Option<Vec<SubmissionRequirement>>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PresentationDefinition {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submission_requirements: Option<Vec<SubmissionRequirement>>,
+    pub input_descriptors: Vec<InputDescriptor>
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+    Descendant(String)
+}
+
+// Subset of JSONPath used by presentation definitions: `.name`, `['name']`, `[0]`, `[*]`, `.*` and `..name`.
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let invalid = || format!("Unsupported JSONPath {}", path);
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = vec![];
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+
+            if end == 0 || &after[..end] == "*" {
+                return Err(invalid());
+            }
+
+            segments.push(Segment::Descendant(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+
+            segments.push(match &after[..end] {
+                "" => return Err(invalid()),
+                "*" => Segment::Wildcard,
+                name => Segment::Key(name.to_string())
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = after[..end].trim();
+
+            segments.push(match inner {
+                "*" => Segment::Wildcard,
+                _ if inner.len() >= 2 && (inner.starts_with('\'') && inner.ends_with('\'') || inner.starts_with('"') && inner.ends_with('"')) => Segment::Key(inner[1..inner.len() - 1].to_string()),
+                _ => Segment::Index(inner.parse().map_err(|_| invalid())?)
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(segments)
+}
+
+fn descendants<'a>(value: &'a Value, name: &str, location: &[Value], results: &mut Vec<(Vec<Value>, &'a Value)>) {
+    let children: Vec<(Value, &Value)> = match value {
+        Value::Object(object) => object.iter().map(|(key, child)| (Value::from(key.as_str()), child)).collect(),
+        Value::Array(array) => array.iter().enumerate().map(|(i, child)| (Value::from(i), child)).collect(),
+        _ => vec![]
+    };
+
+    for (key, child) in children {
+        let mut child_location = location.to_vec();
+        child_location.push(key.clone());
+
+        if key.as_str() == Some(name) {
+            results.push((child_location.clone(), child));
+        }
+
+        descendants(child, name, &child_location, results);
+    }
+}
+
+// Returns every value selected by the path along with its normalized location, in the claims path form of DCQL.
+pub fn select_path<'a>(value: &'a Value, path: &str) -> Result<Vec<(Vec<Value>, &'a Value)>, String> {
+    let mut selected = vec![(vec![], value)];
+
+    for segment in parse_path(path)? {
+        let mut next = vec![];
+
+        for (location, current) in selected {
+            let mut push = |key: Value, child: &'a Value| {
+                let mut child_location = location.clone();
+                child_location.push(key);
+                next.push((child_location, child));
+            };
+
+            match (&segment, current) {
+                (Segment::Key(name), Value::Object(object)) => if let Some(child) = object.get(name) { push(Value::from(name.as_str()), child) },
+                (Segment::Index(i), Value::Array(array)) => if let Some(child) = array.get(*i) { push(Value::from(*i), child) },
+                (Segment::Wildcard, Value::Object(object)) => object.iter().for_each(|(key, child)| push(Value::from(key.as_str()), child)),
+                (Segment::Wildcard, Value::Array(array)) => array.iter().enumerate().for_each(|(i, child)| push(Value::from(i), child)),
+                (Segment::Descendant(name), _) => descendants(current, name, &location, &mut next),
+                _ => {}
+            }
+        }
+
+        selected = next;
+    }
+
+    Ok(selected)
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false
+    }
+}
+
+fn compare_format(format: &str, value: &str, bound: &str) -> Option<std::cmp::Ordering> {
+    match format {
+        "date" => Some(NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.cmp(&NaiveDate::parse_from_str(bound, "%Y-%m-%d").ok()?)),
+        "date-time" => Some(DateTime::parse_from_rfc3339(value).ok()?.cmp(&DateTime::parse_from_rfc3339(bound).ok()?)),
+        _ => None
+    }
+}
+
+// Subset of JSON Schema used in filters. Unknown keywords are ignored, as JSON Schema does.
+pub fn matches_filter(filter: &Value, value: &Value) -> bool {
+    let Some(schema) = filter.as_object() else {
+        return filter.as_bool().unwrap_or(false);
+    };
+
+    schema.iter().all(|(keyword, argument)| match keyword.as_str() {
+        "type" => match argument {
+            Value::String(expected) => type_matches(expected, value),
+            Value::Array(expected) => expected.iter().filter_map(Value::as_str).any(|expected| type_matches(expected, value)),
+            _ => false
+        },
+        "const" => value == argument,
+        "enum" => argument.as_array().is_some_and(|values| values.contains(value)),
+        "pattern" => match (argument.as_str(), value.as_str()) {
+            (Some(pattern), Some(value)) => Regex::new(pattern).is_ok_and(|regex| regex.is_match(value)),
+            _ => !value.is_string()
+        },
+        "minLength" | "maxLength" => match (argument.as_u64(), value.as_str()) {
+            (Some(bound), Some(value)) => {
+                let length = value.chars().count() as u64;
+                if keyword == "minLength" { length >= bound } else { length <= bound }
+            },
+            _ => !value.is_string()
+        },
+        "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" => match (argument.as_f64(), value.as_f64()) {
+            (Some(bound), Some(value)) => match keyword.as_str() {
+                "minimum" => value >= bound,
+                "maximum" => value <= bound,
+                "exclusiveMinimum" => value > bound,
+                _ => value < bound
+            },
+            _ => !value.is_number()
+        },
+        "format" => match (argument.as_str(), value.as_str()) {
+            (Some(format @ ("date" | "date-time")), Some(value)) => compare_format(format, value, value).is_some(),
+            _ => true
+        },
+        "formatMinimum" | "formatMaximum" | "formatExclusiveMinimum" | "formatExclusiveMaximum" => {
+            let format = schema.get("format").and_then(Value::as_str).unwrap_or("date");
+
+            match (value.as_str(), argument.as_str()) {
+                (Some(value), Some(bound)) => compare_format(format, value, bound).is_some_and(|ordering| match keyword.as_str() {
+                    "formatMinimum" => ordering.is_ge(),
+                    "formatMaximum" => ordering.is_le(),
+                    "formatExclusiveMinimum" => ordering.is_gt(),
+                    _ => ordering.is_lt()
+                }),
+                _ => false
+            }
+        },
+        "contains" => value.as_array().is_some_and(|items| items.iter().any(|item| matches_filter(argument, item))),
+        "items" => value.as_array().is_none_or(|items| items.iter().all(|item| matches_filter(argument, item))),
+        "minItems" => value.as_array().is_none_or(|items| argument.as_u64().is_some_and(|bound| items.len() as u64 >= bound)),
+        "maxItems" => value.as_array().is_none_or(|items| argument.as_u64().is_some_and(|bound| items.len() as u64 <= bound)),
+        "not" => !matches_filter(argument, value),
+        "allOf" => argument.as_array().is_some_and(|schemas| schemas.iter().all(|schema| matches_filter(schema, value))),
+        "anyOf" => argument.as_array().is_some_and(|schemas| schemas.iter().any(|schema| matches_filter(schema, value))),
+        "oneOf" => argument.as_array().is_some_and(|schemas| schemas.iter().filter(|schema| matches_filter(schema, value)).count() == 1),
+        _ => true
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FieldMatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub path: Vec<Value>,
+    pub value: Value,
+    pub optional: bool,
+    pub predicate: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DescriptorMatch {
+    pub descriptor_id: String,
+    pub credential: usize,
+    pub fields: Vec<FieldMatch>,
+    pub limit_disclosure: bool
+}
+
+// Every field of the descriptor must select a value that passes its filter, in the first path that has one.
+pub fn evaluate_descriptor(descriptor: &InputDescriptor, credential: &Value) -> Result<Vec<FieldMatch>, String> {
+    let mut matches = vec![];
+
+    for field in &descriptor.constraints.fields {
+        let mut found = None;
+
+        for path in &field.path {
+            found = select_path(credential, path)?.into_iter().find(|(_, value)| field.filter.as_ref().is_none_or(|filter| matches_filter(filter, value)));
+
+            if found.is_some() {
+                break;
+            }
+        }
+
+        match found {
+            Some((path, value)) => matches.push(FieldMatch {
+                id: field.id.clone(),
+                path,
+                value: value.clone(),
+                optional: field.optional,
+                predicate: field.predicate.is_some(),
+                filter: field.filter.clone()
+            }),
+            None if field.optional => {},
+            None => return Err(format!("No value satisfies {} in input descriptor {}", field.path.first().map(String::as_str).unwrap_or("$"), descriptor.id))
+        }
+    }
+
+    Ok(matches)
+}
+
+fn choose(requirement: &SubmissionRequirement, definition: &PresentationDefinition, candidates: &HashMap<&str, usize>) -> Option<Vec<String>> {
+    let children: Vec<Option<Vec<String>>> = match (&requirement.from, &requirement.from_nested) {
+        (Some(group), None) => definition.input_descriptors.iter()
+            .filter(|descriptor| descriptor.group.contains(group))
+            .map(|descriptor| candidates.contains_key(descriptor.id.as_str()).then(|| vec![descriptor.id.clone()]))
+            .collect(),
+        (None, Some(nested)) => nested.iter().map(|requirement| choose(requirement, definition, candidates)).collect(),
+        _ => return None
+    };
+
+    let satisfied: Vec<Vec<String>> = children.iter().flatten().cloned().collect();
+
+    let chosen = match requirement.rule {
+        Rule::All if satisfied.len() == children.len() => satisfied,
+        Rule::All => return None,
+        Rule::Pick => {
+            // The holder discloses as little as allowed, so only the minimum number of children is picked.
+            let needed = requirement.count.or(requirement.min).unwrap_or(1);
+
+            if requirement.max.is_some_and(|max| max < needed) || satisfied.len() < needed {
+                return None;
+            }
+
+            satisfied.into_iter().take(needed).collect()
+        }
+    };
+
+    Some(chosen.into_iter().flatten().collect())
+}
+
+// Selects the input descriptors to answer and, for each, the first credential that satisfies it.
+pub fn evaluate(definition: &PresentationDefinition, credentials: &[Value]) -> Result<Vec<DescriptorMatch>, String> {
+    let mut candidates: HashMap<&str, usize> = HashMap::new();
+    let mut matches: HashMap<&str, Vec<FieldMatch>> = HashMap::new();
+    let mut reasons = vec![];
+
+    for descriptor in &definition.input_descriptors {
+        for (i, credential) in credentials.iter().enumerate() {
+            match evaluate_descriptor(descriptor, credential) {
+                Ok(fields) => {
+                    candidates.insert(&descriptor.id, i);
+                    matches.insert(&descriptor.id, fields);
+                    break;
+                },
+                Err(reason) => reasons.push(reason)
+            }
+        }
+    }
+
+    let unsatisfied = || reasons.first().cloned().unwrap_or_else(|| "No credential satisfies the presentation definition".to_string());
+
+    let chosen: Vec<String> = match &definition.submission_requirements {
+        Some(requirements) => {
+            let mut chosen = vec![];
+
+            for requirement in requirements {
+                chosen.extend(choose(requirement, definition, &candidates).ok_or_else(unsatisfied)?);
+            }
+
+            chosen
+        },
+        None if definition.input_descriptors.iter().all(|descriptor| candidates.contains_key(descriptor.id.as_str())) => {
+            definition.input_descriptors.iter().map(|descriptor| descriptor.id.clone()).collect()
+        },
+        None => return Err(unsatisfied())
+    };
+
+    let mut result: Vec<DescriptorMatch> = vec![];
+
+    for descriptor in &definition.input_descriptors {
+        if !chosen.contains(&descriptor.id) || result.iter().any(|m| m.descriptor_id == descriptor.id) {
+            continue;
+        }
+
+        result.push(DescriptorMatch {
+            descriptor_id: descriptor.id.clone(),
+            credential: candidates[descriptor.id.as_str()],
+            fields: matches.remove(descriptor.id.as_str()).unwrap_or_default(),
+            limit_disclosure: descriptor.constraints.limit_disclosure == Some(Optionality::Required)
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(value: Value) -> PresentationDefinition {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn json_path_subset_is_resolved_to_normalized_locations() {
+        let credential = json!({ "vc": { "credentialSubject": { "dob": "1990-01-01", "addresses": [{ "country": "FR" }, { "country": "DE" }] } } });
+
+        let selected = select_path(&credential, "$['vc']['credentialSubject'].addresses[1].country").unwrap();
+        assert_eq!(selected, vec![(vec![json!("vc"), json!("credentialSubject"), json!("addresses"), json!(1), json!("country")], &json!("DE"))]);
+
+        assert_eq!(select_path(&credential, "$.vc.credentialSubject.addresses[*].country").unwrap().len(), 2);
+        assert_eq!(select_path(&credential, "$..country").unwrap().len(), 2);
+        assert_eq!(select_path(&credential, "$..dob").unwrap()[0].0, vec![json!("vc"), json!("credentialSubject"), json!("dob")]);
+        assert!(select_path(&credential, "$.vc.missing").unwrap().is_empty());
+        assert!(select_path(&credential, "vc.credentialSubject").is_err());
+    }
+
+    // Basic example of the Presentation Exchange specification.
+    #[test]
+    fn basic_example_selects_first_path_with_a_value() {
+        let definition = definition(json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "input_descriptors": [{
+                "id": "wa_driver_license",
+                "name": "Washington State Business License",
+                "purpose": "We can only allow licensed Washington State business representatives into the WA Business Conference",
+                "constraints": {
+                    "limit_disclosure": "required",
+                    "fields": [{
+                        "path": ["$.credentialSubject.dateOfBirth", "$.credentialSubject.dob", "$.vc.credentialSubject.dateOfBirth", "$.vc.credentialSubject.dob"]
+                    }]
+                }
+            }]
+        }));
+
+        let credentials = vec![
+            json!({ "credentialSubject": { "name": "Alice" } }),
+            json!({ "vc": { "credentialSubject": { "dob": "07/04/1987" } } })
+        ];
+
+        let matches = evaluate(&definition, &credentials).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].credential, 1);
+        assert!(matches[0].limit_disclosure);
+        assert_eq!(matches[0].fields[0].path, vec![json!("vc"), json!("credentialSubject"), json!("dob")]);
+        assert_eq!(matches[0].fields[0].value, json!("07/04/1987"));
+
+        assert!(evaluate(&definition, &credentials[..1]).is_err());
+    }
+
+    // Filter example of the specification: issuer pattern, credential type and a date bound.
+    #[test]
+    fn filters_restrict_matching_values() {
+        let definition = definition(json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "input_descriptors": [{
+                "id": "identity_card",
+                "constraints": {
+                    "fields": [
+                        { "path": ["$.issuer", "$.vc.issuer", "$.iss"], "filter": { "type": "string", "pattern": "^did:example:123$|^did:example:456$" } },
+                        { "path": ["$.type"], "filter": { "type": "array", "contains": { "const": "IdentityCard" } } },
+                        { "path": ["$.credentialSubject.dateOfBirth"], "filter": { "type": "string", "format": "date", "formatMaximum": "2008-10-19" } },
+                        { "path": ["$.credentialSubject.placeOfBirth"], "purpose": "Optional", "optional": true },
+                        { "path": ["$.credentialSubject.nickname"], "optional": true }
+                    ]
+                }
+            }]
+        }));
+
+        let adult = json!({
+            "issuer": "did:example:456",
+            "type": ["VerifiableCredential", "IdentityCard"],
+            "credentialSubject": { "dateOfBirth": "1990-01-01", "placeOfBirth": "Paris" }
+        });
+
+        let matches = evaluate(&definition, std::slice::from_ref(&adult)).unwrap();
+        let fields = &matches[0].fields;
+
+        assert_eq!(fields.len(), 4);
+        assert!(!fields[2].optional && fields[3].optional);
+        assert!(!matches[0].limit_disclosure);
+
+        let mut minor = adult.clone();
+        minor["credentialSubject"]["dateOfBirth"] = json!("2010-05-16");
+        assert!(evaluate(&definition, &[minor]).is_err());
+
+        let mut other_issuer = adult.clone();
+        other_issuer["issuer"] = json!("did:example:789");
+        assert!(evaluate(&definition, &[other_issuer]).is_err());
+
+        let mut other_type = adult;
+        other_type["type"] = json!(["VerifiableCredential", "Passport"]);
+        assert!(evaluate(&definition, &[other_type]).is_err());
+    }
+
+    #[test]
+    fn json_schema_subset_keywords() {
+        assert!(matches_filter(&json!({ "type": "number", "minimum": 18 }), &json!(21)));
+        assert!(!matches_filter(&json!({ "type": "number", "exclusiveMaximum": 18 }), &json!(18)));
+        assert!(matches_filter(&json!({ "enum": ["FR", "DE"] }), &json!("FR")));
+        assert!(!matches_filter(&json!({ "not": { "const": "FR" } }), &json!("FR")));
+        assert!(matches_filter(&json!({ "type": ["string", "null"], "minLength": 2, "maxLength": 2 }), &json!("FR")));
+        assert!(matches_filter(&json!({ "anyOf": [{ "const": "FR" }, { "const": "DE" }] }), &json!("DE")));
+        assert!(!matches_filter(&json!({ "format": "date" }), &json!("31/12/2030")));
+        assert!(matches_filter(&json!({ "format": "date-time", "formatExclusiveMinimum": "2020-01-01T00:00:00Z" }), &json!("2026-10-19T08:00:00Z")));
+    }
+
+    // Submission requirements example of the specification, with groups A to D.
+    #[test]
+    fn submission_requirements_pick_minimal_set_of_descriptors() {
+        let descriptor = |id: &str, group: &str, credential_type: &str| json!({
+            "id": id,
+            "group": [group],
+            "constraints": { "fields": [{ "path": ["$.type"], "filter": { "type": "array", "contains": { "const": credential_type } } }] }
+        });
+
+        let definition = definition(json!({
+            "id": "32f54163-7166-48f1-93d8-ff217bdb0653",
+            "submission_requirements": [
+                { "name": "Banking Information", "purpose": "We can only remit payment to a currently-valid bank account in the US, Germany or France.", "rule": "pick", "count": 1, "from": "A" },
+                { "name": "Employment Information", "rule": "all", "from": "B" },
+                { "name": "Citizenship Information", "rule": "pick", "count": 1, "from_nested": [
+                    { "name": "United States Citizenship Proofs", "rule": "pick", "count": 1, "from": "C" },
+                    { "name": "European Union Citizenship Proofs", "rule": "all", "from": "D" }
+                ] }
+            ],
+            "input_descriptors": [
+                descriptor("banking_input_1", "A", "BankAccountUS"),
+                descriptor("banking_input_2", "A", "BankAccountEU"),
+                descriptor("employment_input", "B", "EmploymentCredential"),
+                descriptor("citizenship_input_1", "C", "USPassport"),
+                descriptor("citizenship_input_2", "D", "IdentityCard")
+            ]
+        }));
+
+        let credentials = vec![
+            json!({ "type": ["IdentityCard"] }),
+            json!({ "type": ["BankAccountEU"] }),
+            json!({ "type": ["EmploymentCredential"] })
+        ];
+
+        let selected: Vec<(String, usize)> = evaluate(&definition, &credentials).unwrap().into_iter().map(|m| (m.descriptor_id, m.credential)).collect();
+
+        assert_eq!(selected, vec![
+            ("banking_input_2".to_string(), 1),
+            ("employment_input".to_string(), 2),
+            ("citizenship_input_2".to_string(), 0)
+        ]);
+
+        assert!(evaluate(&definition, &credentials[..2]).is_err());
+    }
+}
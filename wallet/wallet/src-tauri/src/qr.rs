@@ -0,0 +1,301 @@
+use std::io::{Read, Write};
+use std::path::Path;
+
+use ciborium::Value;
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use qrcode::{render::svg, EcLevel, QrCode};
+
+use crate::did;
+use crate::oid4vp;
+use crate::presentation::{NonRevocation, Presentation, PredicateProof};
+use crate::DisclosedMessage;
+
+// Versioned so a scanner can tell a wallet presentation from any other QR code.
+pub const PRESENTATION_PREFIX: &str = "WP1:";
+
+// Alphanumeric capacity of a version 40 QR code at the lowest error correction level.
+pub const QR_ALPHANUMERIC_CAPACITY: usize = 4296;
+
+const MAX_DECODED_LENGTH: u64 = 64 * 1024;
+const QR_MIN_SIZE: u32 = 256;
+
+// Integer map keys keep the CBOR payload small, the field names would otherwise dominate it.
+const PROOF_KEY: u64 = 1;
+const PUBLIC_KEY_KEY: u64 = 2;
+const VERIFICATION_METHOD_KEY: u64 = 3;
+const MESSAGE_COUNT_KEY: u64 = 4;
+const DISCLOSED_KEY: u64 = 5;
+const PSEUDONYM_KEY: u64 = 6;
+const PREDICATES_KEY: u64 = 7;
+const NON_REVOCATION_KEY: u64 = 8;
+const STATE_KEY: u64 = 9;
+
+fn hex_bytes(value: &str) -> Result<Value, String> {
+    hex::decode(value).map(Value::Bytes).map_err(|e| e.to_string())
+}
+
+fn integer(value: &Value) -> Result<u64, String> {
+    value.as_integer().and_then(|integer| u64::try_from(integer).ok()).ok_or("Expected an unsigned integer".to_string())
+}
+
+fn bytes_hex(value: &Value) -> Result<String, String> {
+    value.as_bytes().map(hex::encode).ok_or("Expected a byte string".to_string())
+}
+
+fn text(value: &Value) -> Result<String, String> {
+    value.as_text().map(|text| text.to_string()).ok_or("Expected a text string".to_string())
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>, String> {
+    value.as_array().ok_or("Expected an array".to_string())
+}
+
+fn pair(value: &Value) -> Result<(&Value, &Value), String> {
+    match array(value)?.as_slice() {
+        [first, second] => Ok((first, second)),
+        _ => Err("Expected a pair".to_string())
+    }
+}
+
+fn to_cbor(presentation: &Presentation, state: Option<&str>) -> Result<Value, String> {
+    let public_key = hex::decode(&presentation.public_key).map_err(|e| e.to_string())?;
+
+    let mut map = vec![
+        (Value::from(PROOF_KEY), hex_bytes(&presentation.proof)?),
+        (Value::from(PUBLIC_KEY_KEY), Value::Bytes(public_key.clone()))
+    ];
+
+    // The did:key method is derived from the public key when decoding, a null stands for it. Without a method the key is left out.
+    if let Some(method) = &presentation.verification_method {
+        let method = if *method == did::did_key_verification_method(&public_key) { Value::Null } else { Value::from(method.as_str()) };
+        map.push((Value::from(VERIFICATION_METHOD_KEY), method));
+    }
+
+    map.push((Value::from(MESSAGE_COUNT_KEY), Value::from(presentation.message_count as u64)));
+    map.push((Value::from(DISCLOSED_KEY), Value::Array(presentation.disclosed_messages.iter().map(|dm| {
+        Value::Array(vec![Value::from(dm.index as u64), Value::from(dm.value.as_str())])
+    }).collect())));
+
+    if let Some(pseudonym) = &presentation.pseudonym {
+        map.push((Value::from(PSEUDONYM_KEY), hex_bytes(pseudonym)?));
+    }
+
+    if !presentation.predicates.is_empty() {
+        let predicates = presentation.predicates.iter().map(|predicate| {
+            Ok(Value::Array(vec![Value::serialized(&predicate.predicate).map_err(|e| e.to_string())?, hex_bytes(&predicate.proof)?]))
+        }).collect::<Result<Vec<Value>, String>>()?;
+
+        map.push((Value::from(PREDICATES_KEY), Value::Array(predicates)));
+    }
+
+    if let Some(non_revocation) = &presentation.non_revocation {
+        map.push((Value::from(NON_REVOCATION_KEY), Value::Array(vec![Value::from(non_revocation.epoch), hex_bytes(&non_revocation.proof)?])));
+    }
+
+    if let Some(state) = state {
+        map.push((Value::from(STATE_KEY), Value::from(state)));
+    }
+
+    Ok(Value::Map(map))
+}
+
+fn from_cbor(value: &Value) -> Result<(Presentation, Option<String>), String> {
+    let map = value.as_map().ok_or("Presentation is not a CBOR map")?;
+    let field = |key: u64| map.iter().find(|(k, _)| integer(k).ok() == Some(key)).map(|(_, v)| v);
+    let required = |key: u64| field(key).ok_or(format!("Presentation field {} is missing", key));
+
+    let public_key = bytes_hex(required(PUBLIC_KEY_KEY)?)?;
+    let verification_method = match field(VERIFICATION_METHOD_KEY) {
+        Some(Value::Null) => Some(did::did_key_verification_method(&hex::decode(&public_key).map_err(|e| e.to_string())?)),
+        Some(method) => Some(text(method)?),
+        None => None
+    };
+
+    let presentation = Presentation {
+        proof: bytes_hex(required(PROOF_KEY)?)?,
+        public_key,
+        verification_method,
+        message_count: integer(required(MESSAGE_COUNT_KEY)?)? as usize,
+        disclosed_messages: array(required(DISCLOSED_KEY)?)?.iter().map(|disclosed| {
+            let (index, value) = pair(disclosed)?;
+
+            Ok(DisclosedMessage { index: integer(index)? as usize, value: text(value)? })
+        }).collect::<Result<Vec<DisclosedMessage>, String>>()?,
+        pseudonym: field(PSEUDONYM_KEY).map(bytes_hex).transpose()?,
+        predicates: match field(PREDICATES_KEY) {
+            Some(predicates) => array(predicates)?.iter().map(|predicate| {
+                let (statement, proof) = pair(predicate)?;
+
+                Ok(PredicateProof { predicate: statement.deserialized().map_err(|e| e.to_string())?, proof: bytes_hex(proof)? })
+            }).collect::<Result<Vec<PredicateProof>, String>>()?,
+            None => vec![]
+        },
+        non_revocation: field(NON_REVOCATION_KEY).map(|non_revocation| {
+            let (epoch, proof) = pair(non_revocation)?;
+
+            Ok::<NonRevocation, String>(NonRevocation { epoch: integer(epoch)?, proof: bytes_hex(proof)? })
+        }).transpose()?
+    };
+
+    Ok((presentation, field(STATE_KEY).map(text).transpose()?))
+}
+
+// CBOR, zlib then base45: base45 only uses the QR alphanumeric set, which packs 5.5 bits per character instead of 8 in byte mode.
+// A selective disclosure proof fits in one QR code, a range proof (32 bit proofs) does not and is rejected.
+pub fn encode_presentation(presentation: &Presentation, state: Option<&str>) -> Result<String, String> {
+    let mut cbor = vec![];
+    ciborium::into_writer(&to_cbor(presentation, state)?, &mut cbor).map_err(|e| e.to_string())?;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&cbor).map_err(|e| e.to_string())?;
+    let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+    let payload = format!("{}{}", PRESENTATION_PREFIX, base45::encode(compressed));
+
+    if payload.len() > QR_ALPHANUMERIC_CAPACITY {
+        return Err(format!("Presentation is too large for a QR code ({} characters)", payload.len()));
+    }
+
+    Ok(payload)
+}
+
+// Returns the presentation and the `state` of the request it answers, if any.
+pub fn decode_presentation(payload: &str) -> Result<(Presentation, Option<String>), String> {
+    let encoded = payload.trim().strip_prefix(PRESENTATION_PREFIX).ok_or("Not a wallet presentation QR code")?;
+    let compressed = base45::decode(encoded).map_err(|e| e.to_string())?;
+
+    let mut cbor = vec![];
+    ZlibDecoder::new(compressed.as_slice()).take(MAX_DECODED_LENGTH).read_to_end(&mut cbor).map_err(|e| e.to_string())?;
+
+    let value: Value = ciborium::from_reader(cbor.as_slice()).map_err(|e| e.to_string())?;
+
+    from_cbor(&value)
+}
+
+pub fn render_svg(data: &str) -> Result<String, String> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::L).map_err(|e| e.to_string())?;
+
+    Ok(code.render::<svg::Color>().min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE).build())
+}
+
+// Every QR code found in a PNG, JPEG or GIF file, e.g. a screenshot of a verifier page.
+pub fn decode_image(path: &Path) -> Result<Vec<String>, String> {
+    let image = image::open(path).map_err(|e| format!("{}: {}", path.display(), e))?.to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(image.width() as usize, image.height() as usize, |x, y| image.get_pixel(x as u32, y as u32).0[0]);
+
+    let contents: Vec<String> = prepared.detect_grids().iter().filter_map(|grid| grid.decode().ok()).map(|(_, content)| content).collect();
+
+    if contents.is_empty() {
+        return Err(format!("No QR code found in {}", path.display()));
+    }
+
+    Ok(contents)
+}
+
+// The first scanned content that is a verifier request, an `openid4vp://` (or https) link or a request object.
+pub fn scanned_request(contents: &[String]) -> Result<String, String> {
+    if let Some(request) = contents.iter().find(|content| oid4vp::parse_authorization_request(content).is_ok()) {
+        return Ok(request.trim().to_string());
+    }
+
+    if contents.iter().any(|content| content.starts_with(PRESENTATION_PREFIX)) {
+        return Err("QR code holds a presentation, not a verifier request".to_string());
+    }
+
+    Err("QR code is not a verifier request".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::{create_presentation, verify_presentation, BoundCredential, Predicate};
+    use crate::zkp::testing;
+
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+    const VERIFIER_ID: &str = "https://verifier.example";
+
+    fn present(predicates: &[Predicate]) -> Presentation {
+        let messages = testing::card_messages();
+        let mut credential = BoundCredential::issue_for_tests(&testing::issuer_key(), &messages);
+        credential.verification_method = Some(did::did_key_verification_method(&credential.public_key.to_bytes()));
+
+        create_presentation(&credential, &[0, 2], PRESENTATION_HEADER, Some(VERIFIER_ID), predicates, None).unwrap()
+    }
+
+    #[test]
+    fn presentation_round_trips_through_qr_payload() {
+        let presentation = present(&[]);
+
+        let payload = encode_presentation(&presentation, Some("challenge-1")).unwrap();
+        assert!(payload.len() <= QR_ALPHANUMERIC_CAPACITY);
+
+        let (decoded, state) = decode_presentation(&payload).unwrap();
+        assert_eq!(state.as_deref(), Some("challenge-1"));
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&presentation).unwrap());
+        assert!(verify_presentation(&decoded, PRESENTATION_HEADER, Some(VERIFIER_ID), &[], None, None).verified);
+
+        assert!(render_svg(&payload).unwrap().starts_with("<?xml"));
+    }
+
+    #[test]
+    fn predicates_are_encoded_but_range_proofs_exceed_a_qr_code() {
+        let presentation = present(&[Predicate::AgeOver { years: 18, date: "2026-10-19".to_string() }]);
+
+        let (decoded, _) = from_cbor(&to_cbor(&presentation, None).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&presentation).unwrap());
+
+        assert!(encode_presentation(&presentation, None).unwrap_err().starts_with("Presentation is too large for a QR code"));
+    }
+
+    #[test]
+    fn verifier_request_is_read_from_an_image_file() {
+        let request = "openid4vp://?client_id=https%3A%2F%2Fverifier.example&request_uri=https%3A%2F%2Fverifier.example%2Fchallenges%2F1%2Frequest";
+        let code = QrCode::with_error_correction_level(request.as_bytes(), EcLevel::M).unwrap();
+
+        // Rendered with a quiet zone of 4 modules, 8 pixels per module.
+        let (width, colors) = (code.width() as u32, code.to_colors());
+        let image = image::GrayImage::from_fn((width + 8) * 8, (width + 8) * 8, |x, y| {
+            let (column, row) = ((x / 8) as i64 - 4, (y / 8) as i64 - 4);
+            let dark = (0..width as i64).contains(&column) && (0..width as i64).contains(&row) && colors[(row * width as i64 + column) as usize] == qrcode::Color::Dark;
+
+            image::Luma([if dark { 0 } else { 255 }])
+        });
+
+        let path = std::env::temp_dir().join(format!("wallet-qr-test-{}.png", std::process::id()));
+        image.save(&path).unwrap();
+
+        let contents = decode_image(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(scanned_request(&contents).unwrap(), request);
+    }
+
+    #[test]
+    fn only_verifier_requests_are_accepted_from_scans() {
+        let request_object = r#"{"client_id": "https://verifier.example", "response_type": "vp_token", "nonce": "n"}"#.to_string();
+        assert_eq!(scanned_request(&["https://example.com/menu".to_string(), request_object.clone()]).unwrap(), request_object);
+
+        assert_eq!(scanned_request(&[format!("{}ABC", PRESENTATION_PREFIX)]).unwrap_err(), "QR code holds a presentation, not a verifier request");
+        assert!(scanned_request(&["hello".to_string()]).is_err());
+        assert!(decode_image(Path::new("does-not-exist.png")).is_err());
+    }
+
+    #[test]
+    fn verification_methods_are_kept_and_bad_payloads_rejected() {
+        let mut presentation = present(&[]);
+        presentation.verification_method = Some("did:web:issuer.example#key-1".to_string());
+
+        let (decoded, state) = decode_presentation(&encode_presentation(&presentation, None).unwrap()).unwrap();
+        assert_eq!(decoded.verification_method, presentation.verification_method);
+        assert_eq!(state, None);
+
+        presentation.verification_method = None;
+
+        let (decoded, _) = decode_presentation(&encode_presentation(&presentation, None).unwrap()).unwrap();
+        assert_eq!(decoded.verification_method, None);
+
+        assert!(decode_presentation("openid4vp://?client_id=x").is_err());
+        assert!(decode_presentation(&format!("{}{}", PRESENTATION_PREFIX, base45::encode(b"not zlib"))).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use bip39::{Language, Mnemonic};
+use rand_core::{OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::keys::{self, DataKey, KdfParams, WrappedKey};
+
+// 24 words, 256 bits of entropy.
+pub const RECOVERY_PHRASE_ENTROPY_LENGTH: usize = 32;
+
+pub fn generate_recovery_phrase() -> Zeroizing<String> {
+    let mut entropy = Zeroizing::new([0u8; RECOVERY_PHRASE_ENTROPY_LENGTH]);
+    OsRng.fill_bytes(entropy.as_mut());
+
+    Zeroizing::new(Mnemonic::from_entropy_in(Language::English, entropy.as_ref()).expect("32 bytes is a valid entropy length").to_string())
+}
+
+// Case and spacing do not matter, the words and the checksum do.
+pub fn normalize_recovery_phrase(phrase: &str) -> Result<Zeroizing<String>, String> {
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, &Zeroizing::new(phrase.to_lowercase())).map_err(|e| format!("Invalid recovery phrase: {}", e))?;
+
+    if mnemonic.word_count() != RECOVERY_PHRASE_ENTROPY_LENGTH * 3 / 4 {
+        return Err(format!("Invalid recovery phrase: {} words instead of {}", mnemonic.word_count(), RECOVERY_PHRASE_ENTROPY_LENGTH * 3 / 4));
+    }
+
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+// Second wrapping of the account data key, next to the one by the password.
+pub fn wrap_with_recovery_phrase(key: &DataKey, phrase: &str, params: KdfParams) -> Result<WrappedKey, String> {
+    keys::wrap_data_key(key, &normalize_recovery_phrase(phrase)?, params)
+}
+
+pub fn unwrap_with_recovery_phrase(wrapped: &WrappedKey, phrase: &str) -> Result<DataKey, String> {
+    keys::unwrap_data_key(wrapped, &normalize_recovery_phrase(phrase)?).map_err(|_| "Wrong recovery phrase".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KDF_PARAMS: KdfParams = KdfParams { memory_kib: 64, iterations: 1, parallelism: 1 };
+
+    #[test]
+    fn recovery_phrase_unwraps_the_data_key() {
+        let phrase = generate_recovery_phrase();
+        assert_eq!(phrase.split(' ').count(), 24);
+
+        let key = keys::generate_data_key();
+        let wrapped = wrap_with_recovery_phrase(&key, &phrase, TEST_KDF_PARAMS).unwrap();
+
+        assert_eq!(unwrap_with_recovery_phrase(&wrapped, &phrase).unwrap().expose(), key.expose());
+
+        // Written down and typed back in with other spacing and capitals.
+        let typed = format!("  {}\n", phrase.to_uppercase().replace(' ', "  "));
+        assert_eq!(unwrap_with_recovery_phrase(&wrapped, &typed).unwrap().expose(), key.expose());
+
+        assert_eq!(unwrap_with_recovery_phrase(&wrapped, &generate_recovery_phrase()).err(), Some("Wrong recovery phrase".to_string()));
+    }
+
+    #[test]
+    fn mistyped_phrases_are_rejected_before_unwrapping() {
+        let phrase = generate_recovery_phrase();
+        let words: Vec<&str> = phrase.split(' ').collect();
+
+        // A word outside the list, and a missing word.
+        let unknown = [&["walletx"], &words[1..]].concat().join(" ");
+        assert!(normalize_recovery_phrase(&unknown).unwrap_err().starts_with("Invalid recovery phrase"));
+
+        assert!(normalize_recovery_phrase(&words[..23].join(" ")).is_err());
+
+        // A valid 12-word phrase is not one of ours.
+        assert_eq!(normalize_recovery_phrase("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap_err(), "Invalid recovery phrase: 12 words instead of 24");
+    }
+}
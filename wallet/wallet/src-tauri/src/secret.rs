@@ -0,0 +1,180 @@
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use blstrs::Scalar;
+use ff::Field;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Deserializer};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::zkp;
+
+// Key material wiped when dropped. Deliberately neither Debug nor Serialize, and not Copy so that every copy is explicit.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes<const N: usize>([u8; N]);
+
+impl<const N: usize> SecretBytes<N> {
+    pub fn zeroed() -> Self {
+        SecretBytes([0u8; N])
+    }
+
+    pub fn random() -> Self {
+        let mut secret = Self::zeroed();
+        OsRng.fill_bytes(&mut secret.0);
+
+        secret
+    }
+
+    // Moves the bytes in and wipes the source, for values that libraries return as plain arrays.
+    pub fn take(bytes: &mut [u8; N]) -> Self {
+        let secret = SecretBytes(*bytes);
+        bytes.zeroize();
+
+        secret
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != N {
+            return Err(format!("Invalid secret length {} instead of {}", bytes.len(), N));
+        }
+
+        let mut secret = Self::zeroed();
+        secret.0.copy_from_slice(bytes);
+
+        Ok(secret)
+    }
+
+    pub fn expose(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    pub fn expose_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+}
+
+// Secret scalars such as the holder secret and BBS secret keys, wiped when dropped. Neither Copy nor Clone: blstrs
+// scalars are, so only this value is wiped, not the copies made while computing proofs.
+pub struct SecretScalar(Scalar);
+
+impl SecretScalar {
+    pub fn random() -> Self {
+        SecretScalar(zkp::random_scalar())
+    }
+
+    // Moves the scalar in and wipes the source.
+    pub fn take(scalar: &mut Scalar) -> Self {
+        let secret = SecretScalar(*scalar);
+        wipe_scalar(scalar);
+
+        secret
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        zkp::scalar_from_bytes(bytes).map(|mut scalar| Self::take(&mut scalar))
+    }
+
+    pub fn expose(&self) -> &Scalar {
+        &self.0
+    }
+}
+
+// blstrs does not implement Zeroize, so the scalar is overwritten the way zeroize does for plain values.
+fn wipe_scalar(scalar: &mut Scalar) {
+    // SAFETY: a valid Scalar is written through an exclusive reference.
+    unsafe { std::ptr::write_volatile(scalar, Scalar::ZERO) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        wipe_scalar(&mut self.0);
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+// Passwords and recovery phrases received from the frontend.
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        SecretString(Zeroizing::new(value))
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SecretString::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::DataKey;
+    use crate::zkp::signature::SecretKey;
+
+    // Fails to compile when the type implements the trait: both impls then apply and the call is ambiguous.
+    macro_rules! assert_not_impl {
+        ($type:ty: $($trait:path),+) => {
+            $({
+                trait AmbiguousIfImpl<A> { fn some_item() {} }
+
+                impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+
+                #[allow(dead_code)]
+                struct Invalid;
+
+                impl<T: ?Sized + $trait> AmbiguousIfImpl<Invalid> for T {}
+
+                let _ = <$type as AmbiguousIfImpl<_>>::some_item;
+            })+
+        };
+    }
+
+    #[test]
+    fn secrets_cannot_be_printed_or_serialized() {
+        assert_not_impl!(SecretBytes<32>: std::fmt::Debug, std::fmt::Display, serde::Serialize, Copy);
+        assert_not_impl!(DataKey: std::fmt::Debug, serde::Serialize);
+        assert_not_impl!(SecretString: std::fmt::Debug, std::fmt::Display, serde::Serialize, Clone);
+        assert_not_impl!(SecretScalar: std::fmt::Debug, serde::Serialize, Clone, Copy);
+        assert_not_impl!(SecretKey: std::fmt::Debug, serde::Serialize, Clone);
+    }
+
+    #[test]
+    fn secrets_are_wiped() {
+        let mut source = [7u8; 32];
+        let mut secret = SecretBytes::take(&mut source);
+
+        assert_eq!(source, [0u8; 32]);
+        assert_eq!(secret.expose(), &[7u8; 32]);
+
+        // What drop runs.
+        secret.zeroize();
+        assert_eq!(secret.expose(), &[0u8; 32]);
+
+        assert!(SecretBytes::<32>::from_slice(&[1u8; 31]).is_err());
+        assert_ne!(SecretBytes::<32>::random().expose(), SecretBytes::<32>::random().expose());
+
+        let mut scalar = zkp::random_scalar();
+        let mut secret = SecretScalar::take(&mut scalar);
+
+        assert_eq!(scalar, Scalar::ZERO);
+        assert_ne!(*secret.expose(), Scalar::ZERO);
+
+        secret.zeroize();
+        assert_eq!(*secret.expose(), Scalar::ZERO);
+    }
+}
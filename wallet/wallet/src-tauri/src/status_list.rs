@@ -0,0 +1,270 @@
+use std::io::{Read, Write};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Serialize, Deserialize};
+
+use crate::zkp::{self, signature::{PublicKey, SecretKey, Signature}};
+
+pub const STATUS_LIST_LENGTH: usize = 131072;
+pub const STATUS_LIST_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+pub const STATUS_LIST_ID_PREFIX: &str = "urn:wallet:status-list:";
+
+const STATUS_LIST_HEADER: &[u8] = b"wallet-status-list";
+// Lists come from files and verifiers, a few compressed bytes must not expand into gigabytes. 8 million entries.
+const MAX_LIST_BYTES: usize = 1 << 20;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusPurpose {
+    Revocation,
+    Suspension
+}
+
+impl StatusPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusPurpose::Revocation => "revocation",
+            StatusPurpose::Suspension => "suspension"
+        }
+    }
+
+    pub fn list_id(&self) -> String {
+        format!("{}{}", STATUS_LIST_ID_PREFIX, self.as_str())
+    }
+}
+
+pub struct StatusList {
+    bits: Vec<u8>
+}
+
+impl StatusList {
+    pub fn new(length: usize) -> Self {
+        StatusList { bits: vec![0; length.div_ceil(8)] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    // Index 0 is the most significant bit of the first byte, as in the W3C Bitstring Status List.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.bits.get(index / 8).map(|byte| byte & (0x80 >> (index % 8)) != 0)
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) -> Result<(), String> {
+        let byte = self.bits.get_mut(index / 8).ok_or("Status list index out of range")?;
+
+        if value {
+            *byte |= 0x80 >> (index % 8);
+        } else {
+            *byte &= !(0x80 >> (index % 8));
+        }
+
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Result<String, String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.bits).map_err(|e| e.to_string())?;
+
+        let compressed = encoder.finish().map_err(|e| e.to_string())?;
+
+        Ok(format!("u{}", URL_SAFE_NO_PAD.encode(compressed)))
+    }
+
+    pub fn decode(encoded_list: &str) -> Result<Self, String> {
+        let encoded = encoded_list.strip_prefix('u').ok_or("Encoded list must be multibase base64url")?;
+        let compressed = URL_SAFE_NO_PAD.decode(encoded).map_err(|e| e.to_string())?;
+
+        let mut bits = Vec::new();
+        GzDecoder::new(compressed.as_slice()).take(MAX_LIST_BYTES as u64 + 1).read_to_end(&mut bits).map_err(|e| e.to_string())?;
+
+        if bits.len() > MAX_LIST_BYTES {
+            return Err("Status list is too large".to_string());
+        }
+
+        Ok(StatusList { bits })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct StatusListEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    #[serde(rename = "statusPurpose")]
+    pub status_purpose: StatusPurpose,
+    #[serde(rename = "statusListIndex")]
+    pub status_list_index: String,
+    #[serde(rename = "statusListCredential")]
+    pub status_list_credential: String
+}
+
+impl StatusListEntry {
+    pub fn new(purpose: StatusPurpose, index: usize) -> Self {
+        StatusListEntry {
+            id: format!("{}#{}", purpose.list_id(), index),
+            entry_type: "BitstringStatusListEntry".to_string(),
+            status_purpose: purpose,
+            status_list_index: index.to_string(),
+            status_list_credential: purpose.list_id()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct StatusListSubject {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub subject_type: String,
+    #[serde(rename = "statusPurpose")]
+    pub status_purpose: StatusPurpose,
+    #[serde(rename = "encodedList")]
+    pub encoded_list: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct StatusListCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub credential_type: Vec<String>,
+    pub issuer: String,
+    #[serde(rename = "credentialSubject")]
+    pub credential_subject: StatusListSubject,
+    pub proof: String
+}
+
+fn status_list_messages(id: &str, purpose: StatusPurpose, encoded_list: &str) -> Vec<blstrs::Scalar> {
+    zkp::messages_to_scalars(&[id, purpose.as_str(), encoded_list])
+}
+
+impl StatusListCredential {
+    pub fn issue(secret_key: &SecretKey, purpose: StatusPurpose, list: &StatusList) -> Result<Self, String> {
+        let public_key = secret_key.public_key();
+        let id = purpose.list_id();
+        let encoded_list = list.encode()?;
+
+        let signature = zkp::signature::sign(secret_key, &public_key, STATUS_LIST_HEADER, &status_list_messages(&id, purpose, &encoded_list), None)?;
+
+        Ok(StatusListCredential {
+            context: vec![STATUS_LIST_CONTEXT.to_string()],
+            id: id.clone(),
+            credential_type: vec!["VerifiableCredential".to_string(), "BitstringStatusListCredential".to_string()],
+            issuer: hex::encode(public_key.to_bytes()),
+            credential_subject: StatusListSubject {
+                id: format!("{}#list", id),
+                subject_type: "BitstringStatusList".to_string(),
+                status_purpose: purpose,
+                encoded_list
+            },
+            proof: hex::encode(signature.to_bytes())
+        })
+    }
+
+    pub fn verify(&self, public_key: &PublicKey) -> bool {
+        if self.issuer != hex::encode(public_key.to_bytes()) {
+            return false;
+        }
+
+        let Ok(signature) = hex::decode(&self.proof).map_err(|e| e.to_string()).and_then(|bytes| Signature::from_bytes(&bytes)) else {
+            return false;
+        };
+
+        let subject = &self.credential_subject;
+
+        zkp::signature::verify(public_key, STATUS_LIST_HEADER, &status_list_messages(&self.id, subject.status_purpose, &subject.encoded_list), &[], &signature)
+    }
+
+    pub fn list(&self) -> Result<StatusList, String> {
+        StatusList::decode(&self.credential_subject.encoded_list)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CredentialStatus {
+    pub revoked: bool,
+    pub suspended: bool
+}
+
+pub fn check_status(public_key: &PublicKey, index: usize, lists: &[StatusListCredential]) -> Result<CredentialStatus, String> {
+    let mut status = CredentialStatus::default();
+
+    for purpose in [StatusPurpose::Revocation, StatusPurpose::Suspension] {
+        let credential = lists.iter().find(|c| c.id == purpose.list_id()).ok_or_else(|| format!("Missing {} status list", purpose.as_str()))?;
+
+        if credential.credential_subject.status_purpose != purpose || !credential.verify(public_key) {
+            return Err(format!("Invalid {} status list", purpose.as_str()));
+        }
+
+        let value = credential.list()?.get(index).ok_or("Status list index out of range")?;
+
+        match purpose {
+            StatusPurpose::Revocation => status.revoked = value,
+            StatusPurpose::Suspension => status.suspended = value
+        }
+    }
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::testing::issuer_key;
+
+
+    #[test]
+    fn status_list_roundtrip_uses_msb_first_bit_order() {
+        let mut list = StatusList::new(STATUS_LIST_LENGTH);
+        list.set(0, true).unwrap();
+        list.set(9, true).unwrap();
+
+        assert!(list.set(STATUS_LIST_LENGTH, true).is_err());
+
+        let decoded = StatusList::decode(&list.encode().unwrap()).unwrap();
+
+        assert_eq!(decoded.len(), STATUS_LIST_LENGTH);
+        assert_eq!(decoded.bits[0], 0x80);
+        assert_eq!(decoded.bits[1], 0x40);
+        assert_eq!(decoded.get(9), Some(true));
+        assert_eq!(decoded.get(10), Some(false));
+
+        // A list that decompresses past the limit is refused, whatever its compressed size.
+        assert_eq!(StatusList::decode(&StatusList::new(8 * MAX_LIST_BYTES).encode().unwrap()).unwrap().len(), 8 * MAX_LIST_BYTES);
+        assert!(StatusList::decode(&StatusList::new(8 * MAX_LIST_BYTES + 8).encode().unwrap()).is_err());
+    }
+
+    #[test]
+    fn check_status_reads_signed_lists() {
+        let secret_key = issuer_key();
+        let public_key = secret_key.public_key();
+
+        let mut revocation = StatusList::new(STATUS_LIST_LENGTH);
+        revocation.set(3, true).unwrap();
+
+        let mut suspension = StatusList::new(STATUS_LIST_LENGTH);
+        suspension.set(4, true).unwrap();
+
+        let lists = vec![
+            StatusListCredential::issue(&secret_key, StatusPurpose::Revocation, &revocation).unwrap(),
+            StatusListCredential::issue(&secret_key, StatusPurpose::Suspension, &suspension).unwrap()
+        ];
+
+        assert_eq!(check_status(&public_key, 3, &lists), Ok(CredentialStatus { revoked: true, suspended: false }));
+        assert_eq!(check_status(&public_key, 4, &lists), Ok(CredentialStatus { revoked: false, suspended: true }));
+        assert_eq!(check_status(&public_key, 5, &lists), Ok(CredentialStatus::default()));
+
+        let mut tampered = lists.clone();
+        tampered[0].credential_subject.encoded_list = StatusList::new(STATUS_LIST_LENGTH).encode().unwrap();
+
+        assert!(check_status(&public_key, 3, &tampered).is_err());
+        assert!(check_status(&public_key, 3, &lists[..1]).is_err());
+    }
+}
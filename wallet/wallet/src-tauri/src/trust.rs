@@ -0,0 +1,204 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use crate::validity::{self, Clock, ValidityError};
+
+// Type of the cards the wallet creates and receives, as named in the issuer credential configurations.
+pub const IDENTITY_CARD_TYPE: &str = "IdentityCard";
+
+const PUBLIC_KEY_LENGTH: usize = 96;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TrustedIssuer {
+    pub id: String,
+    pub name: String,
+    // Hex encoded BBS public key, the same issuer may be listed once per key when it rotates them.
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+    // Credential types the issuer is trusted for, any type when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub credential_types: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct TrustRegistry {
+    #[serde(default)]
+    pub issuers: Vec<TrustedIssuer>
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum IssuerTrust {
+    Trusted { id: String, name: String },
+    Unknown,
+    NotYetValid { id: String, name: String, valid_from: String },
+    Expired { id: String, name: String, valid_until: String },
+    TypeNotAllowed { id: String, name: String, credential_type: String }
+}
+
+impl IssuerTrust {
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, IssuerTrust::Trusted { .. })
+    }
+}
+
+impl fmt::Display for IssuerTrust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssuerTrust::Trusted { name, .. } => write!(f, "Signed by trusted issuer {}", name),
+            IssuerTrust::Unknown => write!(f, "Unknown issuer"),
+            IssuerTrust::NotYetValid { name, valid_from, .. } => write!(f, "Issuer {} is not trusted before {}", name, valid_from),
+            IssuerTrust::Expired { name, valid_until, .. } => write!(f, "Issuer {} is no longer trusted since {}", name, valid_until),
+            IssuerTrust::TypeNotAllowed { name, credential_type, .. } => write!(f, "Issuer {} is not trusted for {}", name, credential_type)
+        }
+    }
+}
+
+impl TrustedIssuer {
+    fn trust(&self, credential_type: &str, clock: &dyn Clock) -> IssuerTrust {
+        let (id, name) = (self.id.clone(), self.name.clone());
+
+        // Dates are checked when the issuer is added, a malformed entry is never trusted.
+        let Ok(valid_from) = self.valid_from.as_deref().map(validity::parse_date).transpose() else {
+            return IssuerTrust::Unknown;
+        };
+
+        if let (Some(from), Some(valid_from)) = (valid_from, &self.valid_from) {
+            if clock.today() < from {
+                return IssuerTrust::NotYetValid { id, name, valid_from: valid_from.clone() };
+            }
+        }
+
+        match self.valid_until.as_deref().map_or(Ok(()), |valid_until| validity::check_expiry_date(valid_until, clock)) {
+            Err(ValidityError::Expired { expiry_date }) => IssuerTrust::Expired { id, name, valid_until: expiry_date },
+            Err(ValidityError::InvalidDate { .. } | ValidityError::ExpiryNotProven) => IssuerTrust::Unknown,
+            Ok(()) if !self.credential_types.is_empty() && !self.credential_types.iter().any(|allowed| allowed == credential_type) => {
+                IssuerTrust::TypeNotAllowed { id, name, credential_type: credential_type.to_string() }
+            },
+            Ok(()) => IssuerTrust::Trusted { id, name }
+        }
+    }
+}
+
+impl TrustRegistry {
+    // An entry currently trusted for the type wins over other entries of the same key, such as a rotated out period.
+    pub fn check(&self, public_key: &str, credential_type: &str, clock: &dyn Clock) -> IssuerTrust {
+        let statuses: Vec<IssuerTrust> = self.issuers.iter()
+            .filter(|issuer| issuer.public_key.eq_ignore_ascii_case(public_key.trim()))
+            .map(|issuer| issuer.trust(credential_type, clock))
+            .collect();
+
+        statuses.iter().find(|status| status.is_trusted()).or(statuses.first()).cloned().unwrap_or(IssuerTrust::Unknown)
+    }
+
+    pub fn add(&mut self, issuer: TrustedIssuer) -> Result<(), String> {
+        if issuer.id.trim().is_empty() || issuer.name.trim().is_empty() {
+            return Err("Issuer id and name are required".to_string());
+        }
+
+        if hex::decode(&issuer.public_key).map(|bytes| bytes.len()) != Ok(PUBLIC_KEY_LENGTH) {
+            return Err("Issuer public key must be a hex encoded BBS public key".to_string());
+        }
+
+        if self.issuers.iter().any(|existing| existing.id == issuer.id && existing.public_key.eq_ignore_ascii_case(&issuer.public_key)) {
+            return Err(format!("Issuer {} already trusts this key", issuer.id));
+        }
+
+        let valid_from = issuer.valid_from.as_deref().map(validity::parse_date).transpose().map_err(|error| error.to_string())?;
+        let valid_until = issuer.valid_until.as_deref().map(validity::parse_date).transpose().map_err(|error| error.to_string())?;
+
+        if let (Some(from), Some(until)) = (valid_from, valid_until) {
+            if from >= until {
+                return Err("Issuer trust period is empty".to_string());
+            }
+        }
+
+        self.issuers.push(issuer);
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        let count = self.issuers.len();
+        self.issuers.retain(|issuer| issuer.id != id);
+
+        if self.issuers.len() == count {
+            return Err(format!("Unknown issuer {}", id));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use crate::validity::FixedClock;
+
+    fn clock() -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())
+    }
+
+    fn key(byte: u8) -> String {
+        hex::encode([byte; PUBLIC_KEY_LENGTH])
+    }
+
+    fn issuer(id: &str, public_key: String) -> TrustedIssuer {
+        TrustedIssuer {
+            id: id.to_string(),
+            name: format!("{} ministry", id),
+            public_key,
+            valid_from: None,
+            valid_until: None,
+            credential_types: vec![]
+        }
+    }
+
+    #[test]
+    fn registry_reports_the_trusted_issuer_or_why_it_is_not() {
+        let mut registry = TrustRegistry::default();
+
+        registry.add(TrustedIssuer { credential_types: vec![IDENTITY_CARD_TYPE.to_string()], ..issuer("fr", key(1)) }).unwrap();
+        registry.add(TrustedIssuer { valid_until: Some("2026-01-01".to_string()), ..issuer("de", key(2)) }).unwrap();
+        registry.add(TrustedIssuer { valid_from: Some("2027-01-01".to_string()), ..issuer("it", key(3)) }).unwrap();
+
+        assert_eq!(registry.check(&key(1).to_uppercase(), IDENTITY_CARD_TYPE, &clock()), IssuerTrust::Trusted { id: "fr".to_string(), name: "fr ministry".to_string() });
+        assert_eq!(registry.check(&key(1), "DrivingLicence", &clock()), IssuerTrust::TypeNotAllowed { id: "fr".to_string(), name: "fr ministry".to_string(), credential_type: "DrivingLicence".to_string() });
+        assert_eq!(registry.check(&key(2), IDENTITY_CARD_TYPE, &clock()).to_string(), "Issuer de ministry is no longer trusted since 2026-01-01");
+        assert_eq!(registry.check(&key(3), IDENTITY_CARD_TYPE, &clock()).to_string(), "Issuer it ministry is not trusted before 2027-01-01");
+        assert_eq!(registry.check(&key(4), IDENTITY_CARD_TYPE, &clock()), IssuerTrust::Unknown);
+    }
+
+    #[test]
+    fn rotated_keys_keep_their_own_trust_period() {
+        let mut registry = TrustRegistry::default();
+
+        registry.add(TrustedIssuer { valid_until: Some("2026-06-01".to_string()), ..issuer("fr", key(1)) }).unwrap();
+        registry.add(TrustedIssuer { valid_from: Some("2026-06-01".to_string()), ..issuer("fr", key(2)) }).unwrap();
+
+        assert!(!registry.check(&key(1), IDENTITY_CARD_TYPE, &clock()).is_trusted());
+        assert!(registry.check(&key(2), IDENTITY_CARD_TYPE, &clock()).is_trusted());
+
+        registry.remove("fr").unwrap();
+        assert!(registry.issuers.is_empty());
+        assert!(registry.remove("fr").is_err());
+    }
+
+    #[test]
+    fn malformed_entries_are_rejected() {
+        let mut registry = TrustRegistry::default();
+
+        assert!(registry.add(issuer("fr", "abcd".to_string())).is_err());
+        assert!(registry.add(issuer("", key(1))).is_err());
+        assert!(registry.add(TrustedIssuer { valid_from: Some("01/01/2026".to_string()), ..issuer("fr", key(1)) }).is_err());
+        assert!(registry.add(TrustedIssuer { valid_from: Some("2027-01-01".to_string()), valid_until: Some("2026-01-01".to_string()), ..issuer("fr", key(1)) }).is_err());
+
+        registry.add(issuer("fr", key(1))).unwrap();
+        assert!(registry.add(issuer("fr", key(1))).is_err());
+    }
+}
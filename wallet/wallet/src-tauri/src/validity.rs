@@ -0,0 +1,120 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::presentation::{Predicate, EXPIRY_DATE_INDEX};
+use crate::DisclosedMessage;
+
+pub(crate) const DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValidityError {
+    Expired { expiry_date: String },
+    InvalidDate { value: String },
+    ExpiryNotProven
+}
+
+impl fmt::Display for ValidityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidityError::Expired { expiry_date } => write!(f, "Credential expired on {}", expiry_date),
+            ValidityError::InvalidDate { value } => write!(f, "Invalid validity date: {}", value),
+            ValidityError::ExpiryNotProven => write!(f, "Credential neither discloses its expiry date nor proves it is not expired")
+        }
+    }
+}
+
+pub(crate) fn parse_date(value: &str) -> Result<NaiveDate, ValidityError> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT).map_err(|_| ValidityError::InvalidDate { value: value.to_string() })
+}
+
+// Cards carry no issuance date, only an expiry date: they are valid until the day before it.
+pub fn check_expiry_date(expiry_date: &str, clock: &dyn Clock) -> Result<(), ValidityError> {
+    if clock.today() >= parse_date(expiry_date)? {
+        return Err(ValidityError::Expired { expiry_date: expiry_date.to_string() });
+    }
+
+    Ok(())
+}
+
+pub fn check_messages_validity(messages: &[String], clock: &dyn Clock) -> Result<(), ValidityError> {
+    messages.get(EXPIRY_DATE_INDEX).map_or(Ok(()), |expiry_date| check_expiry_date(expiry_date, clock))
+}
+
+// A hidden expiry date is only accepted with a proof that the card is not expired today or later.
+pub fn check_disclosed_validity(disclosed_messages: &[DisclosedMessage], proven_predicates: &[Predicate], clock: &dyn Clock) -> Result<(), ValidityError> {
+    if let Some(expiry_date) = disclosed_messages.iter().find(|dm| dm.index == EXPIRY_DATE_INDEX) {
+        return check_expiry_date(&expiry_date.value, clock);
+    }
+
+    let today = clock.today();
+
+    if proven_predicates.iter().any(|predicate| matches!(predicate, Predicate::NotExpired { date } if parse_date(date).is_ok_and(|date| date >= today))) {
+        Ok(())
+    } else {
+        Err(ValidityError::ExpiryNotProven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn clock(year: i32, month: u32, day: u32) -> FixedClock {
+        FixedClock(Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn expiry_date_is_exclusive() {
+        assert_eq!(check_expiry_date("2030-01-01", &clock(2029, 12, 31)), Ok(()));
+        assert_eq!(check_expiry_date("2030-01-01", &clock(2030, 1, 1)), Err(ValidityError::Expired { expiry_date: "2030-01-01".to_string() }));
+    }
+
+    #[test]
+    fn malformed_dates_are_rejected() {
+        assert_eq!(check_expiry_date("31/12/2030", &clock(2026, 10, 18)), Err(ValidityError::InvalidDate { value: "31/12/2030".to_string() }));
+    }
+
+    #[test]
+    fn expiry_date_has_to_be_disclosed_or_proven() {
+        let today = clock(2026, 10, 18);
+        let disclosed = |value: &str| vec![DisclosedMessage { index: EXPIRY_DATE_INDEX, value: value.to_string() }];
+        let not_expired = |date: &str| vec![Predicate::NotExpired { date: date.to_string() }];
+
+        assert!(matches!(check_disclosed_validity(&disclosed("2020-01-01"), &[], &today), Err(ValidityError::Expired { .. })));
+        assert_eq!(check_disclosed_validity(&disclosed("2030-01-01"), &[], &today), Ok(()));
+
+        assert_eq!(check_disclosed_validity(&[], &[], &today), Err(ValidityError::ExpiryNotProven));
+        assert_eq!(check_disclosed_validity(&[], &not_expired("2026-10-18"), &today), Ok(()));
+        assert_eq!(check_disclosed_validity(&[], &not_expired("2026-12-31"), &today), Ok(()));
+        assert_eq!(check_disclosed_validity(&[], &not_expired("2026-10-17"), &today), Err(ValidityError::ExpiryNotProven));
+        assert_eq!(check_disclosed_validity(&[], &[Predicate::AgeOver { years: 18, date: "2026-10-18".to_string() }], &today), Err(ValidityError::ExpiryNotProven));
+    }
+}
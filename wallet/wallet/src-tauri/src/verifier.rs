@@ -0,0 +1,581 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path as FilePath, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::{Path, State}, http::{header, StatusCode}, routing::{get, post}, Form, Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::did::DidResolver;
+use crate::oid4vci::{claim_index, ErrorResponse, CLAIM_NAMES, CREDENTIAL_FORMAT};
+use crate::oid4vp::{self, AuthorizationRequest, ClaimsQuery, CredentialQuery, DcqlQuery, ResponseResult, DIRECT_POST, RESPONSE_TYPE};
+use crate::presentation::{self, AccumulatorState, Presentation, STATUS_INDEX};
+use crate::qr;
+use crate::status_list::StatusListCredential;
+use crate::trust::{IssuerTrust, TrustRegistry};
+use crate::validity::{Clock, SystemClock};
+use crate::zkp::{accumulator::Accumulator, signature::PublicKey};
+
+pub const CREDENTIAL_QUERY_ID: &str = "identity_card";
+pub const CHALLENGE_LIFETIME_MINUTES: i64 = 5;
+// Challenges are created without authentication, so their number is bounded and expired ones are dropped.
+pub const MAX_SESSIONS: usize = 10_000;
+
+pub struct VerifierConfig {
+    // Client identifier of the verifier, bound into every proof together with the challenge nonce.
+    pub verifier_id: String,
+    // Address the wallet reaches the service at, used to build request and response URIs.
+    pub base_url: String,
+    // Issuers whose cards are accepted, with their keys, trust periods and credential types.
+    pub trusted_issuers: TrustRegistry,
+    // Local copies of did:web documents, looked up before fetching them.
+    pub did_documents: Option<PathBuf>,
+    // Fields every presentation must disclose when a challenge does not list its own.
+    pub required_fields: Vec<String>,
+    // Revocation and suspension status lists of the trusted issuers, read for every presentation so that a new list applies at once.
+    pub status_lists: Option<PathBuf>,
+    // Published accumulator that non-revocation proofs are checked against, read the same way.
+    pub accumulator: Option<PathBuf>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ChallengeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_fields: Option<Vec<String>>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Challenge {
+    pub id: String,
+    pub verifier_id: String,
+    pub nonce: String,
+    pub required_fields: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+    pub request_uri: String,
+    // `openid4vp://` link to hand over to the wallet, as a QR code or deep link.
+    pub authorization_request: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerificationResult {
+    pub challenge_id: String,
+    pub verified: bool,
+    pub issuer: IssuerTrust,
+    pub disclosed: BTreeMap<String, String>,
+    pub pseudonym: Option<String>,
+    pub missing_fields: Vec<String>,
+    pub error: Option<String>
+}
+
+struct Session {
+    challenge: Challenge,
+    result: Option<VerificationResult>
+}
+
+struct VerifierState {
+    config: VerifierConfig,
+    clock: Box<dyn Clock + Send + Sync>,
+    resolver: DidResolver,
+    sessions: Mutex<HashMap<String, Session>>
+}
+
+type SharedState = Arc<VerifierState>;
+
+// Same format as the wallet's `fetch_accumulator` command.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublishedAccumulator {
+    epoch: u64,
+    value: String,
+    public_key: String
+}
+
+struct Revocation {
+    status_lists: Option<Vec<StatusListCredential>>,
+    accumulator: Option<AccumulatorState>
+}
+
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+fn api_error(status: StatusCode, error: &str, description: String) -> ApiError {
+    (status, Json(ErrorResponse { error: error.to_string(), error_description: Some(description) }))
+}
+
+fn authorization_request(challenge: &Challenge, base_url: &str) -> AuthorizationRequest {
+    AuthorizationRequest {
+        client_id: challenge.verifier_id.clone(),
+        response_type: RESPONSE_TYPE.to_string(),
+        response_mode: Some(DIRECT_POST.to_string()),
+        response_uri: Some(format!("{}/challenges/{}/response", base_url, challenge.id)),
+        nonce: challenge.nonce.clone(),
+        state: Some(challenge.id.clone()),
+        presentation_definition: None,
+        presentation_definition_uri: None,
+        dcql_query: Some(DcqlQuery {
+            credentials: vec![CredentialQuery {
+                id: CREDENTIAL_QUERY_ID.to_string(),
+                format: CREDENTIAL_FORMAT.to_string(),
+                claims: Some(challenge.required_fields.iter().map(|field| ClaimsQuery {
+                    id: None,
+                    path: vec![Value::from("credentialSubject"), Value::from(field.as_str())],
+                    values: None
+                }).collect())
+            }]
+        })
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &FilePath) -> Result<T, String> {
+    serde_json::from_str(&std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn load_revocation(config: &VerifierConfig) -> Result<Revocation, String> {
+    let status_lists = config.status_lists.as_deref().map(read_json).transpose()?;
+
+    let accumulator = match config.accumulator.as_deref() {
+        Some(path) => {
+            let published: PublishedAccumulator = read_json(path)?;
+
+            Some(AccumulatorState {
+                public_key: PublicKey::from_bytes(&hex::decode(&published.public_key).map_err(|e| e.to_string())?)?,
+                accumulator: Accumulator::from_bytes(&hex::decode(&published.value).map_err(|e| e.to_string())?)?,
+                epoch: published.epoch
+            })
+        },
+        None => None
+    };
+
+    Ok(Revocation { status_lists, accumulator })
+}
+
+// Checks the proof against the challenge nonce and the revocation data, the issuer against the trusted keys and its DID, then the validity dates and required fields.
+fn verify(state: &VerifierState, challenge: &Challenge, presentation: &Presentation, verification_method: Result<(), String>, revocation: &Result<Revocation, String>) -> VerificationResult {
+    let (status_lists, accumulator) = match revocation {
+        // Without a non-revocation proof the card is checked on the status lists when there are some, otherwise the accumulator requires the proof.
+        Ok(Revocation { status_lists: Some(lists), .. }) if presentation.non_revocation.is_none() => (Some(lists.as_slice()), None),
+        Ok(revocation) => (revocation.status_lists.as_deref(), revocation.accumulator.as_ref()),
+        Err(_) => (None, None)
+    };
+
+    let mut result = presentation::verify_presentation(presentation, &oid4vp::presentation_header(&challenge.verifier_id, &challenge.nonce), Some(&challenge.verifier_id), &[], status_lists, accumulator);
+    presentation::check_presentation_validity(&mut result, state.clock.as_ref());
+    presentation::check_presentation_issuer(&mut result, presentation, &state.config.trusted_issuers, state.clock.as_ref());
+
+    let issuer = result.issuer.clone().unwrap_or(IssuerTrust::Unknown);
+
+    // The proof rejects repeated indices too, the service does not rely on it to report a single value per field.
+    let mut indices: Vec<usize> = presentation.disclosed_messages.iter().map(|dm| dm.index).collect();
+    indices.sort_unstable();
+    let repeated_index = indices.windows(2).any(|pair| pair[0] == pair[1]);
+
+    let disclosed: BTreeMap<String, String> = result.disclosed_messages.iter()
+        .filter(|_| !repeated_index)
+        .filter_map(|dm| CLAIM_NAMES.get(dm.index).map(|name| (name.to_string(), dm.value.clone())))
+        .collect();
+
+    let missing_fields: Vec<String> = challenge.required_fields.iter().filter(|field| !disclosed.contains_key(*field)).cloned().collect();
+
+    let error = if state.clock.now() > challenge.expires_at {
+        Some("Challenge expired".to_string())
+    } else if repeated_index {
+        Some("Disclosed messages repeat a field".to_string())
+    } else if let Err(error) = revocation {
+        Some(error.clone())
+    } else if !issuer.is_trusted() {
+        Some(issuer.to_string())
+    } else if let Err(error) = verification_method {
+        Some(error)
+    } else if result.status.is_some_and(|status| status.revoked) {
+        Some("Card revoked".to_string())
+    } else if result.status.is_some_and(|status| status.suspended) {
+        Some("Card suspended".to_string())
+    } else if !result.verified {
+        Some(result.error.map(|error| error.to_string()).unwrap_or_else(|| "Invalid presentation proof".to_string()))
+    } else if !missing_fields.is_empty() {
+        Some(format!("Missing required fields: {}", missing_fields.join(", ")))
+    } else {
+        None
+    };
+
+    VerificationResult {
+        challenge_id: challenge.id.clone(),
+        verified: error.is_none(),
+        issuer,
+        pseudonym: if error.is_none() { result.pseudonym } else { None },
+        disclosed,
+        missing_fields,
+        error
+    }
+}
+
+// A challenge is used up by its first valid presentation, so that it cannot be replayed. The challenge id is public,
+// a failed attempt only records its result and leaves the challenge open for the holder.
+fn answer_challenge(state: &VerifierState, id: &str, presentation: &Presentation, verification_method: Result<(), String>) -> Result<VerificationResult, ApiError> {
+    let revocation = load_revocation(&state.config);
+
+    let mut sessions = state.sessions.lock().unwrap();
+
+    let session = sessions.get_mut(id).ok_or_else(|| api_error(StatusCode::NOT_FOUND, "unknown_challenge", format!("No challenge {}", id)))?;
+
+    if session.result.as_ref().is_some_and(|result| result.verified) {
+        return Err(api_error(StatusCode::CONFLICT, "challenge_used", "Challenge was already answered".to_string()));
+    }
+
+    let result = verify(state, &session.challenge, presentation, verification_method, &revocation);
+    session.result = Some(result.clone());
+
+    Ok(result)
+}
+
+async fn create_challenge(State(state): State<SharedState>, request: Option<Json<ChallengeRequest>>) -> Result<Json<Challenge>, ApiError> {
+    let mut required_fields = request.and_then(|Json(request)| request.required_fields).unwrap_or_else(|| state.config.required_fields.clone());
+
+    if let Some(field) = required_fields.iter().find(|field| claim_index(field).is_none()) {
+        return Err(api_error(StatusCode::BAD_REQUEST, "invalid_request", format!("Unknown field {}", field)));
+    }
+
+    // Wallets answer OpenID4VP requests without a non-revocation proof, the card is then looked up in the status lists.
+    if state.config.status_lists.is_some() && !required_fields.iter().any(|field| field == CLAIM_NAMES[STATUS_INDEX]) {
+        required_fields.push(CLAIM_NAMES[STATUS_INDEX].to_string());
+    }
+
+    let id = hex::encode(crate::zkp::random_scalar().to_bytes_be());
+    let request_uri = format!("{}/challenges/{}/request", state.config.base_url, id);
+
+    let authorization_request = format!(
+        "openid4vp://?client_id={}&request_uri={}",
+        url::form_urlencoded::byte_serialize(state.config.verifier_id.as_bytes()).collect::<String>(),
+        url::form_urlencoded::byte_serialize(request_uri.as_bytes()).collect::<String>()
+    );
+
+    let challenge = Challenge {
+        id: id.clone(),
+        verifier_id: state.config.verifier_id.clone(),
+        nonce: hex::encode(crate::zkp::random_scalar().to_bytes_be()),
+        required_fields,
+        expires_at: state.clock.now() + Duration::minutes(CHALLENGE_LIFETIME_MINUTES),
+        request_uri,
+        authorization_request
+    };
+
+    let mut sessions = state.sessions.lock().unwrap();
+
+    // Results stay readable for one more lifetime after the challenge expires.
+    let now = state.clock.now();
+    sessions.retain(|_, session| now <= session.challenge.expires_at + Duration::minutes(CHALLENGE_LIFETIME_MINUTES));
+
+    if sessions.len() >= MAX_SESSIONS {
+        return Err(api_error(StatusCode::SERVICE_UNAVAILABLE, "temporarily_unavailable", "Too many pending challenges".to_string()));
+    }
+
+    sessions.insert(id, Session { challenge: challenge.clone(), result: None });
+
+    Ok(Json(challenge))
+}
+
+async fn request_object(State(state): State<SharedState>, Path(id): Path<String>) -> Result<Json<AuthorizationRequest>, ApiError> {
+    let sessions = state.sessions.lock().unwrap();
+    let session = sessions.get(&id).ok_or_else(|| api_error(StatusCode::NOT_FOUND, "unknown_challenge", format!("No challenge {}", id)))?;
+
+    Ok(Json(authorization_request(&session.challenge, &state.config.base_url)))
+}
+
+// OpenID4VP `direct_post` endpoint, answering the DCQL query of the challenge.
+async fn direct_post(State(state): State<SharedState>, Path(id): Path<String>, Form(form): Form<HashMap<String, String>>) -> Result<Json<ResponseResult>, ApiError> {
+    if form.get("state") != Some(&id) {
+        return Err(api_error(StatusCode::BAD_REQUEST, "invalid_request", "State does not match the challenge".to_string()));
+    }
+
+    let presentation: Presentation = form.get("vp_token")
+        .and_then(|token| serde_json::from_str::<Value>(token).ok())
+        .and_then(|token| token.get(CREDENTIAL_QUERY_ID).and_then(|presentations| presentations.get(0)).cloned())
+        .and_then(|presentation| serde_json::from_value(presentation).ok())
+        .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "invalid_request", format!("vp_token has no presentation for {}", CREDENTIAL_QUERY_ID)))?;
+
+    // Resolved before taking the sessions lock, a did:web document may have to be fetched.
+    let verification_method = presentation::check_verification_method(&presentation, &state.resolver).await;
+    let result = answer_challenge(&state, &id, &presentation, verification_method)?;
+
+    match result.error {
+        Some(error) => Err(api_error(StatusCode::BAD_REQUEST, "invalid_presentation", error)),
+        None => Ok(Json(ResponseResult::default()))
+    }
+}
+
+async fn submit_presentation(State(state): State<SharedState>, Path(id): Path<String>, Json(presentation): Json<Presentation>) -> Result<Json<VerificationResult>, ApiError> {
+    let verification_method = presentation::check_verification_method(&presentation, &state.resolver).await;
+    answer_challenge(&state, &id, &presentation, verification_method).map(Json)
+}
+
+// QR code of the `openid4vp://` link, for a wallet on another device to scan.
+async fn challenge_qr_code(State(state): State<SharedState>, Path(id): Path<String>) -> Result<([(header::HeaderName, &'static str); 1], String), ApiError> {
+    let sessions = state.sessions.lock().unwrap();
+    let session = sessions.get(&id).ok_or_else(|| api_error(StatusCode::NOT_FOUND, "unknown_challenge", format!("No challenge {}", id)))?;
+
+    let svg = qr::render_svg(&session.challenge.authorization_request).map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error", e))?;
+
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], svg))
+}
+
+// Text scanned from the QR code shown by the wallet, the offline counterpart of `direct_post`.
+async fn submit_qr_code(State(state): State<SharedState>, Path(id): Path<String>, payload: String) -> Result<Json<VerificationResult>, ApiError> {
+    let (presentation, presentation_state) = qr::decode_presentation(&payload).map_err(|e| api_error(StatusCode::BAD_REQUEST, "invalid_request", e))?;
+
+    if presentation_state.is_some_and(|presentation_state| presentation_state != id) {
+        return Err(api_error(StatusCode::BAD_REQUEST, "invalid_request", "State does not match the challenge".to_string()));
+    }
+
+    let verification_method = presentation::check_verification_method(&presentation, &state.resolver).await;
+    answer_challenge(&state, &id, &presentation, verification_method).map(Json)
+}
+
+async fn challenge_result(State(state): State<SharedState>, Path(id): Path<String>) -> Result<Json<VerificationResult>, ApiError> {
+    let sessions = state.sessions.lock().unwrap();
+    let session = sessions.get(&id).ok_or_else(|| api_error(StatusCode::NOT_FOUND, "unknown_challenge", format!("No challenge {}", id)))?;
+
+    session.result.clone().map(Json).ok_or_else(|| api_error(StatusCode::NOT_FOUND, "pending", "Challenge has not been answered yet".to_string()))
+}
+
+fn router_with_clock(config: VerifierConfig, clock: Box<dyn Clock + Send + Sync>) -> Router {
+    let resolver = DidResolver::new(reqwest::Client::new(), config.did_documents.clone());
+    let state = Arc::new(VerifierState { config, clock, resolver, sessions: Mutex::new(HashMap::new()) });
+
+    Router::new()
+        .route("/challenges", post(create_challenge))
+        .route("/challenges/{id}/request", get(request_object))
+        .route("/challenges/{id}/response", post(direct_post))
+        .route("/challenges/{id}/presentation", post(submit_presentation))
+        .route("/challenges/{id}/qr", get(challenge_qr_code).post(submit_qr_code))
+        .route("/challenges/{id}/result", get(challenge_result))
+        .with_state(state)
+}
+
+pub fn router(config: VerifierConfig) -> Router {
+    router_with_clock(config, Box::new(SystemClock))
+}
+
+pub async fn serve(listener: tokio::net::TcpListener, config: VerifierConfig) -> std::io::Result<()> {
+    axum::serve(listener, router(config)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::did;
+    use crate::presentation::BoundCredential;
+    use crate::trust::{TrustedIssuer, IDENTITY_CARD_TYPE};
+    use crate::validity::FixedClock;
+    use crate::DisclosedMessage;
+    use crate::zkp::{signature::SecretKey, testing::{card_messages, issuer_key, other_issuer_key}};
+
+    async fn start_verifier(trusted_key: &SecretKey, status_lists: Option<PathBuf>) -> (String, reqwest::Client) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let config = VerifierConfig {
+            verifier_id: url.clone(),
+            base_url: url.clone(),
+            trusted_issuers: TrustRegistry {
+                issuers: vec![TrustedIssuer {
+                    id: "https://issuer.example".to_string(),
+                    name: "Example issuer".to_string(),
+                    public_key: hex::encode(trusted_key.public_key().to_bytes()),
+                    valid_from: None,
+                    valid_until: None,
+                    credential_types: vec![IDENTITY_CARD_TYPE.to_string()]
+                }]
+            },
+            did_documents: None,
+            required_fields: vec!["dateOfBirth".to_string(), "country".to_string()],
+            status_lists,
+            accumulator: None
+        };
+
+        let app = router_with_clock(config, Box::new(FixedClock(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (url, reqwest::Client::builder().no_proxy().build().unwrap())
+    }
+
+    fn presentation(secret_key: &SecretKey, messages: &[String], challenge: &Challenge, disclosed_indices: &[usize]) -> Presentation {
+        let credential = BoundCredential::issue_for_tests(secret_key, messages);
+
+        presentation::create_presentation(&credential, disclosed_indices, &oid4vp::presentation_header(&challenge.verifier_id, &challenge.nonce), Some(&challenge.verifier_id), &[], None).unwrap()
+    }
+
+    async fn challenge(client: &reqwest::Client, url: &str, request: &ChallengeRequest) -> Challenge {
+        client.post(format!("{}/challenges", url)).json(request).send().await.unwrap().json().await.unwrap()
+    }
+
+    async fn submit(client: &reqwest::Client, url: &str, challenge: &Challenge, presentation: &Presentation) -> reqwest::Response {
+        client.post(format!("{}/challenges/{}/presentation", url, challenge.id)).json(presentation).send().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn wallet_answers_challenge_through_openid4vp() {
+        let secret_key = issuer_key();
+        let (url, client) = start_verifier(&secret_key, None).await;
+
+        let challenge = challenge(&client, &url, &ChallengeRequest::default()).await;
+        assert_eq!(challenge.required_fields, vec!["dateOfBirth", "country"]);
+
+        let messages = card_messages();
+        let mut credential = BoundCredential::issue_for_tests(&secret_key, &messages);
+        credential.verification_method = Some(did::did_key_verification_method(&credential.public_key.to_bytes()));
+
+        let request = oid4vp::resolve_authorization_request(&client, &challenge.authorization_request).await.unwrap();
+        let requested = oid4vp::match_credential(&request, &messages, &SystemClock).unwrap();
+        assert_eq!(requested.required, vec![4, 2, 7]);
+
+        let response = oid4vp::create_response(&request, &requested, &credential, &requested.required).unwrap();
+        oid4vp::post_response(&client, &request, &response).await.unwrap();
+
+        let result: VerificationResult = client.get(format!("{}/challenges/{}/result", url, challenge.id)).send().await.unwrap().json().await.unwrap();
+
+        assert!(result.verified, "{:?}", result.error);
+        assert_eq!(result.issuer, IssuerTrust::Trusted { id: "https://issuer.example".to_string(), name: "Example issuer".to_string() });
+        assert!(result.pseudonym.is_some());
+        assert_eq!(result.disclosed, BTreeMap::from([("country".to_string(), "FR".to_string()), ("dateOfBirth".to_string(), "1990-01-01".to_string()), ("expiryDate".to_string(), "2030-01-01".to_string())]));
+    }
+
+    #[tokio::test]
+    async fn presentation_is_scanned_from_a_qr_code() {
+        let secret_key = issuer_key();
+        let (url, client) = start_verifier(&secret_key, None).await;
+
+        let scanned = challenge(&client, &url, &ChallengeRequest::default()).await;
+        let other = challenge(&client, &url, &ChallengeRequest::default()).await;
+
+        let request_qr = client.get(format!("{}/challenges/{}/qr", url, scanned.id)).send().await.unwrap();
+        assert_eq!(request_qr.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert!(request_qr.text().await.unwrap().contains("<svg"));
+
+        let payload = qr::encode_presentation(&presentation(&secret_key, &card_messages(), &scanned, &[2, 4, 7]), Some(&scanned.id)).unwrap();
+
+        let wrong_state = client.post(format!("{}/challenges/{}/qr", url, other.id)).body(payload.clone()).send().await.unwrap();
+        assert_eq!(wrong_state.status(), StatusCode::BAD_REQUEST);
+
+        let result: VerificationResult = client.post(format!("{}/challenges/{}/qr", url, scanned.id)).body(payload).send().await.unwrap().json().await.unwrap();
+        assert!(result.verified, "{:?}", result.error);
+        assert_eq!(result.disclosed["dateOfBirth"], "1990-01-01");
+    }
+
+    #[tokio::test]
+    async fn missing_fields_untrusted_issuers_and_replays_are_rejected() {
+        let secret_key = issuer_key();
+        let (url, client) = start_verifier(&secret_key, None).await;
+
+        let request = ChallengeRequest { required_fields: Some(vec!["surname".to_string()]) };
+
+        let first = challenge(&client, &url, &request).await;
+        let presentation_without_surname = presentation(&secret_key, &card_messages(), &first, &[1, 7]);
+        let result: VerificationResult = submit(&client, &url, &first, &presentation_without_surname).await.json().await.unwrap();
+
+        assert!(!result.verified);
+        assert_eq!(result.missing_fields, vec!["surname"]);
+
+        let second = challenge(&client, &url, &request).await;
+        let other_issuer = other_issuer_key();
+        let result: VerificationResult = submit(&client, &url, &second, &presentation(&other_issuer, &card_messages(), &second, &[0, 7])).await.json().await.unwrap();
+
+        assert!(!result.verified);
+        assert_eq!(result.issuer, IssuerTrust::Unknown);
+        assert_eq!(result.error.as_deref(), Some("Unknown issuer"));
+
+        let third = challenge(&client, &url, &request).await;
+        let valid = presentation(&secret_key, &card_messages(), &third, &[0, 7]);
+
+        // A proof made for another challenge does not verify against this nonce.
+        let for_first = presentation(&secret_key, &card_messages(), &first, &[0, 7]);
+        let replayed: VerificationResult = submit(&client, &url, &third, &for_first).await.json().await.unwrap();
+        assert!(!replayed.verified);
+
+        // Anyone can post to a challenge, a failed attempt leaves it open for the holder.
+        let result: VerificationResult = submit(&client, &url, &third, &valid).await.json().await.unwrap();
+        assert!(result.verified, "{:?}", result.error);
+
+        assert_eq!(submit(&client, &url, &third, &valid).await.status(), StatusCode::CONFLICT);
+        assert_eq!(submit(&client, &url, &third, &for_first).await.status(), StatusCode::CONFLICT);
+
+        let mut expired = card_messages();
+        expired[7] = "2026-01-01".to_string();
+
+        let fifth = challenge(&client, &url, &request).await;
+        let result: VerificationResult = submit(&client, &url, &fifth, &presentation(&secret_key, &expired, &fifth, &[0, 7])).await.json().await.unwrap();
+        assert_eq!(result.error.as_deref(), Some("Credential expired on 2026-01-01"));
+
+        // Hiding the expiry date without proving the card is not expired does not get an expired card through.
+        let hidden = challenge(&client, &url, &request).await;
+        let result: VerificationResult = submit(&client, &url, &hidden, &presentation(&secret_key, &expired, &hidden, &[0])).await.json().await.unwrap();
+        assert!(!result.verified);
+        assert_eq!(result.error.as_deref(), Some("Credential neither discloses its expiry date nor proves it is not expired"));
+
+        // The DID URL the presentation refers to must name the key the proof was made with.
+        let sixth = challenge(&client, &url, &request).await;
+        let mut mismatched = presentation(&secret_key, &card_messages(), &sixth, &[0, 7]);
+        mismatched.verification_method = Some(did::did_key_verification_method(&other_issuer.public_key().to_bytes()));
+
+        let result: VerificationResult = submit(&client, &url, &sixth, &mismatched).await.json().await.unwrap();
+        assert_eq!(result.error, Some(format!("Presentation key does not match {}", mismatched.verification_method.unwrap())));
+
+        // A second value for a disclosed field is rejected rather than reported.
+        let seventh = challenge(&client, &url, &request).await;
+        let mut repeated = presentation(&secret_key, &card_messages(), &seventh, &[0, 2, 7]);
+        repeated.disclosed_messages.push(DisclosedMessage { index: 2, value: "DE".to_string() });
+
+        let result: VerificationResult = submit(&client, &url, &seventh, &repeated).await.json().await.unwrap();
+        assert!(!result.verified);
+        assert_eq!(result.error.as_deref(), Some("Disclosed messages repeat a field"));
+        assert!(result.disclosed.is_empty());
+
+        let unknown = client.post(format!("{}/challenges", url)).json(&ChallengeRequest { required_fields: Some(vec!["height".to_string()]) }).send().await.unwrap();
+        assert_eq!(unknown.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn revoked_cards_are_rejected() {
+        use crate::status_list::{StatusList, StatusPurpose, STATUS_LIST_LENGTH};
+
+        let secret_key = issuer_key();
+        let path = std::env::temp_dir().join(format!("wallet-status-lists-test-{}.json", std::process::id()));
+
+        let write_lists = |revocation: &StatusList| std::fs::write(&path, serde_json::to_string(&vec![
+            StatusListCredential::issue(&secret_key, StatusPurpose::Revocation, revocation).unwrap(),
+            StatusListCredential::issue(&secret_key, StatusPurpose::Suspension, &StatusList::new(STATUS_LIST_LENGTH)).unwrap()
+        ]).unwrap()).unwrap();
+
+        let mut revocation = StatusList::new(STATUS_LIST_LENGTH);
+        write_lists(&revocation);
+
+        let (url, client) = start_verifier(&secret_key, Some(path.clone())).await;
+
+        let mut messages = card_messages();
+        messages.push("42".to_string());
+
+        let first = challenge(&client, &url, &ChallengeRequest::default()).await;
+        assert_eq!(first.required_fields, vec!["dateOfBirth", "country", "statusListIndex"]);
+
+        let hidden: VerificationResult = submit(&client, &url, &first, &presentation(&secret_key, &messages, &first, &[2, 4, 7])).await.json().await.unwrap();
+        assert!(!hidden.verified);
+
+        let result: VerificationResult = submit(&client, &url, &first, &presentation(&secret_key, &messages, &first, &[2, 4, 7, 8])).await.json().await.unwrap();
+        assert!(result.verified, "{:?}", result.error);
+
+        // The lists are read again for each presentation.
+        revocation.set(42, true).unwrap();
+        write_lists(&revocation);
+
+        let second = challenge(&client, &url, &ChallengeRequest::default()).await;
+        let result: VerificationResult = submit(&client, &url, &second, &presentation(&secret_key, &messages, &second, &[2, 4, 7, 8])).await.json().await.unwrap();
+
+        assert!(!result.verified);
+        assert_eq!(result.error.as_deref(), Some("Card revoked"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+use blstrs::{G1Projective, G2Affine, G2Projective, Scalar};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+
+use super::{g1_from_bytes, random_scalar, Reader, Transcript, G1_LENGTH, SCALAR_LENGTH};
+use super::proof::{LinkedProver, LinkedVerifier, MessageRef};
+use super::signature::{pairing_is_identity, PublicKey, SecretKey};
+
+const ACCUMULATOR_SEED: &[u8] = b"WALLET_BBS_ACCUMULATOR_SEED_";
+const ACCUMULATOR_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_ACCUMULATOR_";
+const NON_REVOCATION_LABEL: &[u8] = b"WALLET_BBS_NON_REVOCATION_";
+
+pub const NON_REVOCATION_PROOF_LENGTH: usize = 2 * G1_LENGTH + SCALAR_LENGTH;
+
+fn invert(scalar: Scalar) -> Result<Scalar, String> {
+    Option::from(scalar.invert()).ok_or_else(|| "Element cannot be inverted".to_string())
+}
+
+// Positive accumulator: every valid credential is a member and revoking a credential removes it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Accumulator(G1Projective);
+
+impl Accumulator {
+    pub fn new() -> Self {
+        Accumulator(G1Projective::hash_to_curve(ACCUMULATOR_SEED, ACCUMULATOR_DST, &[]))
+    }
+
+    pub fn add(&self, secret_key: &SecretKey, element: &Scalar) -> Accumulator {
+        Accumulator(self.0 * (element + secret_key.0.expose()))
+    }
+
+    pub fn remove(&self, secret_key: &SecretKey, element: &Scalar) -> Result<Accumulator, String> {
+        Ok(Accumulator(self.0 * invert(element + secret_key.0.expose())?))
+    }
+
+    pub fn witness(&self, secret_key: &SecretKey, element: &Scalar) -> Result<Witness, String> {
+        Ok(Witness(self.0 * invert(element + secret_key.0.expose())?))
+    }
+
+    pub fn to_bytes(&self) -> [u8; G1_LENGTH] {
+        self.0.to_affine().to_compressed()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        Ok(Accumulator(g1_from_bytes(bytes)?))
+    }
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Accumulator::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpdateAction {
+    Add,
+    Remove
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AccumulatorUpdate {
+    pub action: UpdateAction,
+    pub element: Scalar,
+    pub accumulator: Accumulator
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Witness(G1Projective);
+
+impl Witness {
+    pub fn verify(&self, public_key: &PublicKey, accumulator: &Accumulator, element: &Scalar) -> bool {
+        let key = G2Affine::from(G2Projective::from(public_key.0) + G2Projective::generator() * element);
+
+        pairing_is_identity(&self.0.to_affine(), &key, &(-accumulator.0).to_affine(), &G2Affine::generator())
+    }
+
+    // Applies the issuer's published updates, starting from the accumulator the witness was computed for.
+    pub fn update(&self, element: &Scalar, accumulator: &Accumulator, updates: &[AccumulatorUpdate]) -> Result<Witness, String> {
+        let mut witness = self.0;
+        let mut current = accumulator.0;
+
+        for update in updates {
+            match update.action {
+                UpdateAction::Add => {
+                    witness = current + witness * (update.element - element);
+                },
+                UpdateAction::Remove => {
+                    if update.element == *element {
+                        return Err("Credential has been revoked".to_string());
+                    }
+
+                    witness = (witness - update.accumulator.0) * invert(update.element - element)?;
+                }
+            }
+
+            current = update.accumulator.0;
+        }
+
+        Ok(Witness(witness))
+    }
+
+    pub fn to_bytes(&self) -> [u8; G1_LENGTH] {
+        self.0.to_affine().to_compressed()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        Ok(Witness(g1_from_bytes(bytes)?))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NonRevocationProof {
+    w_bar: G1Projective,
+    b_bar: G1Projective,
+    r_hat: Scalar
+}
+
+impl NonRevocationProof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(NON_REVOCATION_PROOF_LENGTH);
+        bytes.extend_from_slice(&self.w_bar.to_affine().to_compressed());
+        bytes.extend_from_slice(&self.b_bar.to_affine().to_compressed());
+        bytes.extend_from_slice(&self.r_hat.to_bytes_be());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != NON_REVOCATION_PROOF_LENGTH {
+            return Err("Invalid non-revocation proof length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+
+        Ok(NonRevocationProof {
+            w_bar: reader.g1()?,
+            b_bar: reader.g1()?,
+            r_hat: reader.scalar()?
+        })
+    }
+}
+
+fn append_non_revocation(transcript: &mut Transcript, accumulator: &Accumulator, proof: &NonRevocationProof, t: &G1Projective) {
+    transcript.append_bytes(NON_REVOCATION_LABEL);
+    transcript.append_point(&accumulator.0);
+    transcript.append_point(&proof.w_bar);
+    transcript.append_point(&proof.b_bar);
+    transcript.append_point(t);
+}
+
+// The witness is randomized as W' = W * r and B' = V * r - W' * y, so that e(W', P) = e(B', g2)
+// holds publicly while r and y are only proven in zero knowledge.
+pub struct NonRevocationProver {
+    message: MessageRef,
+    accumulator: Accumulator,
+    r: Scalar,
+    r_tilde: Scalar,
+    proof: NonRevocationProof
+}
+
+impl NonRevocationProver {
+    pub fn new(message: MessageRef, element: &Scalar, witness: &Witness, accumulator: &Accumulator) -> Self {
+        let r = random_scalar();
+        let w_bar = witness.0 * r;
+
+        NonRevocationProver {
+            message,
+            accumulator: *accumulator,
+            r,
+            r_tilde: random_scalar(),
+            proof: NonRevocationProof {
+                w_bar,
+                b_bar: accumulator.0 * r - w_bar * element,
+                r_hat: Scalar::ZERO
+            }
+        }
+    }
+
+    pub fn proof(&self) -> &NonRevocationProof {
+        &self.proof
+    }
+}
+
+impl LinkedProver for NonRevocationProver {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn commit(&mut self, message_blinding: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let t = self.accumulator.0 * self.r_tilde - self.proof.w_bar * message_blinding;
+
+        append_non_revocation(transcript, &self.accumulator, &self.proof, &t);
+
+        Ok(())
+    }
+
+    fn respond(&mut self, challenge: &Scalar) {
+        self.proof.r_hat = self.r_tilde + challenge * self.r;
+    }
+}
+
+pub struct NonRevocationVerifier<'a> {
+    message: MessageRef,
+    public_key: &'a PublicKey,
+    accumulator: &'a Accumulator,
+    proof: &'a NonRevocationProof
+}
+
+impl<'a> NonRevocationVerifier<'a> {
+    pub fn new(message: MessageRef, public_key: &'a PublicKey, accumulator: &'a Accumulator, proof: &'a NonRevocationProof) -> Self {
+        NonRevocationVerifier { message, public_key, accumulator, proof }
+    }
+}
+
+impl LinkedVerifier for NonRevocationVerifier<'_> {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn reconstruct(&self, message_response: &Scalar, challenge: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        if bool::from(self.proof.w_bar.is_identity()) {
+            return Err("Invalid non-revocation proof".to_string());
+        }
+
+        if !pairing_is_identity(&self.proof.w_bar.to_affine(), &self.public_key.0, &(-self.proof.b_bar).to_affine(), &G2Affine::generator()) {
+            return Err("Witness does not match the accumulator".to_string());
+        }
+
+        let t = self.accumulator.0 * self.proof.r_hat - self.proof.w_bar * message_response - self.proof.b_bar * challenge;
+
+        append_non_revocation(transcript, self.accumulator, self.proof, &t);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{message_to_scalar, messages_to_scalars, random_scalar, testing::{self, accumulator_key}};
+    use crate::zkp::proof::{proof_gen, proof_verify, ProofGenRequest, ProofVerifyRequest};
+
+    const HEADER: &[u8] = b"wallet-header";
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    const REVOCATION_ID: MessageRef = MessageRef::Signed(1);
+
+    fn prove(revocation_id: &str, witness: &Witness, accumulator: &Accumulator, verifier_accumulator: &Accumulator) -> bool {
+        let messages = messages_to_scalars(&["Doe", revocation_id]);
+        let credential = testing::issue(&testing::issuer_key(), HEADER, &messages, random_scalar());
+
+        let mut prover = NonRevocationProver::new(REVOCATION_ID, &messages[1], witness, accumulator);
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &credential.public_key,
+            signature: &credential.signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &credential.committed_messages(),
+            disclosed_indices: &[0]
+        }, &mut [&mut prover]).unwrap();
+
+        let non_revocation_proof = NonRevocationProof::from_bytes(&prover.proof().to_bytes()).unwrap();
+        let accumulator_public_key = accumulator_key().public_key();
+        let verifier = NonRevocationVerifier::new(REVOCATION_ID, &accumulator_public_key, verifier_accumulator, &non_revocation_proof);
+
+        proof_verify(&ProofVerifyRequest {
+            public_key: &credential.public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: 2,
+            disclosed_messages: &[(0, message_to_scalar(b"Doe"))]
+        }, &[&verifier])
+    }
+
+    #[test]
+    fn witness_updates_follow_additions_and_removals() {
+        let secret_key = accumulator_key();
+        let public_key = secret_key.public_key();
+
+        let holder = message_to_scalar(b"1");
+        let other = message_to_scalar(b"2");
+        let late = message_to_scalar(b"3");
+
+        let initial = Accumulator::new().add(&secret_key, &holder).add(&secret_key, &other);
+        let witness = initial.witness(&secret_key, &holder).unwrap();
+
+        assert!(witness.verify(&public_key, &initial, &holder));
+
+        let added = initial.add(&secret_key, &late);
+        let removed = added.remove(&secret_key, &other).unwrap();
+
+        let updates = [
+            AccumulatorUpdate { action: UpdateAction::Add, element: late, accumulator: added },
+            AccumulatorUpdate { action: UpdateAction::Remove, element: other, accumulator: removed }
+        ];
+
+        assert!(!witness.verify(&public_key, &removed, &holder));
+
+        let updated = witness.update(&holder, &initial, &updates).unwrap();
+
+        assert!(updated.verify(&public_key, &removed, &holder));
+        assert_eq!(updated, removed.witness(&secret_key, &holder).unwrap());
+
+        let other_witness = initial.witness(&secret_key, &other).unwrap();
+        assert!(other_witness.update(&other, &initial, &updates).is_err());
+    }
+
+    #[test]
+    fn non_revocation_proof_is_linked_to_the_hidden_revocation_id() {
+        let secret_key = accumulator_key();
+
+        let holder = message_to_scalar(b"1");
+        let other = message_to_scalar(b"2");
+
+        let accumulator = Accumulator::new().add(&secret_key, &holder).add(&secret_key, &other);
+
+        let witness = accumulator.witness(&secret_key, &holder).unwrap();
+        assert!(prove("1", &witness, &accumulator, &accumulator));
+
+        let revoked = accumulator.remove(&secret_key, &holder).unwrap();
+        assert!(!prove("1", &witness, &accumulator, &revoked));
+
+        let other_witness = accumulator.witness(&secret_key, &other).unwrap();
+        assert!(!prove("1", &other_witness, &accumulator, &accumulator));
+    }
+}
@@ -0,0 +1,142 @@
+use blstrs::{G1Projective, Scalar};
+use group::{Curve, Group};
+
+use super::{random_scalar, Reader, Transcript, G1_LENGTH, SCALAR_LENGTH};
+use super::signature::{sign, Generators, PublicKey, SecretKey, Signature};
+
+const COMMITMENT_LABEL: &[u8] = b"WALLET_BBS_HOLDER_COMMITMENT_";
+
+pub const COMMITTED_MESSAGE_COUNT: usize = 2;
+pub const COMMITMENT_LENGTH: usize = G1_LENGTH + 3 * SCALAR_LENGTH;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HolderCommitment {
+    pub(crate) commitment: G1Projective,
+    challenge: Scalar,
+    secret_response: Scalar,
+    blind_response: Scalar
+}
+
+impl HolderCommitment {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.commitment.to_affine().to_compressed().to_vec();
+        bytes.extend_from_slice(&self.challenge.to_bytes_be());
+        bytes.extend_from_slice(&self.secret_response.to_bytes_be());
+        bytes.extend_from_slice(&self.blind_response.to_bytes_be());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != COMMITMENT_LENGTH {
+            return Err("Invalid commitment length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+
+        Ok(HolderCommitment {
+            commitment: reader.g1()?,
+            challenge: reader.scalar()?,
+            secret_response: reader.scalar()?,
+            blind_response: reader.scalar()?
+        })
+    }
+}
+
+fn commitment_challenge(public_key: &PublicKey, header: &[u8], nonce: &[u8], commitment: &G1Projective, t: &G1Projective) -> Scalar {
+    let mut transcript = Transcript::new(COMMITMENT_LABEL);
+    transcript.append_bytes(&public_key.to_bytes());
+    transcript.append_bytes(header);
+    transcript.append_bytes(nonce);
+    transcript.append_point(commitment);
+    transcript.append_point(t);
+
+    transcript.challenge()
+}
+
+pub fn commit(public_key: &PublicKey, header: &[u8], holder_secret: &Scalar) -> (HolderCommitment, Scalar) {
+    commit_with_nonce(public_key, header, &[], holder_secret)
+}
+
+// The nonce binds the proof of knowledge to an issuer challenge, so that it doubles as a proof of possession.
+pub fn commit_with_nonce(public_key: &PublicKey, header: &[u8], nonce: &[u8], holder_secret: &Scalar) -> (HolderCommitment, Scalar) {
+    let generators = Generators::new(0, COMMITTED_MESSAGE_COUNT);
+
+    let prover_blind = random_scalar();
+    let commitment = generators.committed[0] * holder_secret + generators.committed[1] * prover_blind;
+
+    let secret_tilde = random_scalar();
+    let blind_tilde = random_scalar();
+    let t = generators.committed[0] * secret_tilde + generators.committed[1] * blind_tilde;
+
+    let challenge = commitment_challenge(public_key, header, nonce, &commitment, &t);
+
+    (HolderCommitment {
+        commitment,
+        challenge,
+        secret_response: secret_tilde + challenge * holder_secret,
+        blind_response: blind_tilde + challenge * prover_blind
+    }, prover_blind)
+}
+
+pub fn verify_commitment(public_key: &PublicKey, header: &[u8], commitment: &HolderCommitment) -> bool {
+    verify_commitment_with_nonce(public_key, header, &[], commitment)
+}
+
+pub fn verify_commitment_with_nonce(public_key: &PublicKey, header: &[u8], nonce: &[u8], commitment: &HolderCommitment) -> bool {
+    if bool::from(commitment.commitment.is_identity()) {
+        return false;
+    }
+
+    let generators = Generators::new(0, COMMITTED_MESSAGE_COUNT);
+
+    let t = generators.committed[0] * commitment.secret_response + generators.committed[1] * commitment.blind_response - commitment.commitment * commitment.challenge;
+
+    commitment_challenge(public_key, header, nonce, &commitment.commitment, &t) == commitment.challenge
+}
+
+pub fn blind_sign(secret_key: &SecretKey, public_key: &PublicKey, header: &[u8], messages: &[Scalar], commitment: &HolderCommitment) -> Result<Signature, String> {
+    blind_sign_with_nonce(secret_key, public_key, header, &[], messages, commitment)
+}
+
+pub fn blind_sign_with_nonce(secret_key: &SecretKey, public_key: &PublicKey, header: &[u8], nonce: &[u8], messages: &[Scalar], commitment: &HolderCommitment) -> Result<Signature, String> {
+    if !verify_commitment_with_nonce(public_key, header, nonce, commitment) {
+        return Err("Invalid holder commitment".to_string());
+    }
+
+    sign(secret_key, public_key, header, messages, Some((&commitment.commitment, COMMITTED_MESSAGE_COUNT)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{random_scalar, testing};
+
+    #[test]
+    fn commitment_proof_is_checked_before_signing() {
+        let secret_key = testing::issuer_key();
+        let public_key = secret_key.public_key();
+
+        let (commitment, _) = commit(&public_key, b"header", &random_scalar());
+
+        assert!(verify_commitment(&public_key, b"header", &commitment));
+        assert!(!verify_commitment(&public_key, b"other-header", &commitment));
+
+        let mut forged = HolderCommitment::from_bytes(&commitment.to_bytes()).unwrap();
+        forged.commitment += G1Projective::generator();
+
+        assert!(blind_sign(&secret_key, &public_key, b"header", &[], &forged).is_err());
+    }
+
+    #[test]
+    fn commitment_is_bound_to_issuer_nonce() {
+        let secret_key = testing::issuer_key();
+        let public_key = secret_key.public_key();
+
+        let (commitment, _) = commit_with_nonce(&public_key, b"header", b"nonce", &random_scalar());
+
+        assert!(verify_commitment_with_nonce(&public_key, b"header", b"nonce", &commitment));
+        assert!(!verify_commitment_with_nonce(&public_key, b"header", b"other-nonce", &commitment));
+        assert!(blind_sign(&secret_key, &public_key, b"header", &[], &commitment).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use blstrs::{G1Projective, Scalar};
+use ff::Field;
+use group::Curve;
+
+use super::{create_generators, random_scalar, Reader, Transcript, G1_LENGTH, SCALAR_LENGTH};
+use super::proof::{LinkedProver, LinkedVerifier, MessageRef};
+
+const MEMBERSHIP_GENERATOR_TAG: &[u8] = b"WALLET_BBS_MEMBERSHIP_GENERATOR_";
+const MEMBERSHIP_LABEL: &[u8] = b"WALLET_BBS_MEMBERSHIP_";
+
+pub fn membership_proof_length(set_size: usize) -> usize {
+    G1_LENGTH + SCALAR_LENGTH + (2 * set_size).saturating_sub(1) * SCALAR_LENGTH
+}
+
+struct MembershipGenerators {
+    g: G1Projective,
+    h: G1Projective
+}
+
+impl MembershipGenerators {
+    fn new() -> Self {
+        let generators = create_generators(MEMBERSHIP_GENERATOR_TAG, 2);
+
+        MembershipGenerators { g: generators[0], h: generators[1] }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MembershipProof {
+    commitment: G1Projective,
+    blind_response: Scalar,
+    // One challenge per set element except the last, which is implied by the proof challenge.
+    challenges: Vec<Scalar>,
+    responses: Vec<Scalar>
+}
+
+impl MembershipProof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(membership_proof_length(self.responses.len()));
+        bytes.extend_from_slice(&self.commitment.to_affine().to_compressed());
+        bytes.extend_from_slice(&self.blind_response.to_bytes_be());
+
+        for scalar in self.challenges.iter().chain(&self.responses) {
+            bytes.extend_from_slice(&scalar.to_bytes_be());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8], set_size: usize) -> Result<Self, String> {
+        if set_size == 0 || bytes.len() != membership_proof_length(set_size) {
+            return Err("Invalid membership proof length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+
+        let commitment = reader.g1()?;
+        let blind_response = reader.scalar()?;
+        let challenges = (1..set_size).map(|_| reader.scalar()).collect::<Result<Vec<Scalar>, String>>()?;
+        let responses = (0..set_size).map(|_| reader.scalar()).collect::<Result<Vec<Scalar>, String>>()?;
+
+        Ok(MembershipProof { commitment, blind_response, challenges, responses })
+    }
+}
+
+fn append_membership(transcript: &mut Transcript, set: &[Scalar], commitment: &G1Projective, t: &G1Projective, branch_t: &[G1Projective]) {
+    transcript.append_bytes(MEMBERSHIP_LABEL);
+    transcript.append_bytes(&(set.len() as u64).to_be_bytes());
+
+    for element in set {
+        transcript.append_scalar(element);
+    }
+
+    transcript.append_point(commitment);
+    transcript.append_point(t);
+
+    for point in branch_t {
+        transcript.append_point(point);
+    }
+}
+
+pub struct MembershipProver {
+    message: MessageRef,
+    set: Vec<Scalar>,
+    position: usize,
+    generators: MembershipGenerators,
+    blind: Scalar,
+    blind_tilde: Scalar,
+    nonce: Scalar,
+    simulated_challenges: Vec<Scalar>,
+    simulated_responses: Vec<Scalar>,
+    proof: MembershipProof
+}
+
+impl MembershipProver {
+    pub fn new(message: MessageRef, value: Scalar, set: Vec<Scalar>) -> Result<Self, String> {
+        let position = set.iter().position(|element| *element == value).ok_or("Value is not a member of the set")?;
+
+        let generators = MembershipGenerators::new();
+        let blind = random_scalar();
+
+        Ok(MembershipProver {
+            message,
+            position,
+            blind,
+            blind_tilde: random_scalar(),
+            nonce: random_scalar(),
+            simulated_challenges: set.iter().map(|_| random_scalar()).collect(),
+            simulated_responses: set.iter().map(|_| random_scalar()).collect(),
+            proof: MembershipProof {
+                commitment: generators.g * value + generators.h * blind,
+                blind_response: Scalar::ZERO,
+                challenges: vec![],
+                responses: vec![]
+            },
+            generators,
+            set
+        })
+    }
+
+    pub fn proof(&self) -> &MembershipProof {
+        &self.proof
+    }
+}
+
+impl LinkedProver for MembershipProver {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn commit(&mut self, message_blinding: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let t = self.generators.g * message_blinding + self.generators.h * self.blind_tilde;
+
+        // Every branch except the holder's own value is simulated from a chosen challenge and response.
+        let branch_t: Vec<G1Projective> = self.set.iter().enumerate().map(|(j, element)| {
+            if j == self.position {
+                self.generators.h * self.nonce
+            } else {
+                self.generators.h * self.simulated_responses[j] - (self.proof.commitment - self.generators.g * element) * self.simulated_challenges[j]
+            }
+        }).collect();
+
+        append_membership(transcript, &self.set, &self.proof.commitment, &t, &branch_t);
+
+        Ok(())
+    }
+
+    fn respond(&mut self, challenge: &Scalar) {
+        let simulated_sum = self.simulated_challenges.iter().enumerate()
+            .filter(|(j, _)| *j != self.position)
+            .fold(Scalar::ZERO, |sum, (_, c)| sum + c);
+
+        let mut challenges = self.simulated_challenges.clone();
+        challenges[self.position] = challenge - simulated_sum;
+
+        let mut responses = self.simulated_responses.clone();
+        responses[self.position] = self.nonce + challenges[self.position] * self.blind;
+
+        challenges.pop();
+
+        self.proof.blind_response = self.blind_tilde + challenge * self.blind;
+        self.proof.challenges = challenges;
+        self.proof.responses = responses;
+    }
+}
+
+pub struct MembershipVerifier<'a> {
+    message: MessageRef,
+    set: Vec<Scalar>,
+    proof: &'a MembershipProof
+}
+
+impl<'a> MembershipVerifier<'a> {
+    pub fn new(message: MessageRef, set: Vec<Scalar>, proof: &'a MembershipProof) -> Self {
+        MembershipVerifier { message, set, proof }
+    }
+}
+
+impl LinkedVerifier for MembershipVerifier<'_> {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn reconstruct(&self, message_response: &Scalar, challenge: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        if self.set.is_empty() || self.proof.responses.len() != self.set.len() || self.proof.challenges.len() + 1 != self.set.len() {
+            return Err("Membership proof does not match the set".to_string());
+        }
+
+        let generators = MembershipGenerators::new();
+
+        let last_challenge = self.proof.challenges.iter().fold(*challenge, |last, c| last - c);
+        let challenges = self.proof.challenges.iter().chain(std::iter::once(&last_challenge));
+
+        let t = generators.g * message_response + generators.h * self.proof.blind_response - self.proof.commitment * challenge;
+
+        let branch_t: Vec<G1Projective> = self.set.iter().zip(challenges).zip(&self.proof.responses).map(|((element, c), z)| {
+            generators.h * z - (self.proof.commitment - generators.g * element) * c
+        }).collect();
+
+        append_membership(transcript, &self.set, &self.proof.commitment, &t, &branch_t);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{message_to_scalar, messages_to_scalars, random_scalar, testing};
+    use crate::zkp::proof::{proof_gen, proof_verify, ProofGenRequest, ProofVerifyRequest};
+
+    const HEADER: &[u8] = b"wallet-header";
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    const COUNTRY: MessageRef = MessageRef::Signed(1);
+
+    fn prove(country: &str, set: &[&str], claimed_set: &[&str]) -> Result<bool, String> {
+        let messages = messages_to_scalars(&["Doe", country]);
+        let credential = testing::issue(&testing::issuer_key(), HEADER, &messages, random_scalar());
+
+        let mut prover = MembershipProver::new(COUNTRY, message_to_scalar(b"FR"), messages_to_scalars(set))?;
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &credential.public_key,
+            signature: &credential.signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &credential.committed_messages(),
+            disclosed_indices: &[0]
+        }, &mut [&mut prover])?;
+
+        let membership_proof = MembershipProof::from_bytes(&prover.proof().to_bytes(), set.len())?;
+        let verifier = MembershipVerifier::new(COUNTRY, messages_to_scalars(claimed_set), &membership_proof);
+
+        Ok(proof_verify(&ProofVerifyRequest {
+            public_key: &credential.public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: 2,
+            disclosed_messages: &[(0, message_to_scalar(b"Doe"))]
+        }, &[&verifier]))
+    }
+
+    #[test]
+    fn membership_proof_accepts_member_of_set() {
+        assert_eq!(prove("FR", &["DE", "FR", "IT"], &["DE", "FR", "IT"]), Ok(true));
+        assert_eq!(prove("FR", &["FR"], &["FR"]), Ok(true));
+    }
+
+    #[test]
+    fn membership_proof_rejects_other_set_or_other_signed_value() {
+        assert!(prove("FR", &["DE", "IT"], &["DE", "IT"]).is_err());
+        assert_eq!(prove("FR", &["DE", "FR", "IT"], &["DE", "ES", "IT"]), Ok(false));
+        assert_eq!(prove("US", &["DE", "FR", "IT"], &["DE", "FR", "IT"]), Ok(false));
+    }
+}
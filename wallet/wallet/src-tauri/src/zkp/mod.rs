@@ -0,0 +1,232 @@
+use blstrs::{G1Affine, G1Projective, G2Affine, Scalar};
+use ff::{Field, PrimeField};
+use group::Curve;
+use rand_core::OsRng;
+use sha2::{Sha256, Digest};
+
+pub mod accumulator;
+pub mod commitment;
+pub mod membership;
+pub mod proof;
+pub mod pseudonym;
+pub mod range;
+pub mod signature;
+
+const MESSAGE_TO_SCALAR_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_MAP_MSG_TO_SCALAR_AS_HASH_";
+const GENERATOR_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_SIG_GENERATOR_DST_";
+const CHALLENGE_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2S_CHALLENGE_";
+
+pub const SCALAR_LENGTH: usize = 32;
+pub const G1_LENGTH: usize = 48;
+pub const G2_LENGTH: usize = 96;
+
+pub fn hash_to_scalar(input: &[u8], dst: &[u8]) -> Scalar {
+    let shift = Scalar::from_u128(u128::MAX) + Scalar::ONE;
+
+    let mut scalar = Scalar::ZERO;
+
+    for counter in 0u8..2 {
+        let mut hasher = Sha256::new();
+        hasher.update([dst.len() as u8]);
+        hasher.update(dst);
+        hasher.update([counter]);
+        hasher.update(input);
+
+        let digest = hasher.finalize();
+
+        for chunk in digest.chunks(16) {
+            let limb = u128::from_be_bytes(chunk.try_into().unwrap());
+
+            scalar = scalar * shift + Scalar::from_u128(limb);
+        }
+    }
+
+    scalar
+}
+
+// Dates are signed as their yyyymmdd integer value so that range predicates can be proven over them.
+pub fn message_to_scalar(message: &[u8]) -> Scalar {
+    match std::str::from_utf8(message).ok().and_then(date_to_integer) {
+        Some(date) => Scalar::from(date),
+        None => hash_to_scalar(message, MESSAGE_TO_SCALAR_DST)
+    }
+}
+
+pub fn date_to_integer(date: &str) -> Option<u64> {
+    let bytes = date.as_bytes();
+
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+
+    let digits: String = [&date[0..4], &date[5..7], &date[8..10]].concat();
+
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let month: u64 = digits[4..6].parse().ok()?;
+    let day: u64 = digits[6..8].parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    digits.parse().ok()
+}
+
+pub fn messages_to_scalars<T: AsRef<[u8]>>(messages: &[T]) -> Vec<Scalar> {
+    messages.iter().map(|m| message_to_scalar(m.as_ref())).collect()
+}
+
+pub fn random_scalar() -> Scalar {
+    loop {
+        let scalar = Scalar::random(&mut OsRng);
+
+        if !bool::from(scalar.is_zero()) {
+            return scalar;
+        }
+    }
+}
+
+pub fn create_generators(tag: &[u8], count: usize) -> Vec<G1Projective> {
+    (0..count as u64).map(|i| {
+        let mut seed = tag.to_vec();
+        seed.extend_from_slice(&i.to_be_bytes());
+
+        G1Projective::hash_to_curve(&seed, GENERATOR_DST, &[])
+    }).collect()
+}
+
+pub struct Transcript {
+    bytes: Vec<u8>
+}
+
+impl Transcript {
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript { bytes: vec![] };
+        transcript.append_bytes(label);
+
+        transcript
+    }
+
+    pub fn append_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn append_point(&mut self, point: &G1Projective) {
+        self.bytes.extend_from_slice(&point.to_affine().to_compressed());
+    }
+
+    pub fn append_scalar(&mut self, scalar: &Scalar) {
+        self.bytes.extend_from_slice(&scalar.to_bytes_be());
+    }
+
+    pub fn challenge(&self) -> Scalar {
+        hash_to_scalar(&self.bytes, CHALLENGE_DST)
+    }
+}
+
+pub fn scalar_from_bytes(bytes: &[u8]) -> Result<Scalar, String> {
+    let bytes: [u8; SCALAR_LENGTH] = bytes.try_into().map_err(|_| "Invalid scalar length")?;
+
+    Option::from(Scalar::from_bytes_be(&bytes)).ok_or_else(|| "Invalid scalar".to_string())
+}
+
+pub fn g1_from_bytes(bytes: &[u8]) -> Result<G1Projective, String> {
+    let bytes: [u8; G1_LENGTH] = bytes.try_into().map_err(|_| "Invalid G1 point length")?;
+
+    Option::<G1Affine>::from(G1Affine::from_compressed(&bytes)).map(G1Projective::from).ok_or_else(|| "Invalid G1 point".to_string())
+}
+
+pub fn g2_from_bytes(bytes: &[u8]) -> Result<G2Affine, String> {
+    let bytes: [u8; G2_LENGTH] = bytes.try_into().map_err(|_| "Invalid G2 point length")?;
+
+    Option::from(G2Affine::from_compressed(&bytes)).ok_or_else(|| "Invalid G2 point".to_string())
+}
+
+pub struct Reader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    pub fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < length {
+            return Err("Unexpected end of input".to_string());
+        }
+
+        let (head, tail) = self.bytes.split_at(length);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    pub fn scalar(&mut self) -> Result<Scalar, String> {
+        scalar_from_bytes(self.take(SCALAR_LENGTH)?)
+    }
+
+    pub fn g1(&mut self) -> Result<G1Projective, String> {
+        g1_from_bytes(self.take(G1_LENGTH)?)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+// Fixed issuer keys and blind signed credentials shared by the test modules.
+#[cfg(test)]
+pub(crate) mod testing {
+    use blstrs::Scalar;
+
+    use super::commitment::{blind_sign, commit};
+    use super::signature::{PublicKey, SecretKey, Signature};
+
+    const TEST_KEY_IKM: &[u8] = b"PKGVYkAHJrc95mGeRwLLVN7JxmHwEPwxEnvhyB7UCQsE4k9Py";
+
+    // Identity card fields, in signing order.
+    pub(crate) fn card_messages() -> Vec<String> {
+        ["Doe", "John", "FR", "M", "1990-01-01", "Paris", "X1234567", "2030-01-01"].iter().map(|m| m.to_string()).collect()
+    }
+
+    pub(crate) struct TestCredential {
+        pub public_key: PublicKey,
+        pub signature: Signature,
+        pub holder_secret: Scalar,
+        pub prover_blind: Scalar
+    }
+
+    impl TestCredential {
+        pub(crate) fn committed_messages(&self) -> [Scalar; 2] {
+            [self.holder_secret, self.prover_blind]
+        }
+    }
+
+    pub(crate) fn issuer_key() -> SecretKey {
+        SecretKey::new(TEST_KEY_IKM, b"wallet-test-key-info").unwrap()
+    }
+
+    // An issuer nobody trusts.
+    pub(crate) fn other_issuer_key() -> SecretKey {
+        SecretKey::new(b"another-issuer-key-material-0123456789", b"wallet-test-key-info").unwrap()
+    }
+
+    pub(crate) fn accumulator_key() -> SecretKey {
+        SecretKey::new(TEST_KEY_IKM, b"wallet-test-accumulator").unwrap()
+    }
+
+    // Commits to the holder secret and has the issuer blind sign the messages over it.
+    pub(crate) fn issue(secret_key: &SecretKey, header: &[u8], messages: &[Scalar], holder_secret: Scalar) -> TestCredential {
+        let public_key = secret_key.public_key();
+
+        let (commitment, prover_blind) = commit(&public_key, header, &holder_secret);
+        let signature = blind_sign(secret_key, &public_key, header, messages, &commitment).unwrap();
+
+        TestCredential { public_key, signature, holder_secret, prover_blind }
+    }
+}
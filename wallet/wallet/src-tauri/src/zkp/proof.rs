@@ -0,0 +1,385 @@
+use blstrs::{G1Projective, G2Affine, Scalar};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+
+use super::{random_scalar, Reader, Transcript, G1_LENGTH, SCALAR_LENGTH};
+use super::signature::{calculate_b, calculate_domain, pairing_is_identity, Generators, PublicKey, Signature};
+
+const PROOF_LABEL: &[u8] = b"WALLET_BBS_PROOF_";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Proof {
+    a_bar: G1Projective,
+    b_bar: G1Projective,
+    d: G1Projective,
+    e_hat: Scalar,
+    r1_hat: Scalar,
+    r3_hat: Scalar,
+    m_hat: Vec<Scalar>,
+    challenge: Scalar
+}
+
+impl Proof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * G1_LENGTH + (4 + self.m_hat.len()) * SCALAR_LENGTH);
+
+        for point in [&self.a_bar, &self.b_bar, &self.d] {
+            bytes.extend_from_slice(&point.to_affine().to_compressed());
+        }
+
+        for scalar in [&self.e_hat, &self.r1_hat, &self.r3_hat].into_iter().chain(self.m_hat.iter()) {
+            bytes.extend_from_slice(&scalar.to_bytes_be());
+        }
+
+        bytes.extend_from_slice(&self.challenge.to_bytes_be());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 3 * G1_LENGTH + 4 * SCALAR_LENGTH || !(bytes.len() - 3 * G1_LENGTH).is_multiple_of(SCALAR_LENGTH) {
+            return Err("Invalid proof length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+
+        let a_bar = reader.g1()?;
+        let b_bar = reader.g1()?;
+        let d = reader.g1()?;
+        let e_hat = reader.scalar()?;
+        let r1_hat = reader.scalar()?;
+        let r3_hat = reader.scalar()?;
+
+        let mut m_hat = Vec::new();
+
+        while reader.remaining() > SCALAR_LENGTH {
+            m_hat.push(reader.scalar()?);
+        }
+
+        let challenge = reader.scalar()?;
+
+        Ok(Proof { a_bar, b_bar, d, e_hat, r1_hat, r3_hat, m_hat, challenge })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MessageRef {
+    Signed(usize),
+    Committed(usize)
+}
+
+pub trait LinkedProver {
+    fn message(&self) -> MessageRef;
+    fn commit(&mut self, message_blinding: &Scalar, transcript: &mut Transcript) -> Result<(), String>;
+    fn respond(&mut self, challenge: &Scalar);
+}
+
+pub trait LinkedVerifier {
+    fn message(&self) -> MessageRef;
+    fn reconstruct(&self, message_response: &Scalar, challenge: &Scalar, transcript: &mut Transcript) -> Result<(), String>;
+}
+
+pub struct ProofGenRequest<'a> {
+    pub public_key: &'a PublicKey,
+    pub signature: &'a Signature,
+    pub header: &'a [u8],
+    pub presentation_header: &'a [u8],
+    pub messages: &'a [Scalar],
+    pub committed_messages: &'a [Scalar],
+    pub disclosed_indices: &'a [usize]
+}
+
+pub struct ProofVerifyRequest<'a> {
+    pub public_key: &'a PublicKey,
+    pub header: &'a [u8],
+    pub presentation_header: &'a [u8],
+    pub proof: &'a Proof,
+    pub message_count: usize,
+    pub committed_count: usize,
+    pub disclosed_messages: &'a [(usize, Scalar)]
+}
+
+fn hidden_position(message: MessageRef, message_count: usize, committed_count: usize, disclosed_indices: &[usize]) -> Result<usize, String> {
+    let hidden_signed_count = (0..message_count).filter(|i| !disclosed_indices.contains(i)).count();
+
+    match message {
+        MessageRef::Signed(index) if index < message_count && !disclosed_indices.contains(&index) => {
+            Ok((0..index).filter(|i| !disclosed_indices.contains(i)).count())
+        },
+        MessageRef::Committed(index) if index < committed_count => Ok(hidden_signed_count + index),
+        _ => Err("Linked message must be an undisclosed message of the credential".to_string())
+    }
+}
+
+fn proof_transcript(points: [&G1Projective; 5], disclosed_messages: &[(usize, Scalar)], domain: &Scalar, presentation_header: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(PROOF_LABEL);
+
+    for point in points {
+        transcript.append_point(point);
+    }
+
+    transcript.append_bytes(&(disclosed_messages.len() as u64).to_be_bytes());
+
+    for (index, message) in disclosed_messages {
+        transcript.append_bytes(&(*index as u64).to_be_bytes());
+        transcript.append_scalar(message);
+    }
+
+    transcript.append_scalar(domain);
+    transcript.append_bytes(presentation_header);
+
+    transcript
+}
+
+pub fn proof_gen(request: &ProofGenRequest, linked: &mut [&mut dyn LinkedProver]) -> Result<Proof, String> {
+    let messages = request.messages;
+    let committed_messages = request.committed_messages;
+    let disclosed_indices = request.disclosed_indices;
+
+    if disclosed_indices.iter().any(|&i| i >= messages.len()) {
+        return Err("Disclosed index out of range".to_string());
+    }
+
+    let generators = Generators::new(messages.len(), committed_messages.len());
+    let domain = calculate_domain(request.public_key, messages.len(), committed_messages.len(), request.header);
+
+    let mut commitment = G1Projective::identity();
+
+    for (generator, message) in generators.committed.iter().zip(committed_messages) {
+        commitment += generator * message;
+    }
+
+    let b = calculate_b(&generators, &domain, messages, &commitment);
+
+    let hidden: Vec<(&G1Projective, &Scalar)> = generators.messages.iter().zip(messages).enumerate()
+        .filter(|(i, _)| !disclosed_indices.contains(i))
+        .map(|(_, pair)| pair)
+        .chain(generators.committed.iter().zip(committed_messages))
+        .collect();
+
+    let disclosed_messages: Vec<(usize, Scalar)> = messages.iter().enumerate()
+        .filter(|(i, _)| disclosed_indices.contains(i))
+        .map(|(i, m)| (i, *m))
+        .collect();
+
+    let r_1 = random_scalar();
+    let r_2 = random_scalar();
+    let e_tilde = random_scalar();
+    let r1_tilde = random_scalar();
+    let r3_tilde = random_scalar();
+    let m_tilde: Vec<Scalar> = hidden.iter().map(|_| random_scalar()).collect();
+
+    let d = b * r_2;
+    let a_bar = request.signature.a * (r_1 * r_2);
+    let b_bar = d * r_1 - a_bar * request.signature.e;
+
+    let t_1 = a_bar * e_tilde + d * r1_tilde;
+
+    let mut t_2 = d * r3_tilde;
+
+    for ((generator, _), blinding) in hidden.iter().zip(&m_tilde) {
+        t_2 += *generator * blinding;
+    }
+
+    let mut transcript = proof_transcript([&a_bar, &b_bar, &d, &t_1, &t_2], &disclosed_messages, &domain, request.presentation_header);
+
+    for prover in linked.iter_mut() {
+        let position = hidden_position(prover.message(), messages.len(), committed_messages.len(), disclosed_indices)?;
+
+        prover.commit(&m_tilde[position], &mut transcript)?;
+    }
+
+    let challenge = transcript.challenge();
+
+    for prover in linked.iter_mut() {
+        prover.respond(&challenge);
+    }
+
+    let r_3: Option<Scalar> = r_2.invert().into();
+    let r_3 = r_3.ok_or("Proof generation failed")?;
+
+    Ok(Proof {
+        a_bar,
+        b_bar,
+        d,
+        e_hat: e_tilde + request.signature.e * challenge,
+        r1_hat: r1_tilde - r_1 * challenge,
+        r3_hat: r3_tilde - r_3 * challenge,
+        m_hat: hidden.iter().zip(&m_tilde).map(|((_, message), blinding)| blinding + *message * challenge).collect(),
+        challenge
+    })
+}
+
+pub fn proof_verify(request: &ProofVerifyRequest, linked: &[&dyn LinkedVerifier]) -> bool {
+    let proof = request.proof;
+    let message_count = request.message_count;
+    let committed_count = request.committed_count;
+
+    if request.disclosed_messages.iter().any(|(i, _)| *i >= message_count) {
+        return false;
+    }
+
+    let mut disclosed_messages = request.disclosed_messages.to_vec();
+    disclosed_messages.sort_by_key(|(i, _)| *i);
+
+    // A repeated index would let a second, unsigned value ride along with the proven one.
+    if disclosed_messages.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        return false;
+    }
+
+    let disclosed_indices: Vec<usize> = disclosed_messages.iter().map(|(i, _)| *i).collect();
+
+    if proof.m_hat.len() != message_count - disclosed_messages.len() + committed_count {
+        return false;
+    }
+
+    if bool::from(proof.a_bar.is_identity()) || bool::from(proof.d.is_identity()) {
+        return false;
+    }
+
+    let generators = Generators::new(message_count, committed_count);
+    let domain = calculate_domain(request.public_key, message_count, committed_count, request.header);
+
+    let mut b_v = G1Projective::generator() + generators.q_1 * domain;
+
+    for (index, message) in &disclosed_messages {
+        b_v += generators.messages[*index] * message;
+    }
+
+    let hidden_generators: Vec<&G1Projective> = generators.messages.iter().enumerate()
+        .filter(|(i, _)| !disclosed_indices.contains(i))
+        .map(|(_, generator)| generator)
+        .chain(generators.committed.iter())
+        .collect();
+
+    let t_1 = proof.b_bar * proof.challenge + proof.a_bar * proof.e_hat + proof.d * proof.r1_hat;
+
+    let mut t_2 = b_v * proof.challenge + proof.d * proof.r3_hat;
+
+    for (generator, response) in hidden_generators.iter().zip(&proof.m_hat) {
+        t_2 += *generator * response;
+    }
+
+    let mut transcript = proof_transcript([&proof.a_bar, &proof.b_bar, &proof.d, &t_1, &t_2], &disclosed_messages, &domain, request.presentation_header);
+
+    for verifier in linked {
+        let position = match hidden_position(verifier.message(), message_count, committed_count, &disclosed_indices) {
+            Ok(position) => position,
+            Err(_) => return false
+        };
+
+        if verifier.reconstruct(&proof.m_hat[position], &proof.challenge, &mut transcript).is_err() {
+            return false;
+        }
+    }
+
+    if transcript.challenge() != proof.challenge {
+        return false;
+    }
+
+    pairing_is_identity(&proof.a_bar.to_affine(), &request.public_key.0, &(-proof.b_bar).to_affine(), &G2Affine::generator())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{message_to_scalar, messages_to_scalars, random_scalar, testing};
+    use crate::zkp::signature::verify;
+
+    const HEADER: &[u8] = b"wallet-header";
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    fn issue() -> (PublicKey, Signature, Vec<Scalar>, Vec<Scalar>) {
+        let messages = messages_to_scalars(&testing::card_messages());
+
+        let credential = testing::issue(&testing::issuer_key(), HEADER, &messages, random_scalar());
+
+        (credential.public_key, credential.signature, messages, credential.committed_messages().to_vec())
+    }
+
+    #[test]
+    fn blind_signature_verifies_only_with_holder_secret() {
+        let (public_key, signature, messages, committed_messages) = issue();
+
+        assert!(verify(&public_key, HEADER, &messages, &committed_messages, &signature));
+        assert!(!verify(&public_key, HEADER, &messages, &[random_scalar(), committed_messages[1]], &signature));
+        assert!(!verify(&public_key, HEADER, &messages, &[], &signature));
+    }
+
+    #[test]
+    fn proof_roundtrip_with_hidden_holder_secret() {
+        let (public_key, signature, messages, committed_messages) = issue();
+        let disclosed_indices = [0, 1, 7];
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &public_key,
+            signature: &signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &committed_messages,
+            disclosed_indices: &disclosed_indices
+        }, &mut []).unwrap();
+        let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+
+        let disclosed_messages: Vec<(usize, Scalar)> = disclosed_indices.iter().map(|&i| (i, messages[i])).collect();
+
+        let mut request = ProofVerifyRequest {
+            public_key: &public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: committed_messages.len(),
+            disclosed_messages: &disclosed_messages
+        };
+
+        assert!(proof_verify(&request, &[]));
+
+        request.presentation_header = b"other-presentation-header";
+
+        assert!(!proof_verify(&request, &[]));
+
+        let mut tampered_messages = disclosed_messages.clone();
+        tampered_messages[2].1 = message_to_scalar(b"2040-01-01");
+
+        request.presentation_header = PRESENTATION_HEADER;
+        request.disclosed_messages = &tampered_messages;
+
+        assert!(!proof_verify(&request, &[]));
+
+        // The signed value followed by another one for the same index.
+        let mut repeated_messages = disclosed_messages.clone();
+        repeated_messages.push((7, message_to_scalar(b"2040-01-01")));
+
+        request.disclosed_messages = &repeated_messages;
+
+        assert!(!proof_verify(&request, &[]));
+    }
+
+    #[test]
+    fn proof_gen_fails_without_holder_secret_knowledge() {
+        let (public_key, signature, messages, committed_messages) = issue();
+        let forged_committed_messages = [random_scalar(), committed_messages[1]];
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &public_key,
+            signature: &signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &forged_committed_messages,
+            disclosed_indices: &[0]
+        }, &mut []).unwrap();
+
+        assert!(!proof_verify(&ProofVerifyRequest {
+            public_key: &public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: committed_messages.len(),
+            disclosed_messages: &[(0, messages[0])]
+        }, &[]));
+    }
+}
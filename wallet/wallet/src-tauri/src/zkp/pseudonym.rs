@@ -0,0 +1,168 @@
+use blstrs::{G1Projective, Scalar};
+use group::{Curve, Group};
+
+use super::{g1_from_bytes, Transcript, G1_LENGTH};
+use super::proof::{LinkedProver, LinkedVerifier, MessageRef};
+
+const PSEUDONYM_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_PSEUDONYM_";
+const PSEUDONYM_LABEL: &[u8] = b"WALLET_BBS_PSEUDONYM_";
+
+pub const HOLDER_SECRET: MessageRef = MessageRef::Committed(0);
+
+fn context_generator(verifier_id: &[u8]) -> G1Projective {
+    G1Projective::hash_to_curve(verifier_id, PSEUDONYM_DST, &[])
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pseudonym(G1Projective);
+
+impl Pseudonym {
+    pub fn new(verifier_id: &[u8], holder_secret: &Scalar) -> Self {
+        Pseudonym(context_generator(verifier_id) * holder_secret)
+    }
+
+    pub fn to_bytes(&self) -> [u8; G1_LENGTH] {
+        self.0.to_affine().to_compressed()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let point = g1_from_bytes(bytes)?;
+
+        if bool::from(point.is_identity()) {
+            return Err("Invalid pseudonym".to_string());
+        }
+
+        Ok(Pseudonym(point))
+    }
+}
+
+fn append_pseudonym(transcript: &mut Transcript, verifier_id: &[u8], pseudonym: &Pseudonym, t: &G1Projective) {
+    transcript.append_bytes(PSEUDONYM_LABEL);
+    transcript.append_bytes(verifier_id);
+    transcript.append_point(&pseudonym.0);
+    transcript.append_point(t);
+}
+
+pub struct PseudonymProver<'a> {
+    verifier_id: &'a [u8],
+    pseudonym: Pseudonym
+}
+
+impl<'a> PseudonymProver<'a> {
+    pub fn new(verifier_id: &'a [u8], holder_secret: &Scalar) -> Self {
+        PseudonymProver {
+            verifier_id,
+            pseudonym: Pseudonym::new(verifier_id, holder_secret)
+        }
+    }
+
+    pub fn pseudonym(&self) -> Pseudonym {
+        self.pseudonym
+    }
+}
+
+impl LinkedProver for PseudonymProver<'_> {
+    fn message(&self) -> MessageRef {
+        HOLDER_SECRET
+    }
+
+    fn commit(&mut self, message_blinding: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let t = context_generator(self.verifier_id) * message_blinding;
+
+        append_pseudonym(transcript, self.verifier_id, &self.pseudonym, &t);
+
+        Ok(())
+    }
+
+    fn respond(&mut self, _challenge: &Scalar) {}
+}
+
+pub struct PseudonymVerifier<'a> {
+    verifier_id: &'a [u8],
+    pseudonym: Pseudonym
+}
+
+impl<'a> PseudonymVerifier<'a> {
+    pub fn new(verifier_id: &'a [u8], pseudonym: Pseudonym) -> Self {
+        PseudonymVerifier { verifier_id, pseudonym }
+    }
+}
+
+impl LinkedVerifier for PseudonymVerifier<'_> {
+    fn message(&self) -> MessageRef {
+        HOLDER_SECRET
+    }
+
+    fn reconstruct(&self, message_response: &Scalar, challenge: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let t = context_generator(self.verifier_id) * message_response - self.pseudonym.0 * challenge;
+
+        append_pseudonym(transcript, self.verifier_id, &self.pseudonym, &t);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{message_to_scalar, random_scalar, testing};
+    use crate::zkp::proof::{proof_gen, proof_verify, ProofGenRequest, ProofVerifyRequest};
+
+    const HEADER: &[u8] = b"wallet-header";
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    fn present(verifier_id: &[u8], holder_secret: &Scalar, claimed_verifier_id: &[u8]) -> (Pseudonym, bool) {
+        let messages: Vec<Scalar> = ["Doe", "John", "FR"].iter().map(|m| message_to_scalar(m.as_bytes())).collect();
+        let credential = testing::issue(&testing::issuer_key(), HEADER, &messages, *holder_secret);
+
+        let mut prover = PseudonymProver::new(verifier_id, holder_secret);
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &credential.public_key,
+            signature: &credential.signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &credential.committed_messages(),
+            disclosed_indices: &[2]
+        }, &mut [&mut prover]).unwrap();
+
+        let verifier = PseudonymVerifier::new(claimed_verifier_id, prover.pseudonym());
+
+        let verified = proof_verify(&ProofVerifyRequest {
+            public_key: &credential.public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: 2,
+            disclosed_messages: &[(2, messages[2])]
+        }, &[&verifier]);
+
+        (prover.pseudonym(), verified)
+    }
+
+    #[test]
+    fn pseudonym_is_stable_per_verifier_and_differs_across_verifiers() {
+        let holder_secret = random_scalar();
+
+        let (first, first_verified) = present(b"https://verifier-a.example", &holder_secret, b"https://verifier-a.example");
+        let (second, second_verified) = present(b"https://verifier-a.example", &holder_secret, b"https://verifier-a.example");
+        let (other, other_verified) = present(b"https://verifier-b.example", &holder_secret, b"https://verifier-b.example");
+
+        assert!(first_verified && second_verified && other_verified);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn pseudonym_is_bound_to_verifier_id_and_holder_secret() {
+        let holder_secret = random_scalar();
+
+        let (_, verified) = present(b"https://verifier-a.example", &holder_secret, b"https://verifier-b.example");
+        assert!(!verified);
+
+        let (other_holder, _) = present(b"https://verifier-a.example", &random_scalar(), b"https://verifier-a.example");
+        assert_ne!(other_holder, Pseudonym::new(b"https://verifier-a.example", &holder_secret));
+    }
+}
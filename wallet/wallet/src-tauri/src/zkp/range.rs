@@ -0,0 +1,374 @@
+use blstrs::{G1Projective, Scalar};
+use ff::Field;
+use group::{Curve, Group};
+
+use super::{create_generators, random_scalar, Reader, Transcript, G1_LENGTH, SCALAR_LENGTH};
+use super::proof::{LinkedProver, LinkedVerifier, MessageRef};
+
+const RANGE_GENERATOR_TAG: &[u8] = b"WALLET_BBS_RANGE_GENERATOR_";
+const RANGE_LABEL: &[u8] = b"WALLET_BBS_RANGE_";
+
+pub const RANGE_BITS: usize = 32;
+
+const BIT_PROOF_LENGTH: usize = G1_LENGTH + 3 * SCALAR_LENGTH;
+pub const RANGE_PROOF_LENGTH: usize = G1_LENGTH + SCALAR_LENGTH + RANGE_BITS * BIT_PROOF_LENGTH;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound {
+    AtMost(u64),
+    AtLeast(u64)
+}
+
+impl Bound {
+    fn difference(&self, value: u64) -> Option<u64> {
+        match *self {
+            Bound::AtMost(bound) => bound.checked_sub(value),
+            Bound::AtLeast(bound) => value.checked_sub(bound)
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 9] {
+        let (tag, bound) = match self {
+            Bound::AtMost(bound) => (0u8, bound),
+            Bound::AtLeast(bound) => (1u8, bound)
+        };
+
+        let mut bytes = [tag; 9];
+        bytes[1..].copy_from_slice(&bound.to_be_bytes());
+
+        bytes
+    }
+}
+
+struct RangeGenerators {
+    g: G1Projective,
+    h: G1Projective
+}
+
+impl RangeGenerators {
+    fn new() -> Self {
+        let generators = create_generators(RANGE_GENERATOR_TAG, 2);
+
+        RangeGenerators { g: generators[0], h: generators[1] }
+    }
+
+    // Commitment to the difference between the hidden value and the bound, which must
+    // equal the weighted sum of the bit commitments.
+    fn difference_commitment(&self, bound: Bound, commitment: &G1Projective) -> G1Projective {
+        match bound {
+            Bound::AtMost(bound) => self.g * Scalar::from(bound) - commitment,
+            Bound::AtLeast(bound) => commitment - self.g * Scalar::from(bound)
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct BitProof {
+    commitment: G1Projective,
+    zero_challenge: Scalar,
+    zero_response: Scalar,
+    one_response: Scalar
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeProof {
+    commitment: G1Projective,
+    blind_response: Scalar,
+    bits: Vec<BitProof>
+}
+
+impl RangeProof {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(RANGE_PROOF_LENGTH);
+        bytes.extend_from_slice(&self.commitment.to_affine().to_compressed());
+        bytes.extend_from_slice(&self.blind_response.to_bytes_be());
+
+        for bit in &self.bits {
+            bytes.extend_from_slice(&bit.commitment.to_affine().to_compressed());
+            bytes.extend_from_slice(&bit.zero_challenge.to_bytes_be());
+            bytes.extend_from_slice(&bit.zero_response.to_bytes_be());
+            bytes.extend_from_slice(&bit.one_response.to_bytes_be());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != RANGE_PROOF_LENGTH {
+            return Err("Invalid range proof length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+
+        let commitment = reader.g1()?;
+        let blind_response = reader.scalar()?;
+
+        let mut bits = Vec::with_capacity(RANGE_BITS);
+
+        for _ in 0..RANGE_BITS {
+            bits.push(BitProof {
+                commitment: reader.g1()?,
+                zero_challenge: reader.scalar()?,
+                zero_response: reader.scalar()?,
+                one_response: reader.scalar()?
+            });
+        }
+
+        Ok(RangeProof { commitment, blind_response, bits })
+    }
+}
+
+fn append_range(transcript: &mut Transcript, bound: Bound, commitment: &G1Projective, t: &G1Projective) {
+    transcript.append_bytes(RANGE_LABEL);
+    transcript.append_bytes(&bound.to_bytes());
+    transcript.append_point(commitment);
+    transcript.append_point(t);
+}
+
+fn append_bit(transcript: &mut Transcript, commitment: &G1Projective, zero_t: &G1Projective, one_t: &G1Projective) {
+    transcript.append_point(commitment);
+    transcript.append_point(zero_t);
+    transcript.append_point(one_t);
+}
+
+struct BitWitness {
+    bit: bool,
+    blind: Scalar,
+    nonce: Scalar,
+    simulated_challenge: Scalar,
+    simulated_response: Scalar
+}
+
+pub struct RangeProver {
+    message: MessageRef,
+    bound: Bound,
+    generators: RangeGenerators,
+    blind: Scalar,
+    blind_tilde: Scalar,
+    bits: Vec<BitWitness>,
+    proof: RangeProof
+}
+
+impl RangeProver {
+    pub fn new(message: MessageRef, value: u64, bound: Bound) -> Result<Self, String> {
+        let difference = bound.difference(value).ok_or("Value does not satisfy the predicate")?;
+
+        if difference >> RANGE_BITS != 0 {
+            return Err("Value is out of the provable range".to_string());
+        }
+
+        let generators = RangeGenerators::new();
+
+        let mut bits = Vec::with_capacity(RANGE_BITS);
+        let mut bit_proofs = Vec::with_capacity(RANGE_BITS);
+        let mut difference_blind = Scalar::ZERO;
+
+        for i in 0..RANGE_BITS {
+            let bit = (difference >> i) & 1 == 1;
+            let blind = random_scalar();
+
+            let mut commitment = generators.h * blind;
+
+            if bit {
+                commitment += generators.g;
+            }
+
+            difference_blind += Scalar::from(1u64 << i) * blind;
+
+            bits.push(BitWitness {
+                bit,
+                blind,
+                nonce: random_scalar(),
+                simulated_challenge: random_scalar(),
+                simulated_response: random_scalar()
+            });
+
+            bit_proofs.push(BitProof {
+                commitment,
+                zero_challenge: Scalar::ZERO,
+                zero_response: Scalar::ZERO,
+                one_response: Scalar::ZERO
+            });
+        }
+
+        let blind = match bound {
+            Bound::AtMost(_) => -difference_blind,
+            Bound::AtLeast(_) => difference_blind
+        };
+
+        Ok(RangeProver {
+            message,
+            bound,
+            proof: RangeProof {
+                commitment: generators.g * Scalar::from(value) + generators.h * blind,
+                blind_response: Scalar::ZERO,
+                bits: bit_proofs
+            },
+            generators,
+            blind,
+            blind_tilde: random_scalar(),
+            bits
+        })
+    }
+
+    pub fn proof(&self) -> &RangeProof {
+        &self.proof
+    }
+}
+
+impl LinkedProver for RangeProver {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn commit(&mut self, message_blinding: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let t = self.generators.g * message_blinding + self.generators.h * self.blind_tilde;
+
+        append_range(transcript, self.bound, &self.proof.commitment, &t);
+
+        for (witness, bit_proof) in self.bits.iter().zip(&self.proof.bits) {
+            let real_t = self.generators.h * witness.nonce;
+
+            // The branch the holder cannot open is simulated from a chosen challenge and response.
+            let simulated_target = if witness.bit { bit_proof.commitment } else { bit_proof.commitment - self.generators.g };
+            let simulated_t = self.generators.h * witness.simulated_response - simulated_target * witness.simulated_challenge;
+
+            let (zero_t, one_t) = if witness.bit { (simulated_t, real_t) } else { (real_t, simulated_t) };
+
+            append_bit(transcript, &bit_proof.commitment, &zero_t, &one_t);
+        }
+
+        Ok(())
+    }
+
+    fn respond(&mut self, challenge: &Scalar) {
+        self.proof.blind_response = self.blind_tilde + challenge * self.blind;
+
+        for (witness, bit_proof) in self.bits.iter().zip(self.proof.bits.iter_mut()) {
+            let real_challenge = challenge - witness.simulated_challenge;
+            let real_response = witness.nonce + real_challenge * witness.blind;
+
+            if witness.bit {
+                bit_proof.zero_challenge = witness.simulated_challenge;
+                bit_proof.zero_response = witness.simulated_response;
+                bit_proof.one_response = real_response;
+            } else {
+                bit_proof.zero_challenge = real_challenge;
+                bit_proof.zero_response = real_response;
+                bit_proof.one_response = witness.simulated_response;
+            }
+        }
+    }
+}
+
+pub struct RangeVerifier<'a> {
+    message: MessageRef,
+    bound: Bound,
+    proof: &'a RangeProof
+}
+
+impl<'a> RangeVerifier<'a> {
+    pub fn new(message: MessageRef, bound: Bound, proof: &'a RangeProof) -> Self {
+        RangeVerifier { message, bound, proof }
+    }
+}
+
+impl LinkedVerifier for RangeVerifier<'_> {
+    fn message(&self) -> MessageRef {
+        self.message
+    }
+
+    fn reconstruct(&self, message_response: &Scalar, challenge: &Scalar, transcript: &mut Transcript) -> Result<(), String> {
+        let generators = RangeGenerators::new();
+
+        if self.proof.bits.len() != RANGE_BITS {
+            return Err("Invalid range proof length".to_string());
+        }
+
+        let mut bits_sum = G1Projective::identity();
+
+        for (i, bit_proof) in self.proof.bits.iter().enumerate() {
+            bits_sum += bit_proof.commitment * Scalar::from(1u64 << i);
+        }
+
+        if bits_sum != generators.difference_commitment(self.bound, &self.proof.commitment) {
+            return Err("Bit commitments do not match the range commitment".to_string());
+        }
+
+        let t = generators.g * message_response + generators.h * self.proof.blind_response - self.proof.commitment * challenge;
+
+        append_range(transcript, self.bound, &self.proof.commitment, &t);
+
+        for bit_proof in &self.proof.bits {
+            let one_challenge = challenge - bit_proof.zero_challenge;
+
+            let zero_t = generators.h * bit_proof.zero_response - bit_proof.commitment * bit_proof.zero_challenge;
+            let one_t = generators.h * bit_proof.one_response - (bit_proof.commitment - generators.g) * one_challenge;
+
+            append_bit(transcript, &bit_proof.commitment, &zero_t, &one_t);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zkp::{message_to_scalar, messages_to_scalars, random_scalar, testing};
+    use crate::zkp::proof::{proof_gen, proof_verify, ProofGenRequest, ProofVerifyRequest};
+
+    const HEADER: &[u8] = b"wallet-header";
+    const PRESENTATION_HEADER: &[u8] = b"wallet-presentation-header";
+
+    const DATE_OF_BIRTH: MessageRef = MessageRef::Signed(1);
+
+    fn prove(date_of_birth: &str, value: u64, bound: Bound, claimed_bound: Bound) -> Result<bool, String> {
+        let messages = messages_to_scalars(&["Doe", date_of_birth, "FR"]);
+        let credential = testing::issue(&testing::issuer_key(), HEADER, &messages, random_scalar());
+
+        let mut prover = RangeProver::new(DATE_OF_BIRTH, value, bound)?;
+
+        let proof = proof_gen(&ProofGenRequest {
+            public_key: &credential.public_key,
+            signature: &credential.signature,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            messages: &messages,
+            committed_messages: &credential.committed_messages(),
+            disclosed_indices: &[2]
+        }, &mut [&mut prover])?;
+
+        let range_proof = RangeProof::from_bytes(&prover.proof().to_bytes())?;
+        let verifier = RangeVerifier::new(DATE_OF_BIRTH, claimed_bound, &range_proof);
+
+        Ok(proof_verify(&ProofVerifyRequest {
+            public_key: &credential.public_key,
+            header: HEADER,
+            presentation_header: PRESENTATION_HEADER,
+            proof: &proof,
+            message_count: messages.len(),
+            committed_count: 2,
+            disclosed_messages: &[(2, message_to_scalar(b"FR"))]
+        }, &[&verifier]))
+    }
+
+    #[test]
+    fn range_proof_accepts_satisfied_bounds() {
+        assert_eq!(prove("1990-01-01", 19900101, Bound::AtMost(20081018), Bound::AtMost(20081018)), Ok(true));
+        assert_eq!(prove("1990-01-01", 19900101, Bound::AtMost(19900101), Bound::AtMost(19900101)), Ok(true));
+        assert_eq!(prove("1990-01-01", 19900101, Bound::AtLeast(19000101), Bound::AtLeast(19000101)), Ok(true));
+    }
+
+    #[test]
+    fn range_proof_rejects_unsatisfied_or_mismatched_bounds() {
+        assert!(prove("1990-01-01", 19900101, Bound::AtMost(19891231), Bound::AtMost(19891231)).is_err());
+        assert_eq!(prove("1990-01-01", 19900101, Bound::AtMost(20081018), Bound::AtMost(19891231)), Ok(false));
+        assert_eq!(prove("1990-01-01", 19900101, Bound::AtMost(20081018), Bound::AtLeast(20081018)), Ok(false));
+    }
+
+    #[test]
+    fn range_proof_is_linked_to_the_signed_value() {
+        assert_eq!(prove("1990-01-01", 20100101, Bound::AtMost(20200101), Bound::AtMost(20200101)), Ok(false));
+    }
+}
@@ -0,0 +1,192 @@
+use blstrs::{Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Scalar};
+use ff::Field;
+use group::{prime::PrimeCurveAffine, Curve, Group};
+use pairing::{MillerLoopResult, MultiMillerLoop};
+use zeroize::Zeroizing;
+
+use crate::secret::SecretScalar;
+
+use super::{create_generators, g2_from_bytes, hash_to_scalar, Reader, G1_LENGTH, G2_LENGTH, SCALAR_LENGTH};
+
+const KEYGEN_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_KEYGEN_DST_";
+const DOMAIN_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2S_DOMAIN_";
+const SIGNATURE_DST: &[u8] = b"WALLET_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_H2S_SIGNATURE_";
+
+pub const MESSAGE_GENERATOR_TAG: &[u8] = b"MESSAGE_GENERATOR_";
+pub const COMMITTED_GENERATOR_TAG: &[u8] = b"COMMITTED_GENERATOR_";
+
+pub const SIGNATURE_LENGTH: usize = G1_LENGTH + SCALAR_LENGTH;
+
+// Wiped when dropped, through the scalar it wraps.
+pub struct SecretKey(pub(crate) SecretScalar);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PublicKey(pub(crate) G2Affine);
+
+impl SecretKey {
+    pub fn new(ikm: &[u8], key_info: &[u8]) -> Result<Self, String> {
+        if ikm.len() < 32 {
+            return Err("Key material must be at least 32 bytes".to_string());
+        }
+
+        let mut input = Zeroizing::new(ikm.to_vec());
+        input.extend_from_slice(&(key_info.len() as u16).to_be_bytes());
+        input.extend_from_slice(key_info);
+
+        let mut scalar = hash_to_scalar(&input, KEYGEN_DST);
+
+        if bool::from(scalar.is_zero()) {
+            return Err("Invalid key material".to_string());
+        }
+
+        Ok(SecretKey(SecretScalar::take(&mut scalar)))
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey((G2Projective::generator() * self.0.expose()).to_affine())
+    }
+
+    pub fn to_bytes(&self) -> [u8; SCALAR_LENGTH] {
+        self.0.expose().to_bytes_be()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let scalar = SecretScalar::from_bytes(bytes)?;
+
+        if bool::from(scalar.expose().is_zero()) {
+            return Err("Invalid secret key".to_string());
+        }
+
+        Ok(SecretKey(scalar))
+    }
+}
+
+impl PublicKey {
+    pub fn to_bytes(&self) -> [u8; G2_LENGTH] {
+        self.0.to_compressed()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let point = g2_from_bytes(bytes)?;
+
+        if bool::from(point.is_identity()) {
+            return Err("Invalid public key".to_string());
+        }
+
+        Ok(PublicKey(point))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Signature {
+    pub(crate) a: G1Projective,
+    pub(crate) e: Scalar
+}
+
+impl Signature {
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SIGNATURE_LENGTH];
+        bytes[..G1_LENGTH].copy_from_slice(&self.a.to_affine().to_compressed());
+        bytes[G1_LENGTH..].copy_from_slice(&self.e.to_bytes_be());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != SIGNATURE_LENGTH {
+            return Err("Invalid signature length".to_string());
+        }
+
+        let mut reader = Reader::new(bytes);
+        let a = reader.g1()?;
+        let e = reader.scalar()?;
+
+        if bool::from(a.is_identity()) {
+            return Err("Invalid signature".to_string());
+        }
+
+        Ok(Signature { a, e })
+    }
+}
+
+pub struct Generators {
+    pub q_1: G1Projective,
+    pub messages: Vec<G1Projective>,
+    pub committed: Vec<G1Projective>
+}
+
+impl Generators {
+    pub fn new(message_count: usize, committed_count: usize) -> Self {
+        let mut messages = create_generators(MESSAGE_GENERATOR_TAG, message_count + 1);
+        let q_1 = messages.remove(0);
+
+        Generators {
+            q_1,
+            messages,
+            committed: create_generators(COMMITTED_GENERATOR_TAG, committed_count)
+        }
+    }
+}
+
+pub fn calculate_domain(public_key: &PublicKey, message_count: usize, committed_count: usize, header: &[u8]) -> Scalar {
+    let mut input = public_key.to_bytes().to_vec();
+    input.extend_from_slice(&(message_count as u64).to_be_bytes());
+    input.extend_from_slice(&(committed_count as u64).to_be_bytes());
+    input.extend_from_slice(&(header.len() as u64).to_be_bytes());
+    input.extend_from_slice(header);
+
+    hash_to_scalar(&input, DOMAIN_DST)
+}
+
+pub fn calculate_b(generators: &Generators, domain: &Scalar, messages: &[Scalar], commitment: &G1Projective) -> G1Projective {
+    let mut b = G1Projective::generator() + generators.q_1 * domain + commitment;
+
+    for (generator, message) in generators.messages.iter().zip(messages) {
+        b += generator * message;
+    }
+
+    b
+}
+
+pub fn sign(secret_key: &SecretKey, public_key: &PublicKey, header: &[u8], messages: &[Scalar], commitment: Option<(&G1Projective, usize)>) -> Result<Signature, String> {
+    let (commitment, committed_count) = commitment.map(|(c, n)| (*c, n)).unwrap_or((G1Projective::identity(), 0));
+
+    let generators = Generators::new(messages.len(), committed_count);
+    let domain = calculate_domain(public_key, messages.len(), committed_count, header);
+    let b = calculate_b(&generators, &domain, messages, &commitment);
+
+    let mut input = Zeroizing::new(secret_key.0.expose().to_bytes_be().to_vec());
+    input.extend_from_slice(&b.to_affine().to_compressed());
+    input.extend_from_slice(&domain.to_bytes_be());
+
+    let e = hash_to_scalar(&input, SIGNATURE_DST);
+
+    let inverse: Option<Scalar> = (secret_key.0.expose() + e).invert().into();
+    let a = b * inverse.ok_or("Signature generation failed")?;
+
+    Ok(Signature { a, e })
+}
+
+pub fn verify(public_key: &PublicKey, header: &[u8], messages: &[Scalar], committed_messages: &[Scalar], signature: &Signature) -> bool {
+    let generators = Generators::new(messages.len(), committed_messages.len());
+    let domain = calculate_domain(public_key, messages.len(), committed_messages.len(), header);
+
+    let mut commitment = G1Projective::identity();
+
+    for (generator, message) in generators.committed.iter().zip(committed_messages) {
+        commitment += generator * message;
+    }
+
+    let b = calculate_b(&generators, &domain, messages, &commitment);
+
+    let w_e = G2Affine::from(G2Projective::from(public_key.0) + G2Projective::generator() * signature.e);
+
+    pairing_is_identity(&signature.a.to_affine(), &w_e, &(-b).to_affine(), &G2Affine::generator())
+}
+
+pub(crate) fn pairing_is_identity(p_1: &G1Affine, q_1: &G2Affine, p_2: &G1Affine, q_2: &G2Affine) -> bool {
+    let q_1 = G2Prepared::from(*q_1);
+    let q_2 = G2Prepared::from(*q_2);
+
+    bool::from(Bls12::multi_miller_loop(&[(p_1, &q_1), (p_2, &q_2)]).final_exponentiation().is_identity())
+}
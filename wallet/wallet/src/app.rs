@@ -11,6 +11,12 @@ use leptos::prelude::Effect;
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn invoke_result(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
 }
 
 pub fn set_timeout<F>(f: F, ms: i32)
@@ -22,11 +28,21 @@ pub fn set_timeout<F>(f: F, ms: i32)
         window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(cb.unchecked_ref(), ms).unwrap();
     }
 
+const EU_COUNTRIES: [&str; 27] = ["AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU", "IE", "IT", "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum DeepLink {
+    CredentialOffer { uri: String },
+    PresentationRequest { uri: String }
+}
+
 #[derive(Clone)]
 struct Ctx {
     page_name: WriteSignal<String>,
     load_wallet_surname: WriteSignal<String>,
     load_wallet_name: WriteSignal<String>,
+    deep_link: RwSignal<Option<DeepLink>>,
 }
 
 #[component]
@@ -36,11 +52,50 @@ pub fn App() -> impl IntoView {
 
     let (load_wallet_surname, set_load_wallet_surname) = signal(String::new());
     let (load_wallet_name, set_load_wallet_name) = signal(String::new());
+    let deep_link = RwSignal::new(None::<DeepLink>);
 
     provide_context(Ctx {
         page_name: set_page_name,
         load_wallet_surname: set_load_wallet_surname,
-        load_wallet_name: set_load_wallet_name
+        load_wallet_name: set_load_wallet_name,
+        deep_link
+    });
+
+    let take_deep_link = move || {
+        spawn_local(async move {
+            let link: Option<DeepLink> = serde_wasm_bindgen::from_value(invoke("take_deep_link", JsValue::NULL).await).unwrap_or(None);
+
+            if link.is_some() {
+                deep_link.set(link);
+            }
+        });
+    };
+
+    // The link the wallet was launched with, then the ones opened while it runs.
+    take_deep_link();
+
+    spawn_local(async move {
+        let handler = Closure::<dyn FnMut(JsValue)>::new(move |_event: JsValue| {
+            take_deep_link();
+        });
+
+        listen("deep-link", &handler).await;
+        handler.forget();
+    });
+
+    // Links wait for the login, then open the issuance page for an offer or the wallet for a verifier request.
+    Effect::new(move |_| {
+        let target = match deep_link.get() {
+            Some(DeepLink::CredentialOffer { .. }) => "create_identity_card",
+            Some(DeepLink::PresentationRequest { .. }) => "wallet",
+            None => return
+        };
+
+        let page = page_name.get();
+
+        if page != "login_account" && page != "create_account" && page != "recover_account" && page != target {
+            set_page_name.set(String::from(target));
+        }
     });
 
     view! {
@@ -53,6 +108,9 @@ pub fn App() -> impl IntoView {
                     "create_account" => view! {
                         <CreateAccountPage/>
                     }.into_any(),
+                    "recover_account" => view! {
+                        <RecoverAccountPage/>
+                    }.into_any(),
                     "create_identity_card" => view! {
                         <CreateIdentityCardPage
                             account_surname = { load_wallet_surname.get() }
@@ -65,6 +123,21 @@ pub fn App() -> impl IntoView {
                             account_name = { load_wallet_name.get() }
                         />
                     }.into_any(),
+                    "trusted_issuers" => view! {
+                        <TrustedIssuersPage/>
+                    }.into_any(),
+                    "history" => view! {
+                        <HistoryPage
+                            account_surname = { load_wallet_surname.get() }
+                            account_name = { load_wallet_name.get() }
+                        />
+                    }.into_any(),
+                    "security" => view! {
+                        <SecurityPage
+                            account_surname = { load_wallet_surname.get() }
+                            account_name = { load_wallet_name.get() }
+                        />
+                    }.into_any(),
                     _ => view! {
                         <p>Error 404</p>
                     }.into_any()
@@ -82,8 +155,31 @@ pub fn LoginAccountPage() -> impl IntoView {
     let (name, set_name) = signal(String::new());
     let (password, set_password) = signal(String::new());
 
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum LoginError {
+        WrongCredentials { remaining_attempts: Option<u32> },
+        Locked { until: String, retry_after_seconds: i64 },
+        Failed { error: String }
+    }
+
+    impl LoginError {
+        fn message(&self) -> String {
+            match self {
+                LoginError::WrongCredentials { remaining_attempts: Some(remaining) } => format!("Incorrect username or password. {} attempt(s) left before the login is delayed.", remaining),
+                LoginError::WrongCredentials { remaining_attempts: None } => String::from("Incorrect username or password. Please try again."),
+                LoginError::Locked { until, retry_after_seconds } => {
+                    let until = js_sys::Date::new(&JsValue::from_str(until)).to_locale_time_string("default");
+
+                    format!("Too many failed attempts. Login locked until {} ({} s).", until, retry_after_seconds)
+                },
+                LoginError::Failed { error } => error.clone()
+            }
+        }
+    }
+
     let (login_successful, set_login_successful) = signal(false);
-    let (login_error, set_login_error) = signal(false);
+    let (login_error, set_login_error) = signal(String::new());
 
     let check_account = move |surname: String, name: String, password: String| {
         spawn_local(async move {
@@ -100,23 +196,29 @@ pub fn LoginAccountPage() -> impl IntoView {
                 password: password.clone()
             }).unwrap();
         
-            let response = invoke("login_account", args).await.as_bool().unwrap();
-            
-            if response == true {
-                set_login_successful.set(true);
+            match invoke_result("login_account", args).await {
+                Ok(_) => {
+                    set_login_successful.set(true);
 
-                ctx.load_wallet_surname.set(surname.clone());
-                ctx.load_wallet_name.set(name.clone());
-                
-                set_timeout(move || {
-                    ctx.page_name.set(String::from("wallet"));
-                }, 2500);
-            } else {
-                set_login_error.set(true);
-
-                set_timeout(move || {
-                    set_login_error.set(false);
-                }, 5000);
+                    ctx.load_wallet_surname.set(surname.clone());
+                    ctx.load_wallet_name.set(name.clone());
+
+                    set_timeout(move || {
+                        ctx.page_name.set(String::from("wallet"));
+                    }, 2500);
+                },
+                Err(error) => {
+                    let message = match serde_wasm_bindgen::from_value::<LoginError>(error) {
+                        Ok(error) => error.message(),
+                        Err(_) => String::from("Incorrect username or password. Please try again.")
+                    };
+
+                    set_login_error.set(message);
+
+                    set_timeout(move || {
+                        set_login_error.set(String::new());
+                    }, 5000);
+                }
             };
         });
     };
@@ -158,11 +260,267 @@ pub fn LoginAccountPage() -> impl IntoView {
                         ctx.page_name.set(String::from("create_account"));
                     }
                 >Create an account</p>
+                <p class="text-center cursor-pointer underline mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("recover_account"));
+                    }
+                >Forgot your password?</p>
+            </fieldset>
+            <Show when=move || login_error.get() != String::from("")>
+                <div class="toast">
+                    <div class="alert alert-error">
+                        <span>{move || login_error.get()}</span>
+                    </div>
+                </div>
+            </Show>
+        </main>
+    }
+}
+
+#[component]
+pub fn PasswordStrengthMeter(surname: ReadSignal<String>, name: ReadSignal<String>, password: ReadSignal<String>) -> impl IntoView {
+    #[derive(Serialize, Deserialize, Clone)]
+    struct PasswordStrength {
+        score: u8,
+        acceptable: bool,
+        #[serde(default)]
+        warning: Option<String>,
+        suggestions: Vec<String>
+    }
+
+    let (strength, set_strength) = signal(None::<PasswordStrength>);
+
+    // Estimated by the backend, which applies the same rules when the password is saved.
+    Effect::new(move |_| {
+        let password = password.get();
+
+        if password.is_empty() {
+            set_strength.set(None);
+            return;
+        }
+
+        let surname = surname.get();
+        let name = name.get();
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                password: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname,
+                name,
+                password
+            }).unwrap();
+
+            set_strength.set(serde_wasm_bindgen::from_value(invoke("password_strength", args).await).ok());
+        });
+    });
+
+    view! {
+        <Show when=move || strength.get().is_some()>
+            {
+                move || {
+                    let strength = strength.get().unwrap();
+
+                    let (label, color) = match strength.score {
+                        0 => ("Very weak", "progress-error"),
+                        1 => ("Weak", "progress-error"),
+                        2 => ("Fair", "progress-warning"),
+                        3 => ("Good", "progress-success"),
+                        _ => ("Strong", "progress-success")
+                    };
+
+                    view! {
+                        <progress class={format!("progress w-full {}", color)} value={(strength.score as u32 + 1).to_string()} max="5"></progress>
+                        <p class="text-xs">{format!("{}{}", label, if strength.acceptable { "" } else { ", not accepted" })}</p>
+                        <p class="text-xs opacity-70">
+                            {strength.warning.into_iter().chain(strength.suggestions).collect::<Vec<String>>().join(" ")}
+                        </p>
+                    }
+                }
+            }
+        </Show>
+    }
+}
+
+#[component]
+pub fn RecoveryPhraseConfirmation(phrase: String, on_confirmed: Callback<()>) -> impl IntoView {
+    let words: Vec<String> = phrase.split_whitespace().map(String::from).collect();
+
+    // Three words asked back at random positions, so that the phrase has to be written down.
+    let mut positions: Vec<usize> = vec![];
+    while positions.len() < 3.min(words.len()) {
+        let position = (js_sys::Math::random() * words.len() as f64) as usize;
+
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+    positions.sort();
+
+    let (answers, set_answers) = signal(vec![String::new(); positions.len()]);
+    let (confirmation_error, set_confirmation_error) = signal(false);
+
+    let expected: Vec<String> = positions.iter().map(|&position| words[position].clone()).collect();
+
+    view! {
+        <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+            <h1 class="text-center text-xl">Recovery phrase</h1>
+            <p>Write these words down in order and keep them offline. They are the only way back into the wallet if the password is forgotten, and they will not be shown again.</p>
+            <ol class="list-decimal list-inside grid grid-cols-2 gap-1 bg-base-100 rounded-box p-2 mt-2 font-mono">
+                {words.iter().map(|word| view! { <li>{word.clone()}</li> }).collect::<Vec<_>>()}
+            </ol>
+            {
+                positions.iter().enumerate().map(|(i, &position)| view! {
+                    <label class="label mt-2">{format!("Word {}", position + 1)}</label>
+                    <input type="text" class="input w-full" autocomplete="off"
+                        on:input = move |ev| {
+                            set_answers.update(|answers| answers[i] = event_target_value(&ev).trim().to_lowercase());
+                        }
+                    />
+                }).collect::<Vec<_>>()
+            }
+            <button class="btn btn-neutral mt-4"
+                on:click = move |_| {
+                    if answers.get() == expected {
+                        on_confirmed.run(());
+                    } else {
+                        set_confirmation_error.set(true);
+
+                        set_timeout(move || {
+                            set_confirmation_error.set(false);
+                        }, 5000);
+                    }
+                }
+            >I have written it down</button>
+            <Show when=move || confirmation_error.get()>
+                <div class="toast">
+                    <div class="alert alert-error">
+                        <span>The words do not match the recovery phrase.</span>
+                    </div>
+                </div>
+            </Show>
+        </fieldset>
+    }
+}
+
+#[component]
+pub fn RecoverAccountPage() -> impl IntoView {
+    let ctx = use_context::<Ctx>().unwrap();
+
+    let (surname, set_surname) = signal(String::new());
+    let (name, set_name) = signal(String::new());
+    let (recovery_phrase, set_recovery_phrase) = signal(String::new());
+    let (new_password, set_new_password) = signal(String::new());
+    let (confirm_password, set_confirm_password) = signal(String::new());
+
+    let (recovery_successful, set_recovery_successful) = signal(false);
+    let (recovery_error, set_recovery_error) = signal(String::new());
+
+    let show_error = move |error: String| {
+        set_recovery_error.set(error);
+
+        set_timeout(move || {
+            set_recovery_error.set(String::new());
+        }, 5000);
+    };
+
+    let recover_account = move || {
+        if new_password.get_untracked() != confirm_password.get_untracked() {
+            show_error(String::from("The two passwords must be identical."));
+            return;
+        }
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                recoveryPhrase: String,
+                newPassword: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                recoveryPhrase: recovery_phrase.get_untracked(),
+                newPassword: new_password.get_untracked()
+            }).unwrap();
+
+            match invoke_result("recover_account", args).await {
+                Ok(_) => {
+                    set_recovery_successful.set(true);
+
+                    ctx.load_wallet_surname.set(surname.get_untracked());
+                    ctx.load_wallet_name.set(name.get_untracked());
+
+                    set_timeout(move || {
+                        ctx.page_name.set(String::from("wallet"));
+                    }, 2500);
+                },
+                Err(error) => show_error(error.as_string().unwrap_or_default())
+            };
+        });
+    };
+
+    view! {
+        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
+            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+                <h1 class="text-center text-xl">Recover account</h1>
+                <label class="label mt-2">Surname</label>
+                <input type="text" class="input mb-2" placeholder="Surname"
+                    on:input = move |ev| {
+                        set_surname.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label mt-2">Name</label>
+                <input type="text" class="input mb-2" placeholder="Name"
+                    on:input = move |ev| {
+                        set_name.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label mt-2">Recovery phrase</label>
+                <textarea class="textarea h-24 w-full" placeholder="24 words" autocomplete="off"
+                    on:input = move |ev| {
+                        set_recovery_phrase.set(event_target_value(&ev));
+                    }
+                ></textarea>
+                <label class="label mt-2">New password</label>
+                <input type="password" class="input mb-2" placeholder="New password"
+                    on:input = move |ev| {
+                        set_new_password.set(event_target_value(&ev));
+                    }
+                />
+                <PasswordStrengthMeter surname = surname name = name password = new_password/>
+                <input type="password" class="input mb-2 mt-2" placeholder="Confirm the new password"
+                    on:input = move |ev| {
+                        set_confirm_password.set(event_target_value(&ev));
+                    }
+                />
+                <Show when=move || recovery_successful.get() == false>
+                    <button class="btn btn-neutral mt-4"
+                        on:click = move |_| {
+                            recover_account();
+                        }
+                    >Reset password</button>
+                </Show>
+                <Show when=move || recovery_successful.get() == true>
+                    <span class="loading loading-spinner loading-xl mx-auto block"></span>
+                </Show>
+                <p class="text-center cursor-pointer underline mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("login_account"));
+                    }
+                >Back to login</p>
             </fieldset>
-            <Show when=move || login_error.get() == true>
+            <Show when=move || recovery_error.get() != String::from("")>
                 <div class="toast">
                     <div class="alert alert-error">
-                        <span>Incorrect username or password. Please try again.</span>
+                        <span>{move || recovery_error.get()}</span>
                     </div>
                 </div>
             </Show>
@@ -180,6 +538,7 @@ pub fn CreateAccountPage() -> impl IntoView {
     let (confirm_password, set_confirm_password) = signal(String::new());
     
     let (creation_successful, set_creation_successful) = signal(false);
+    let (recovery_phrase, set_recovery_phrase) = signal(String::new());
     let (fields_completion_error, set_fields_completion_error) = signal(false);
     let (fields_completion_error_message, set_fields_completion_error_message) = signal(String::new());
 
@@ -210,17 +569,24 @@ pub fn CreateAccountPage() -> impl IntoView {
                     password: password.clone()
                 }).unwrap();
             
-                let response = invoke("create_account", args).await.as_bool().unwrap();
+                match invoke_result("create_account", args).await {
+                    Ok(phrase) => {
+                        set_creation_successful.set(true);
 
-                if response == true {
-                    set_creation_successful.set(true);
+                        ctx.load_wallet_surname.set(surname.clone());
+                        ctx.load_wallet_name.set(name.clone());
 
-                    ctx.load_wallet_surname.set(surname.clone());
-                    ctx.load_wallet_name.set(name.clone());
+                        // The wallet opens once the recovery phrase is confirmed.
+                        set_recovery_phrase.set(phrase.as_string().unwrap_or_default());
+                    },
+                    Err(error) => {
+                        set_fields_completion_error.set(true);
+                        set_fields_completion_error_message.set(error.as_string().unwrap_or_default());
 
-                    set_timeout(move || {
-                        ctx.page_name.set(String::from("create_identity_card"));
-                    }, 2500);
+                        set_timeout(move || {
+                            set_fields_completion_error.set(false);
+                        }, 5000);
+                    }
                 };
             });
         } else {
@@ -235,48 +601,59 @@ pub fn CreateAccountPage() -> impl IntoView {
 
     view! {
         <main class="flex items-center justify-center min-h-screen py-4 flex-col">
-            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
-                <h1 class="text-center text-xl">Create account</h1>
-                <label class="label mt-2">Surname</label>
-                <input type="text" class="input mb-2" placeholder="Surname"
-                    on:input = move |ev| {
-                        set_surname.set(event_target_value(&ev));
-                    }
-                />
-                <label class="label mt-2">Name</label>
-                <input type="text" class="input mb-2" placeholder="Name"
-                    on:input = move |ev| {
-                        set_name.set(event_target_value(&ev));
-                    }
-                />
-                <label class="label mt-2">Password</label>
-                <input type="password" class="input mb-2" placeholder="Password"
-                    on:input = move |ev| {
-                        set_password.set(event_target_value(&ev));
-                    }
-                />
-                <label class="label mt-2">Confirm your password</label>
-                <input type="password" class="input mb-2" placeholder="Password"
-                    on:input = move |ev| {
-                        set_confirm_password.set(event_target_value(&ev));
-                    }
+            <Show when=move || recovery_phrase.get() != String::from("")>
+                <RecoveryPhraseConfirmation
+                    phrase = recovery_phrase.get()
+                    on_confirmed = Callback::new(move |_| {
+                        ctx.page_name.set(String::from("create_identity_card"));
+                    })
                 />
-                <Show when=move || creation_successful.get() == false>
-                    <button class="btn btn-neutral mt-4"
+            </Show>
+            <Show when=move || recovery_phrase.get() == String::from("")>
+                <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+                    <h1 class="text-center text-xl">Create account</h1>
+                    <label class="label mt-2">Surname</label>
+                    <input type="text" class="input mb-2" placeholder="Surname"
+                        on:input = move |ev| {
+                            set_surname.set(event_target_value(&ev));
+                        }
+                    />
+                    <label class="label mt-2">Name</label>
+                    <input type="text" class="input mb-2" placeholder="Name"
+                        on:input = move |ev| {
+                            set_name.set(event_target_value(&ev));
+                        }
+                    />
+                    <label class="label mt-2">Password</label>
+                    <input type="password" class="input mb-2" placeholder="Password"
+                        on:input = move |ev| {
+                            set_password.set(event_target_value(&ev));
+                        }
+                    />
+                    <PasswordStrengthMeter surname = surname name = name password = password/>
+                    <label class="label mt-2">Confirm your password</label>
+                    <input type="password" class="input mb-2" placeholder="Password"
+                        on:input = move |ev| {
+                            set_confirm_password.set(event_target_value(&ev));
+                        }
+                    />
+                    <Show when=move || creation_successful.get() == false>
+                        <button class="btn btn-neutral mt-4"
+                            on:click = move |_| {
+                                create_account(surname.get(), name.get(), password.get(), confirm_password.get());
+                            }
+                        >Create account</button>
+                    </Show>
+                    <Show when=move || creation_successful.get() == true>
+                        <span class="loading loading-spinner loading-xl mx-auto block"></span>
+                    </Show>
+                    <p class="text-center cursor-pointer underline mt-2"
                         on:click = move |_| {
-                            create_account(surname.get(), name.get(), password.get(), confirm_password.get());
+                            ctx.page_name.set(String::from("login_account"));
                         }
-                    >Create account</button>
-                </Show>
-                <Show when=move || creation_successful.get() == true>
-                    <span class="loading loading-spinner loading-xl mx-auto block"></span>
-                </Show>
-                <p class="text-center cursor-pointer underline mt-2"
-                    on:click = move |_| {
-                        ctx.page_name.set(String::from("login_account"));
-                    }
-                >Login to an account</p>
-            </fieldset>
+                    >Login to an account</p>
+                </fieldset>
+            </Show>
             <Show when=move || fields_completion_error.get() == true>
                 <div class="toast">
                     <div class="alert alert-error">
@@ -310,16 +687,28 @@ pub fn CreateIdentityCardPage(account_surname: String, account_name: String) ->
     let (place_of_birth, set_place_of_birth) = signal(String::new());
     let (document_no, set_document_no) = signal(String::new());
     let (expiry_date, set_expiry_date) = signal(String::new());
+    let (ciphersuite, set_ciphersuite) = signal(String::from("BLS12-381-SHA-256"));
 
     let (creation_successful, set_creation_successful) = signal(false);
     let (fields_completion_error, set_fields_completion_error) = signal(false);
 
+    let (credential_offer, set_credential_offer) = signal(String::new());
+    let (tx_code, set_tx_code) = signal(String::new());
+    let (issuance_error, set_issuance_error) = signal(String::new());
+
     Effect::new(move |_| {
         set_surname.set(account_surname.clone());
         set_name.set(account_name.clone());
     });
 
-    let create_card = move |surname: String, name: String, country: String, sex: String, date_of_birth: String, place_of_birth: String, document_no: String, expiry_date: String| {
+    Effect::new(move |_| {
+        if let Some(DeepLink::CredentialOffer { uri }) = ctx.deep_link.get() {
+            set_credential_offer.set(uri);
+            ctx.deep_link.set(None);
+        }
+    });
+
+    let create_card = move |surname: String, name: String, country: String, sex: String, date_of_birth: String, place_of_birth: String, document_no: String, expiry_date: String, ciphersuite: String| {
         if surname == String::from("") || name == String::from("") || country == String::from("") || sex == String::from("") || date_of_birth == String::from("") || place_of_birth == String::from("") || document_no == String::from("") || expiry_date == String::from("") {
             set_fields_completion_error.set(true);
 
@@ -340,7 +729,8 @@ pub fn CreateIdentityCardPage(account_surname: String, account_name: String) ->
                 dateOfBirth: String,
                 placeOfBirth: String,
                 documentNo: String,
-                expiryDate: String
+                expiryDate: String,
+                ciphersuite: String
             }
             
             let args = serde_wasm_bindgen::to_value(&Args {
@@ -351,27 +741,81 @@ pub fn CreateIdentityCardPage(account_surname: String, account_name: String) ->
                 dateOfBirth: date_of_birth.clone(),
                 placeOfBirth: place_of_birth.clone(),
                 documentNo: document_no.clone(),
-                expiryDate: expiry_date.clone()
+                expiryDate: expiry_date.clone(),
+                ciphersuite: ciphersuite.clone()
             }).unwrap();
             
-            let response = invoke("create_card", args).await.as_bool().unwrap();
+            match invoke_result("create_card", args).await {
+                Ok(_) => {
+                    set_creation_successful.set(true);
 
-            if response == true {
-                set_creation_successful.set(true);
+                    set_timeout(move || {
+                        ctx.page_name.set(String::from("wallet"));
+                    }, 2500);
+                },
+                Err(error) => {
+                    set_issuance_error.set(error.as_string().unwrap_or_default());
 
-                set_timeout(move || {
-                    ctx.page_name.set(String::from("wallet"));
-                }, 2500);
+                    set_timeout(move || {
+                        set_issuance_error.set(String::new());
+                    }, 5000);
+                }
             };
         });
     };
 
-    view! {
-        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
-            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
-                <h1 class="text-center text-xl">Create identity card</h1>
-                <div class="hover-3d mt-2 mb-2">
-                    <div class="card w-128 bg-black text-white bg-[radial-gradient(circle_at_bottom_left,#ffffff04_35%,transparent_36%),radial-gradient(circle_at_top_right,#ffffff04_35%,transparent_36%)] bg-size-[4.95em_4.95em]">
+    let receive_credential = move |surname: String, name: String, credential_offer: String, tx_code: String| {
+        if credential_offer == String::from("") {
+            set_fields_completion_error.set(true);
+
+            set_timeout(move || {
+                set_fields_completion_error.set(false);
+            }, 5000);
+
+            return;
+        };
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                credentialOffer: String,
+                txCode: Option<String>
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.clone(),
+                name: name.clone(),
+                credentialOffer: credential_offer.clone(),
+                txCode: if tx_code == String::from("") { None } else { Some(tx_code.clone()) }
+            }).unwrap();
+
+            match invoke_result("receive_credential", args).await {
+                Ok(_) => {
+                    set_creation_successful.set(true);
+
+                    set_timeout(move || {
+                        ctx.page_name.set(String::from("wallet"));
+                    }, 2500);
+                },
+                Err(error) => {
+                    set_issuance_error.set(error.as_string().unwrap_or_default());
+
+                    set_timeout(move || {
+                        set_issuance_error.set(String::new());
+                    }, 5000);
+                }
+            };
+        });
+    };
+
+    view! {
+        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
+            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+                <h1 class="text-center text-xl">Create identity card</h1>
+                <div class="hover-3d mt-2 mb-2">
+                    <div class="card w-128 bg-black text-white bg-[radial-gradient(circle_at_bottom_left,#ffffff04_35%,transparent_36%),radial-gradient(circle_at_top_right,#ffffff04_35%,transparent_36%)] bg-size-[4.95em_4.95em]">
                         <div class="card-body">
                             <div class="flex justify-between">
                                 <div class="font-bold">IDENTITY CARD</div>
@@ -561,12 +1005,44 @@ pub fn CreateIdentityCardPage(account_surname: String, account_name: String) ->
                         set_expiry_date.set(event_target_value(&ev));
                     }
                 />
+                <label class="label mt-2">Ciphersuite</label>
+                <select class="select select-bordered mb-2 w-full">
+                    <option selected
+                        on:click = move |_| {
+                            set_ciphersuite.set(String::from("BLS12-381-SHA-256"));
+                        }
+                    >BLS12-381-SHA-256</option>
+                    <option
+                        on:click = move |_| {
+                            set_ciphersuite.set(String::from("BLS12-381-SHAKE-256"));
+                        }
+                    >BLS12-381-SHAKE-256</option>
+                </select>
                 <Show when=move || creation_successful.get() == false>
                     <button class="btn btn-neutral mt-4"
                         on:click = move |_| {
-                            create_card(surname.get(), name.get(), country.get(), sex.get(), date_of_birth.get(), place_of_birth.get(), document_no.get(), expiry_date.get());
+                            create_card(surname.get(), name.get(), country.get(), sex.get(), date_of_birth.get(), place_of_birth.get(), document_no.get(), expiry_date.get(), ciphersuite.get());
                         }
                     >Create card</button>
+                    <div class="divider">OR</div>
+                    <label class="label">Credential offer</label>
+                    <input type="text" class="input mb-2 w-full" placeholder="openid-credential-offer://?credential_offer_uri=..."
+                        prop:value = move || credential_offer.get()
+                        on:input = move |ev| {
+                            set_credential_offer.set(event_target_value(&ev));
+                        }
+                    />
+                    <label class="label mt-2">Transaction code</label>
+                    <input type="text" class="input mb-2 w-full" placeholder="Optional"
+                        on:input = move |ev| {
+                            set_tx_code.set(event_target_value(&ev));
+                        }
+                    />
+                    <button class="btn btn-neutral mt-4"
+                        on:click = move |_| {
+                            receive_credential(surname.get(), name.get(), credential_offer.get(), tx_code.get());
+                        }
+                    >Receive from issuer</button>
                 </Show>
                 <Show when=move || creation_successful.get() == true>
                     <span class="loading loading-spinner loading-xl mx-auto block"></span>
@@ -579,118 +1055,1339 @@ pub fn CreateIdentityCardPage(account_surname: String, account_name: String) ->
                     </div>
                 </div>
             </Show>
+            <Show when=move || issuance_error.get() != String::from("")>
+                <div class="toast">
+                    <div class="alert alert-error">
+                        <span>{move || issuance_error.get()}</span>
+                    </div>
+                </div>
+            </Show>
         </main>
     }
 }
 
 #[component]
-pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
-    #[derive(Serialize, Deserialize)]
-    struct IdentityCard {
-        surname: String,
+pub fn TrustedIssuersPage() -> impl IntoView {
+    let ctx = use_context::<Ctx>().unwrap();
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct TrustedIssuer {
+        id: String,
         name: String,
-        country: String,
-        sex: String,
-        dateOfBirth: String,
-        placeOfBirth: String,
-        documentNo: String,
-        expiryDate: String
+        public_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        valid_from: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        valid_until: Option<String>,
+        #[serde(default)]
+        credential_types: Vec<String>
     }
 
-    enum WalletItem {
-        IdentityCard(IdentityCard)
+    let (trusted_issuers, set_trusted_issuers) = signal(Vec::<TrustedIssuer>::new());
+
+    let (issuer_id, set_issuer_id) = signal(String::new());
+    let (issuer_name, set_issuer_name) = signal(String::new());
+    let (issuer_public_key, set_issuer_public_key) = signal(String::new());
+    let (valid_from, set_valid_from) = signal(String::new());
+    let (valid_until, set_valid_until) = signal(String::new());
+    let (credential_types, set_credential_types) = signal(String::from("IdentityCard"));
+
+    let (registry_error, set_registry_error) = signal(String::new());
+
+    let fetch_trusted_issuers = move || {
+        spawn_local(async move {
+            let trusted_issuers: Vec<TrustedIssuer> = serde_wasm_bindgen::from_value(invoke("fetch_trusted_issuers", JsValue::NULL).await).unwrap_or_default();
+
+            set_trusted_issuers.set(trusted_issuers);
+        });
+    };
+
+    Effect::new(move |_| {
+        fetch_trusted_issuers();
+    });
+
+    let show_error = move |error: JsValue| {
+        set_registry_error.set(error.as_string().unwrap_or_default());
+
+        set_timeout(move || {
+            set_registry_error.set(String::new());
+        }, 5000);
+    };
+
+    let add_trusted_issuer = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                trustedIssuer: TrustedIssuer
+            }
+
+            let optional = |value: String| if value.trim().is_empty() { None } else { Some(value.trim().to_string()) };
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                trustedIssuer: TrustedIssuer {
+                    id: issuer_id.get_untracked(),
+                    name: issuer_name.get_untracked(),
+                    public_key: issuer_public_key.get_untracked().trim().to_string(),
+                    valid_from: optional(valid_from.get_untracked()),
+                    valid_until: optional(valid_until.get_untracked()),
+                    credential_types: credential_types.get_untracked().split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+                }
+            }).unwrap();
+
+            match invoke_result("add_trusted_issuer", args).await {
+                Ok(_) => {
+                    set_issuer_id.set(String::new());
+                    set_issuer_name.set(String::new());
+                    set_issuer_public_key.set(String::new());
+                    set_valid_from.set(String::new());
+                    set_valid_until.set(String::new());
+
+                    fetch_trusted_issuers();
+                },
+                Err(error) => show_error(error)
+            };
+        });
+    };
+
+    let remove_trusted_issuer = move |id: String| {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                id: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args { id }).unwrap();
+
+            match invoke_result("remove_trusted_issuer", args).await {
+                Ok(_) => fetch_trusted_issuers(),
+                Err(error) => show_error(error)
+            };
+        });
+    };
+
+    view! {
+        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
+            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+                <h1 class="text-center text-xl">Trusted issuers</h1>
+                <ul class="list bg-base-100 rounded-box mt-2 mb-2">
+                    <For
+                        each = move || trusted_issuers.get()
+                        key = |issuer| (issuer.id.clone(), issuer.public_key.clone())
+                        children = move |issuer| {
+                            let id = issuer.id.clone();
+
+                            view! {
+                                <li class="list-row">
+                                    <div>
+                                        <div>{issuer.name.clone()}</div>
+                                        <div class="text-xs opacity-50">{issuer.id.clone()}</div>
+                                        <div class="text-xs opacity-50">
+                                            {format!("{} to {}", issuer.valid_from.clone().unwrap_or(String::from("...")), issuer.valid_until.clone().unwrap_or(String::from("...")))}
+                                        </div>
+                                        <div class="text-xs opacity-50">
+                                            {if issuer.credential_types.is_empty() { String::from("Any credential type") } else { issuer.credential_types.join(", ") }}
+                                        </div>
+                                        <div class="text-xs opacity-25 break-all">{issuer.public_key.clone()}</div>
+                                    </div>
+                                    <button class="btn btn-error btn-sm"
+                                        on:click = move |_| {
+                                            remove_trusted_issuer(id.clone());
+                                        }
+                                    >Remove</button>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+                <Show when=move || trusted_issuers.get().is_empty()>
+                    <p class="text-center opacity-50">No trusted issuer, every signature is reported as coming from an unknown issuer.</p>
+                </Show>
+                <label class="label mt-2">Issuer ID</label>
+                <input type="text" class="input w-full" placeholder="https://issuer.example"
+                    prop:value = move || issuer_id.get()
+                    on:input = move |ev| {
+                        set_issuer_id.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label">Name</label>
+                <input type="text" class="input w-full" placeholder="Ministry of the Interior"
+                    prop:value = move || issuer_name.get()
+                    on:input = move |ev| {
+                        set_issuer_name.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label">Public key or DID URL</label>
+                <textarea class="textarea h-24 w-full" placeholder="did:web:issuer.example#key-1"
+                    prop:value = move || issuer_public_key.get()
+                    on:input = move |ev| {
+                        set_issuer_public_key.set(event_target_value(&ev));
+                    }
+                ></textarea>
+                <label class="label">Trusted from</label>
+                <input type="date" class="input w-full"
+                    prop:value = move || valid_from.get()
+                    on:input = move |ev| {
+                        set_valid_from.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label">Trusted until</label>
+                <input type="date" class="input w-full"
+                    prop:value = move || valid_until.get()
+                    on:input = move |ev| {
+                        set_valid_until.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label">Credential types</label>
+                <input type="text" class="input w-full" placeholder="IdentityCard"
+                    prop:value = move || credential_types.get()
+                    on:input = move |ev| {
+                        set_credential_types.set(event_target_value(&ev));
+                    }
+                />
+                <button class="btn btn-neutral mt-4"
+                    on:click = move |_| {
+                        add_trusted_issuer();
+                    }
+                >Add issuer</button>
+                <button class="btn mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("wallet"));
+                    }
+                >Back to wallet</button>
+            </fieldset>
+            <div class="toast toast-end">
+                <Show when=move || registry_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || registry_error.get()}</span>
+                    </div>
+                </Show>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+pub fn HistoryPage(account_surname: String, account_name: String) -> impl IntoView {
+    let ctx = use_context::<Ctx>().unwrap();
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum Predicate {
+        AgeOver { years: u32, date: String },
+        NotExpired { date: String },
+        CountryIn { countries: Vec<String> }
+    }
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct HistoryEntry {
+        timestamp: String,
+        verifier_id: Option<String>,
+        credential_id: String,
+        channel: String,
+        disclosed_attributes: Vec<String>,
+        #[serde(default)]
+        disclosed_values: Option<std::collections::BTreeMap<String, String>>,
+        #[serde(default)]
+        predicates: Vec<Predicate>
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct History {
+        entries: Vec<HistoryEntry>,
+        recordValues: bool
     }
 
+    let (entries, set_entries) = signal(Vec::<HistoryEntry>::new());
+    let (record_values, set_record_values) = signal(false);
+    let (export_path, set_export_path) = signal(String::new());
+
+    let (history_message, set_history_message) = signal(String::new());
+    let (history_error, set_history_error) = signal(String::new());
+
     let (surname, set_surname) = signal(String::new());
     let (name, set_name) = signal(String::new());
-    let (country, set_country) = signal(String::new());
-    let (sex, set_sex) = signal(String::new());
-    let (date_of_birth, set_date_of_birth) = signal(String::new());
-    let (place_of_birth, set_place_of_birth) = signal(String::new());
-    let (document_no, set_document_no) = signal(String::new());
-    let (expiry_date, set_expiry_date) = signal(String::new());
 
-    let (surname_checkbox, set_surname_checkbox) = signal(true);
-    let (name_checkbox, set_name_checkbox) = signal(true);
-    let (country_checkbox, set_country_checkbox) = signal(true);
-    let (sex_checkbox, set_sex_checkbox) = signal(true);
-    let (date_of_birth_checkbox, set_date_of_birth_checkbox) = signal(true);
-    let (place_of_birth_checkbox, set_place_of_birth_checkbox) = signal(true);
-    let (document_no_checkbox, set_document_no_checkbox) = signal(true);
-    let (expiry_date_checkbox, set_expiry_date_checkbox) = signal(true);
+    let show_error = move |error: JsValue| {
+        set_history_error.set(error.as_string().unwrap_or_default());
 
-    let (signature_generated, set_signature_generated) = signal(false);
+        set_timeout(move || {
+            set_history_error.set(String::new());
+        }, 5000);
+    };
 
-    let (signature, set_signature) = signal(String::new());
-    let (public_key, set_public_key) = signal(String::new());
+    let fetch_history = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked()
+            }).unwrap();
+
+            match invoke_result("fetch_history", args).await {
+                Ok(response) => {
+                    let history: History = serde_wasm_bindgen::from_value(response).unwrap();
+
+                    // Most recent first.
+                    set_entries.set(history.entries.into_iter().rev().collect());
+                    set_record_values.set(history.recordValues);
+                },
+                Err(error) => show_error(error)
+            };
+        });
+    };
+
+    Effect::new(move |_| {
+        set_surname.set(account_surname.clone());
+        set_name.set(account_name.clone());
+
+        fetch_history();
+    });
+
+    let set_history_values = move |enabled: bool| {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                recordValues: bool
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                recordValues: enabled
+            }).unwrap();
+
+            match invoke_result("set_history_values", args).await {
+                Ok(_) => set_record_values.set(enabled),
+                Err(error) => show_error(error)
+            };
+        });
+    };
+
+    let export_history = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                exportPath: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                exportPath: export_path.get_untracked()
+            }).unwrap();
+
+            match invoke_result("export_history", args).await {
+                Ok(count) => {
+                    set_history_message.set(format!("{} entries exported.", count.as_f64().unwrap_or_default()));
+
+                    set_timeout(move || {
+                        set_history_message.set(String::new());
+                    }, 5000);
+                },
+                Err(error) => show_error(error)
+            };
+        });
+    };
+
+    view! {
+        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
+            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
+                <h1 class="text-center text-xl">Presentation history</h1>
+                <ul class="list bg-base-100 rounded-box mt-2 mb-2">
+                    <For
+                        each = move || entries.get()
+                        key = |entry| (entry.timestamp.clone(), entry.credential_id.clone(), entry.channel.clone())
+                        children = move |entry| {
+                            let shared = entry.disclosed_attributes.iter().map(|attribute| match entry.disclosed_values.as_ref().and_then(|values| values.get(attribute)) {
+                                Some(value) => format!("{}: {}", attribute, value),
+                                None => attribute.clone()
+                            }).chain(entry.predicates.iter().map(|predicate| match predicate {
+                                Predicate::AgeOver { years, .. } => format!("age over {}", years),
+                                Predicate::NotExpired { .. } => String::from("not expired"),
+                                Predicate::CountryIn { .. } => String::from("country in set")
+                            })).collect::<Vec<String>>();
+
+                            view! {
+                                <li class="list-row">
+                                    <div>
+                                        <div>{entry.verifier_id.clone().unwrap_or(String::from("No verifier"))}</div>
+                                        <div class="text-xs opacity-50">{format!("{} ({})", entry.timestamp.replace('T', " ").chars().take(19).collect::<String>(), entry.channel.replace('_', " "))}</div>
+                                        <div class="text-xs opacity-50">{format!("Card {}", entry.credential_id)}</div>
+                                        <div class="text-xs">{if shared.is_empty() { String::from("Nothing disclosed") } else { shared.join(", ") }}</div>
+                                    </div>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+                <Show when=move || entries.get().is_empty()>
+                    <p class="text-center opacity-50">No presentation yet.</p>
+                </Show>
+                <label class="label mt-2">
+                    <input type="checkbox" class="toggle"
+                        prop:checked = move || record_values.get()
+                        on:click = move |_| {
+                            set_history_values(!record_values.get());
+                        }
+                    />
+                    Also record disclosed values
+                </label>
+                <label class="label mt-2">Export file</label>
+                <div class="join w-full">
+                    <input type="text" class="input join-item w-full" placeholder="history.json"
+                        on:input = move |ev| {
+                            set_export_path.set(event_target_value(&ev));
+                        }
+                    />
+                    <button class="btn join-item"
+                        on:click = move |_| {
+                            export_history();
+                        }
+                    >Export</button>
+                </div>
+                <button class="btn mt-4"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("wallet"));
+                    }
+                >Back to wallet</button>
+            </fieldset>
+            <div class="toast toast-end">
+                <Show when=move || history_message.get() != String::from("")>
+                    <div class="alert alert-success">
+                        <span>{move || history_message.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || history_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || history_error.get()}</span>
+                    </div>
+                </Show>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+pub fn SecurityPage(account_surname: String, account_name: String) -> impl IntoView {
+    let ctx = use_context::<Ctx>().unwrap();
+
+    #[derive(Serialize, Deserialize, Clone)]
+    #[serde(tag = "reason", rename_all = "snake_case")]
+    enum BrokenLink {
+        Malformed { line: usize, error: String },
+        SequenceMismatch { line: usize, expected: u64, found: u64 },
+        PreviousHashMismatch { line: usize, sequence: u64 },
+        HashMismatch { line: usize, sequence: u64 },
+        MissingRecords { expected: u64, found: u64 },
+        UnexpectedHead { sequence: u64 }
+    }
+
+    impl BrokenLink {
+        fn message(&self) -> String {
+            match self {
+                BrokenLink::Malformed { line, error } => format!("Record on line {} is unreadable: {}.", line, error),
+                BrokenLink::SequenceMismatch { line, expected, found } => format!("Line {} holds record {} instead of record {}, records were removed or reordered.", line, found, expected),
+                BrokenLink::PreviousHashMismatch { line, sequence } => format!("Record {} on line {} does not follow the previous record.", sequence, line),
+                BrokenLink::HashMismatch { line, sequence } => format!("Record {} on line {} was modified.", sequence, line),
+                BrokenLink::MissingRecords { expected, found } => format!("{} records expected, only {} found: the last records were removed.", expected, found),
+                BrokenLink::UnexpectedHead { sequence } => format!("The log does not end with record {} stored with the account.", sequence)
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct AuditVerification {
+        records: usize,
+        broken_link: Option<BrokenLink>
+    }
+
+    let (password, set_password) = signal(String::new());
+    let (new_password, set_new_password) = signal(String::new());
+    let (new_password_confirmation, set_new_password_confirmation) = signal(String::new());
+    let (audit_verification, set_audit_verification) = signal(None::<AuditVerification>);
+    let (recovery_phrase, set_recovery_phrase) = signal(String::new());
+
+    let (security_message, set_security_message) = signal(String::new());
+    let (security_error, set_security_error) = signal(String::new());
+
+    let (surname, set_surname) = signal(String::new());
+    let (name, set_name) = signal(String::new());
+
+    Effect::new(move |_| {
+        set_surname.set(account_surname.clone());
+        set_name.set(account_name.clone());
+    });
+
+    let show_error = move |error: String| {
+        set_security_error.set(error);
+
+        set_timeout(move || {
+            set_security_error.set(String::new());
+        }, 5000);
+    };
+
+    let change_password = move || {
+        if new_password.get_untracked() != new_password_confirmation.get_untracked() {
+            show_error(String::from("The two passwords must be identical."));
+            return;
+        }
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                password: String,
+                newPassword: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                password: password.get_untracked(),
+                newPassword: new_password.get_untracked()
+            }).unwrap();
+
+            match invoke_result("change_password", args).await {
+                Ok(_) => {
+                    set_security_message.set(String::from("Password changed."));
+
+                    set_timeout(move || {
+                        set_security_message.set(String::new());
+                    }, 5000);
+                },
+                Err(error) => show_error(error.as_string().unwrap_or_default())
+            };
+        });
+    };
+
+    // Uses the current password typed above, the previous phrase keeps working until the new one is confirmed.
+    let create_recovery_phrase = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                password: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                password: password.get_untracked()
+            }).unwrap();
+
+            match invoke_result("create_recovery_phrase", args).await {
+                Ok(phrase) => set_recovery_phrase.set(phrase.as_string().unwrap_or_default()),
+                Err(error) => show_error(error.as_string().unwrap_or_default())
+            };
+        });
+    };
+
+    // The new phrase replaces the previous one once its words are typed back.
+    let confirm_recovery_phrase = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked()
+            }).unwrap();
+
+            set_recovery_phrase.set(String::new());
+
+            match invoke_result("confirm_recovery_phrase", args).await {
+                Ok(_) => {
+                    set_security_message.set(String::from("New recovery phrase saved."));
+
+                    set_timeout(move || {
+                        set_security_message.set(String::new());
+                    }, 5000);
+                },
+                Err(error) => show_error(error.as_string().unwrap_or_default())
+            };
+        });
+    };
+
+    let verify_audit_log = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked()
+            }).unwrap();
+
+            match invoke_result("verify_audit_log", args).await {
+                Ok(response) => set_audit_verification.set(Some(serde_wasm_bindgen::from_value(response).unwrap())),
+                Err(error) => show_error(error.as_string().unwrap_or_default())
+            };
+        });
+    };
+
+    view! {
+        <main class="flex items-center justify-center min-h-screen py-4 flex-col">
+            <Show when=move || recovery_phrase.get() != String::from("")>
+                <RecoveryPhraseConfirmation
+                    phrase = recovery_phrase.get()
+                    on_confirmed = Callback::new(move |_| confirm_recovery_phrase())
+                />
+            </Show>
+            <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4" class:hidden=move || recovery_phrase.get() != String::from("")>
+                <h1 class="text-center text-xl">Security</h1>
+                <label class="label mt-2">Current password</label>
+                <input type="password" class="input w-full" placeholder="Current password"
+                    on:input = move |ev| {
+                        set_password.set(event_target_value(&ev));
+                    }
+                />
+                <label class="label mt-2">New password</label>
+                <input type="password" class="input w-full" placeholder="New password"
+                    on:input = move |ev| {
+                        set_new_password.set(event_target_value(&ev));
+                    }
+                />
+                <PasswordStrengthMeter surname = surname name = name password = new_password/>
+                <input type="password" class="input w-full mt-2" placeholder="Confirm the new password"
+                    on:input = move |ev| {
+                        set_new_password_confirmation.set(event_target_value(&ev));
+                    }
+                />
+                <button class="btn btn-neutral mt-2"
+                    on:click = move |_| {
+                        change_password();
+                    }
+                >Change password</button>
+                <button class="btn mt-2"
+                    on:click = move |_| {
+                        create_recovery_phrase();
+                    }
+                >New recovery phrase</button>
+                <label class="label mt-4">Audit log</label>
+                <button class="btn"
+                    on:click = move |_| {
+                        verify_audit_log();
+                    }
+                >Verify audit log</button>
+                <Show when=move || audit_verification.get().is_some()>
+                    {
+                        move || {
+                            let verification = audit_verification.get().unwrap();
+
+                            match verification.broken_link {
+                                None => view! {
+                                    <div class="alert alert-success mt-2">
+                                        <span>{format!("{} records, chain intact.", verification.records)}</span>
+                                    </div>
+                                }.into_any(),
+                                Some(broken_link) => view! {
+                                    <div class="alert alert-error mt-2">
+                                        <span>{format!("Chain broken after {} valid records. {}", verification.records, broken_link.message())}</span>
+                                    </div>
+                                }.into_any()
+                            }
+                        }
+                    }
+                </Show>
+                <button class="btn mt-4"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("wallet"));
+                    }
+                >Back to wallet</button>
+            </fieldset>
+            <div class="toast toast-end">
+                <Show when=move || security_message.get() != String::from("")>
+                    <div class="alert alert-success">
+                        <span>{move || security_message.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || security_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || security_error.get()}</span>
+                    </div>
+                </Show>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
+    let ctx = use_context::<Ctx>().unwrap();
+
+    #[derive(Serialize, Deserialize)]
+    struct IdentityCard {
+        surname: String,
+        name: String,
+        country: String,
+        sex: String,
+        dateOfBirth: String,
+        placeOfBirth: String,
+        documentNo: String,
+        expiryDate: String,
+        ciphersuite: String,
+        statusListIndex: Option<usize>,
+        expired: bool,
+        issuedCredential: Option<IssuedCredential>
+    }
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct IssuedCredential {
+        issuer: String,
+        signature: String,
+        publicKey: String,
+        proverBlind: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        verificationMethod: Option<String>
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    enum ValidityError {
+        Expired { expiry_date: String },
+        InvalidDate { value: String },
+        ExpiryNotProven
+    }
+
+    impl ValidityError {
+        fn message(&self) -> String {
+            match self {
+                ValidityError::Expired { expiry_date } => format!("Credential expired on {}.", expiry_date),
+                ValidityError::InvalidDate { value } => format!("Invalid validity date: {}.", value),
+                ValidityError::ExpiryNotProven => String::from("Credential neither discloses its expiry date nor proves it is not expired.")
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    #[serde(tag = "status", rename_all = "snake_case")]
+    enum IssuerTrust {
+        Trusted { id: String, name: String },
+        Unknown,
+        NotYetValid { id: String, name: String, valid_from: String },
+        Expired { id: String, name: String, valid_until: String },
+        TypeNotAllowed { id: String, name: String, credential_type: String }
+    }
+
+    impl IssuerTrust {
+        fn message(&self) -> String {
+            match self {
+                IssuerTrust::Trusted { name, .. } => format!("Signed by trusted issuer {}.", name),
+                IssuerTrust::Unknown => String::from("Unknown issuer."),
+                IssuerTrust::NotYetValid { name, valid_from, .. } => format!("Issuer {} is not trusted before {}.", name, valid_from),
+                IssuerTrust::Expired { name, valid_until, .. } => format!("Issuer {} is no longer trusted since {}.", name, valid_until),
+                IssuerTrust::TypeNotAllowed { name, credential_type, .. } => format!("Issuer {} is not trusted for {}.", name, credential_type)
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct PendingAuthorization {
+        authorizationRequest: String,
+        verifierId: String,
+        matchingCards: Vec<usize>,
+        requiredIndices: Vec<usize>,
+        optionalIndices: Vec<usize>,
+        requestedFields: Vec<String>
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct SignatureResult {
+        verified: bool,
+        error: Option<ValidityError>,
+        issuer: IssuerTrust
+    }
+
+    enum WalletItem {
+        IdentityCard(IdentityCard)
+    }
+
+    let (surname, set_surname) = signal(String::new());
+    let (name, set_name) = signal(String::new());
+    let (country, set_country) = signal(String::new());
+    let (sex, set_sex) = signal(String::new());
+    let (date_of_birth, set_date_of_birth) = signal(String::new());
+    let (place_of_birth, set_place_of_birth) = signal(String::new());
+    let (document_no, set_document_no) = signal(String::new());
+    let (expiry_date, set_expiry_date) = signal(String::new());
+    let (ciphersuite, set_ciphersuite) = signal(String::new());
+    let (status_list_index, set_status_list_index) = signal(None::<usize>);
+    let (expired, set_expired) = signal(false);
+    let (issued_credential, set_issued_credential) = signal(None::<IssuedCredential>);
+
+    let (surname_checkbox, set_surname_checkbox) = signal(true);
+    let (name_checkbox, set_name_checkbox) = signal(true);
+    let (country_checkbox, set_country_checkbox) = signal(true);
+    let (sex_checkbox, set_sex_checkbox) = signal(true);
+    let (date_of_birth_checkbox, set_date_of_birth_checkbox) = signal(true);
+    let (place_of_birth_checkbox, set_place_of_birth_checkbox) = signal(true);
+    let (document_no_checkbox, set_document_no_checkbox) = signal(true);
+    let (expiry_date_checkbox, set_expiry_date_checkbox) = signal(true);
+
+    let (signature_generated, set_signature_generated) = signal(false);
+
+    let (signature, set_signature) = signal(String::new());
+    let (public_key, set_public_key) = signal(String::new());
+    let (prover_blind, set_prover_blind) = signal(String::new());
+    let (holder_binding, set_holder_binding) = signal(false);
+    let (verifier_id, set_verifier_id) = signal(String::new());
+    let (pseudonym, set_pseudonym) = signal(String::new());
+    let (age_over_checkbox, set_age_over_checkbox) = signal(false);
+    let (not_expired_checkbox, set_not_expired_checkbox) = signal(false);
+    let (country_in_checkbox, set_country_in_checkbox) = signal(false);
+    let (proven_predicates, set_proven_predicates) = signal(Vec::<String>::new());
+    let (status_list_file, set_status_list_file) = signal(String::new());
+    let (card_status, set_card_status) = signal(String::new());
+    let (non_revocation_checkbox, set_non_revocation_checkbox) = signal(false);
+    let (authorization_request, set_authorization_request) = signal(String::new());
+    let (pending_authorization, set_pending_authorization) = signal(None::<PendingAuthorization>);
+    let (authorization_sent, set_authorization_sent) = signal(None::<bool>);
+    let (authorization_message, set_authorization_message) = signal(String::new());
+
+    let (disclosed_messages, set_disclosed_messages) = signal(Vec::<(usize, String)>::new());
+    let (signature_successfully_verified, set_signature_successfully_verified) = signal(None);
+    let (partial_signature_successfully_verified, set_partial_signature_successfully_verified) = signal(None);
+    let (validity_error, set_validity_error) = signal(String::new());
+    let (issuer_trust, set_issuer_trust) = signal(String::new());
+    let (key_format, set_key_format) = signal(String::from("jwk"));
+    let (exported_public_key, set_exported_public_key) = signal(String::new());
+    let (key_error, set_key_error) = signal(String::new());
+    let (qr_code, set_qr_code) = signal(String::new());
+    let (qr_code_error, set_qr_code_error) = signal(String::new());
+    let (qr_code_image, set_qr_code_image) = signal(String::new());
+    let (delete_confirmation, set_delete_confirmation) = signal(false);
+    let (delete_card_error, set_delete_card_error) = signal(String::new());
+
+    let fetch_wallet_data = move |surname: String, name: String| {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String
+            }
+            
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.clone(),
+                name: name.clone()
+            }).unwrap();
+            
+            let response: Vec<IdentityCard> = serde_wasm_bindgen::from_value(invoke("fetch_wallet_data", args).await).unwrap();
+
+            if response.len() > 0 {
+                set_surname.set(response[0].surname.clone());
+                set_name.set(response[0].name.clone());
+                set_country.set(response[0].country.clone());
+                set_sex.set(response[0].sex.clone());
+                set_date_of_birth.set(response[0].dateOfBirth.clone());
+                set_place_of_birth.set(response[0].placeOfBirth.clone());
+                set_document_no.set(response[0].documentNo.clone());
+                set_expiry_date.set(response[0].expiryDate.clone());
+                set_ciphersuite.set(response[0].ciphersuite.clone());
+                set_status_list_index.set(response[0].statusListIndex);
+                set_expired.set(response[0].expired);
+                set_issued_credential.set(response[0].issuedCredential.clone());
+            };
+        });
+    };
+    
+    Effect::new(move |_| {
+        fetch_wallet_data(account_surname.clone(), account_name.clone());
+    });
+
+    // The wallet shows the first card of the account.
+    let delete_card = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String,
+                cardIndex: usize
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                cardIndex: 0
+            }).unwrap();
+
+            match invoke_result("delete_card", args).await {
+                Ok(_) => ctx.page_name.set(String::from("create_identity_card")),
+                Err(error) => {
+                    set_delete_confirmation.set(false);
+                    set_delete_card_error.set(error.as_string().unwrap_or_default());
+
+                    set_timeout(move || {
+                        set_delete_card_error.set(String::new());
+                    }, 5000);
+                }
+            };
+        });
+    };
+
+    let logout = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                surname: String,
+                name: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked()
+            }).unwrap();
+
+            invoke("logout_account", args).await;
+
+            ctx.page_name.set(String::from("login_account"));
+        });
+    };
+
+    #[derive(Serialize, Deserialize)]
+    struct Signature {
+        signature: String,
+        public_key: String,
+        ciphersuite: String
+    }
+
+    let create_signature = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                messagesArray: Vec<String>,
+                ciphersuite: String
+            }
+            
+            let args = serde_wasm_bindgen::to_value(&Args {
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ],
+                ciphersuite: ciphersuite.get_untracked()
+            }).unwrap();
+
+            let response: Signature = serde_wasm_bindgen::from_value(invoke("create_signature", args).await).unwrap();
+            
+            set_signature.set(response.signature);
+            set_public_key.set(response.public_key);
+            set_ciphersuite.set(response.ciphersuite);
+            set_signature_generated.set(true);
+        });
+    };
+
+    #[derive(Serialize, Deserialize)]
+    struct BoundSignature {
+        signature: String,
+        public_key: String,
+        prover_blind: String
+    }
+
+    let create_bound_signature = move || {
+        // Cards received from an issuer already carry a signature bound to this wallet.
+        if let Some(credential) = issued_credential.get_untracked() {
+            set_signature.set(credential.signature);
+            set_public_key.set(credential.publicKey);
+            set_prover_blind.set(credential.proverBlind);
+            set_signature_generated.set(true);
+
+            return;
+        }
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                messagesArray: Vec<String>
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ].into_iter().chain(status_list_index.get_untracked().map(|index| index.to_string())).collect()
+            }).unwrap();
+
+            let response: BoundSignature = serde_wasm_bindgen::from_value(invoke("create_bound_signature", args).await).unwrap();
+
+            set_signature.set(response.signature);
+            set_public_key.set(response.public_key);
+            set_prover_blind.set(response.prover_blind);
+            set_signature_generated.set(true);
+        });
+    };
+
+    let verify_bound_signature = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                signatureHex: String,
+                publicKeyHex: String,
+                proverBlindHex: String,
+                messagesArray: Vec<String>
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                signatureHex: signature.get_untracked(),
+                publicKeyHex: public_key.get_untracked(),
+                proverBlindHex: prover_blind.get_untracked(),
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ].into_iter().chain(status_list_index.get_untracked().map(|index| index.to_string())).collect()
+            }).unwrap();
+
+            let response: SignatureResult = serde_wasm_bindgen::from_value(invoke("verify_bound_signature", args).await).unwrap();
+
+            set_validity_error.set(response.error.map(|error| error.message()).unwrap_or_default());
+            set_issuer_trust.set(response.issuer.message());
+            set_signature_successfully_verified.set(Some(response.verified));
+
+            set_timeout(move || {
+                set_signature_successfully_verified.set(None);
+            }, 5000);
+        });
+
+        let indices_array = [surname_checkbox.get(), name_checkbox.get(), country_checkbox.get(), sex_checkbox.get(), date_of_birth_checkbox.get(), place_of_birth_checkbox.get(), document_no_checkbox.get(), expiry_date_checkbox.get()];
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                signatureHex: String,
+                publicKeyHex: String,
+                proverBlindHex: String,
+                messagesArray: Vec<String>,
+                indicesArray: Vec<usize>,
+                verifierId: Option<String>,
+                predicates: Vec<Predicate>,
+                nonRevocation: bool
+            }
+
+            #[derive(Serialize, Deserialize, Debug, Clone)]
+            #[serde(tag = "type", rename_all = "snake_case")]
+            pub enum Predicate {
+                AgeOver { years: u32, date: String },
+                NotExpired { date: String },
+                CountryIn { countries: Vec<String> }
+            }
+
+            let verifier_id = if verifier_id.get_untracked().is_empty() { None } else { Some(verifier_id.get_untracked()) };
+
+            let today: String = String::from(js_sys::Date::new_0().to_iso_string()).chars().take(10).collect();
+
+            let mut predicates = vec![];
+
+            if age_over_checkbox.get_untracked() {
+                predicates.push(Predicate::AgeOver { years: 18, date: today.clone() });
+            }
+
+            if not_expired_checkbox.get_untracked() {
+                predicates.push(Predicate::NotExpired { date: today.clone() });
+            }
+
+            if country_in_checkbox.get_untracked() {
+                predicates.push(Predicate::CountryIn { countries: EU_COUNTRIES.iter().map(|c| c.to_string()).collect() });
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                signatureHex: signature.get_untracked(),
+                publicKeyHex: public_key.get_untracked(),
+                proverBlindHex: prover_blind.get_untracked(),
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ].into_iter().chain(status_list_index.get_untracked().map(|index| index.to_string())).collect(),
+                // The expiry date is disclosed unless the card is proven not expired, and the status list index unless non-revocation is proven against the accumulator.
+                indicesArray: indices_array.iter().enumerate().filter_map(|(i, &value)| if value { Some(i) } else { None })
+                    .chain((!indices_array[7] && !not_expired_checkbox.get_untracked()).then_some(7))
+                    .chain(status_list_index.get_untracked().filter(|_| !non_revocation_checkbox.get_untracked()).map(|_| 8))
+                    .collect(),
+                verifierId: verifier_id.clone(),
+                predicates: predicates.clone(),
+                nonRevocation: non_revocation_checkbox.get_untracked()
+            }).unwrap();
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct DisclosedMessage {
+                pub index: usize,
+                pub value: String,
+            }
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct NonRevocation {
+                pub epoch: u64,
+                pub proof: String
+            }
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct PredicateProof {
+                pub predicate: Predicate,
+                pub proof: String
+            }
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct Presentation {
+                pub proof: String,
+                pub public_key: String,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub verification_method: Option<String>,
+                pub message_count: usize,
+                pub disclosed_messages: Vec<DisclosedMessage>,
+                pub pseudonym: Option<String>,
+                #[serde(default)]
+                pub predicates: Vec<PredicateProof>,
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub non_revocation: Option<NonRevocation>
+            }
+
+            let presentation: Presentation = match serde_wasm_bindgen::from_value(invoke("create_presentation", args).await) {
+                Ok(p) => p,
+                Err(_err) => {
+                    set_pseudonym.set(String::new());
+                    set_proven_predicates.set(vec![]);
+                    set_partial_signature_successfully_verified.set(Some(false));
+
+                    set_timeout(move || {
+                        set_partial_signature_successfully_verified.set(None);
+                    }, 5000);
+
+                    return;
+                }
+            };
+
+            #[derive(Serialize)]
+            struct QrCodeArgs<'a> {
+                presentation: &'a Presentation
+            }
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct QrCodeImage {
+                pub payload: String,
+                pub svg: String
+            }
+
+            // Predicate proofs do not fit in a QR code, the presentation is then only verified here.
+            match invoke_result("presentation_qr_code", serde_wasm_bindgen::to_value(&QrCodeArgs { presentation: &presentation }).unwrap()).await {
+                Ok(image) => set_qr_code.set(serde_wasm_bindgen::from_value::<QrCodeImage>(image).map(|image| image.svg).unwrap_or_default()),
+                Err(error) => {
+                    set_qr_code.set(String::new());
+                    set_qr_code_error.set(error.as_string().unwrap_or_default());
+
+                    set_timeout(move || {
+                        set_qr_code_error.set(String::new());
+                    }, 5000);
+                }
+            };
+
+            #[derive(Serialize)]
+            struct VerifyArgs {
+                presentation: Presentation,
+                verifierId: Option<String>,
+                predicates: Vec<Predicate>,
+                statusListFile: Option<String>
+            }
+
+            let verify_args = serde_wasm_bindgen::to_value(&VerifyArgs {
+                presentation,
+                verifierId: verifier_id,
+                predicates,
+                statusListFile: if status_list_file.get_untracked().is_empty() { None } else { Some(status_list_file.get_untracked()) }
+            }).unwrap();
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct CredentialStatus {
+                pub revoked: bool,
+                pub suspended: bool
+            }
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct PresentationResult {
+                pub verified: bool,
+                pub disclosed_messages: Vec<DisclosedMessage>,
+                pub pseudonym: Option<String>,
+                pub predicates: Vec<Predicate>,
+                pub status: Option<CredentialStatus>,
+                pub non_revocation_epoch: Option<u64>,
+                pub error: Option<ValidityError>,
+                pub issuer: Option<IssuerTrust>
+            }
+
+            let response: PresentationResult = match serde_wasm_bindgen::from_value(invoke("verify_presentation", verify_args).await) {
+                Ok(r) => r,
+                Err(_err) => {
+                    PresentationResult {
+                        verified: false,
+                        disclosed_messages: vec![],
+                        pseudonym: None,
+                        predicates: vec![],
+                        status: None,
+                        non_revocation_epoch: None,
+                        error: None,
+                        issuer: None
+                    }
+                }
+            };
+
+            set_disclosed_messages.set(response.disclosed_messages.iter().map(|dm| (dm.index, dm.value.clone())).collect());
+            set_pseudonym.set(response.pseudonym.unwrap_or_default());
+            set_validity_error.set(response.error.map(|error| error.message()).unwrap_or_default());
+            set_issuer_trust.set(response.issuer.map(|issuer| issuer.message()).unwrap_or_default());
+            set_card_status.set(match (response.status, response.non_revocation_epoch) {
+                (Some(CredentialStatus { revoked: true, .. }), _) => String::from("Revoked"),
+                (Some(CredentialStatus { suspended: true, .. }), _) => String::from("Suspended"),
+                (Some(_), _) => String::from("Valid"),
+                (None, Some(epoch)) => format!("Not revoked (accumulator epoch {})", epoch),
+                (None, None) => String::new()
+            });
+            set_proven_predicates.set(response.predicates.iter().map(|predicate| match predicate {
+                Predicate::AgeOver { years, date } => format!("Age over {} on {}", years, date),
+                Predicate::NotExpired { date } => format!("Not expired on {}", date),
+                Predicate::CountryIn { countries } => format!("Country in {}", countries.join(", "))
+            }).collect());
+
+            set_partial_signature_successfully_verified.set(Some(response.verified));
+
+            set_timeout(move || {
+                set_partial_signature_successfully_verified.set(None);
+            }, 5000);
+        });
+    };
+
+    let show_key_error = move |error: JsValue| {
+        set_key_error.set(error.as_string().unwrap_or_default());
+
+        set_timeout(move || {
+            set_key_error.set(String::new());
+        }, 5000);
+    };
+
+    let export_public_key = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                publicKeyHex: String,
+                format: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                publicKeyHex: public_key.get_untracked(),
+                format: key_format.get_untracked()
+            }).unwrap();
+
+            match invoke_result("export_public_key", args).await {
+                Ok(exported) => set_exported_public_key.set(exported.as_string().unwrap_or_default()),
+                Err(error) => show_key_error(error)
+            };
+        });
+    };
+
+    // Replaces a pasted JWK, Multikey or PEM key by its hex encoding before verifying with it.
+    let import_public_key = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize)]
+            struct Args {
+                publicKey: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                publicKey: public_key.get_untracked()
+            }).unwrap();
 
-    let (disclosed_messages, set_disclosed_messages) = signal(Vec::<(usize, String)>::new());
-    let (signature_successfully_verified, set_signature_successfully_verified) = signal(None);
-    let (partial_signature_successfully_verified, set_partial_signature_successfully_verified) = signal(None);
+            match invoke_result("import_public_key", args).await {
+                Ok(imported) => set_public_key.set(imported.as_string().unwrap_or_default()),
+                Err(error) => show_key_error(error)
+            };
+        });
+    };
 
-    let fetch_wallet_data = move |surname: String, name: String| {
+    let revoke_card = move || {
         spawn_local(async move {
             #[derive(Serialize, Deserialize, Debug)]
             struct Args {
-                surname: String,
-                name: String
+                statusListIndex: usize
             }
-            
+
+            let Some(index) = status_list_index.get_untracked() else {
+                return;
+            };
+
             let args = serde_wasm_bindgen::to_value(&Args {
-                surname: surname.clone(),
-                name: name.clone()
+                statusListIndex: index
             }).unwrap();
-            
-            let response: Vec<IdentityCard> = serde_wasm_bindgen::from_value(invoke("fetch_wallet_data", args).await).unwrap();
 
-            if response.len() > 0 {
-                set_surname.set(response[0].surname.clone());
-                set_name.set(response[0].name.clone());
-                set_country.set(response[0].country.clone());
-                set_sex.set(response[0].sex.clone());
-                set_date_of_birth.set(response[0].dateOfBirth.clone());
-                set_place_of_birth.set(response[0].placeOfBirth.clone());
-                set_document_no.set(response[0].documentNo.clone());
-                set_expiry_date.set(response[0].expiryDate.clone());
-            };
+            invoke("revoke_card", args).await;
         });
     };
-    
-    Effect::new(move |_| {
-        fetch_wallet_data(account_surname.clone(), account_name.clone());
-    });
-
-    #[derive(Serialize, Deserialize)]
-    struct Signature {
-        signature: String,
-        public_key: String
-    }
 
-    let create_signature = move || {
+    let suspend_card = move |suspended: bool| {
         spawn_local(async move {
             #[derive(Serialize, Deserialize, Debug)]
             struct Args {
-                messagesArray: Vec<String>
+                statusListIndex: usize,
+                suspended: bool
             }
-            
+
+            let Some(index) = status_list_index.get_untracked() else {
+                return;
+            };
+
             let args = serde_wasm_bindgen::to_value(&Args {
-                messagesArray: vec![
-                    surname.get_untracked(),
-                    name.get_untracked(),
-                    country.get_untracked(),
-                    sex.get_untracked(),
-                    date_of_birth.get_untracked(),
-                    place_of_birth.get_untracked(),
-                    document_no.get_untracked(),
-                    expiry_date.get_untracked()
-                ]
+                statusListIndex: index,
+                suspended
             }).unwrap();
 
-            let response: Signature = serde_wasm_bindgen::from_value(invoke("create_signature", args).await).unwrap();
-            
-            set_signature.set(response.signature);
-            set_public_key.set(response.public_key);
-            set_signature_generated.set(true);
+            invoke("suspend_card", args).await;
         });
     };
 
@@ -700,7 +2397,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
             struct Args {
                 signatureHex: String,
                 publicKeyHex: String,
-                messagesArray: Vec<String>
+                messagesArray: Vec<String>,
+                ciphersuite: String
             }
 
             let args = serde_wasm_bindgen::to_value(&Args {
@@ -715,12 +2413,15 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     place_of_birth.get_untracked(),
                     document_no.get_untracked(),
                     expiry_date.get_untracked()
-                ]
+                ],
+                ciphersuite: ciphersuite.get_untracked()
             }).unwrap();
 
-            let response = invoke("verify_signature", args).await.as_bool().unwrap();
+            let response: SignatureResult = serde_wasm_bindgen::from_value(invoke("verify_signature", args).await).unwrap();
 
-            set_signature_successfully_verified.set(Some(response));
+            set_validity_error.set(response.error.map(|error| error.message()).unwrap_or_default());
+            set_issuer_trust.set(response.issuer.message());
+            set_signature_successfully_verified.set(Some(response.verified));
 
             set_timeout(move || {
                 set_signature_successfully_verified.set(None);
@@ -735,7 +2436,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                 signatureHex: String,
                 publicKeyHex: String,
                 messagesArray: Vec<String>,
-                indicesArray: Vec<usize>
+                indicesArray: Vec<usize>,
+                ciphersuite: String
             }
         
             let args = serde_wasm_bindgen::to_value(&Args {
@@ -751,7 +2453,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     document_no.get_untracked(),
                     expiry_date.get_untracked()
                 ],
-                indicesArray: indices_array.iter().enumerate().filter_map(|(i, &value)| if value { Some(i) } else { None }).collect()
+                indicesArray: indices_array.iter().enumerate().filter_map(|(i, &value)| if value { Some(i) } else { None }).collect(),
+                ciphersuite: ciphersuite.get_untracked()
             }).unwrap();
 
             #[derive(Serialize, Deserialize, Debug)]
@@ -764,6 +2467,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
             pub struct PartialSignatureResult {
                 pub verified: bool,
                 pub disclosed_messages: Vec<DisclosedMessage>,
+                pub error: Option<ValidityError>,
+                pub issuer: Option<IssuerTrust>
             }
 
             let response: PartialSignatureResult = match serde_wasm_bindgen::from_value(invoke("verify_signature_indices", args).await) {
@@ -771,12 +2476,16 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                 Err(_err) => {
                     PartialSignatureResult {
                         verified: false,
-                        disclosed_messages: vec![]
+                        disclosed_messages: vec![],
+                        error: None,
+                        issuer: None
                     }
                 }
             };
 
             set_disclosed_messages.set(response.disclosed_messages.iter().map(|dm| (dm.index, dm.value.clone())).collect());
+            set_validity_error.set(response.error.map(|error| error.message()).unwrap_or_default());
+            set_issuer_trust.set(response.issuer.map(|issuer| issuer.message()).unwrap_or_default());
 
             set_partial_signature_successfully_verified.set(Some(response.verified));
 
@@ -786,6 +2495,213 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
         });
     };
 
+    let load_authorization_request = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                authorizationRequest: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                authorizationRequest: authorization_request.get_untracked()
+            }).unwrap();
+
+            match invoke_result("load_authorization_request", args).await {
+                Ok(response) => {
+                    let pending: PendingAuthorization = serde_wasm_bindgen::from_value(response).unwrap();
+
+                    set_verifier_id.set(pending.verifierId.clone());
+
+                    // Only the fields the verifier requires are pre-selected, optional ones are left to the holder.
+                    set_surname_checkbox.set(pending.requiredIndices.contains(&0));
+                    set_name_checkbox.set(pending.requiredIndices.contains(&1));
+                    set_country_checkbox.set(pending.requiredIndices.contains(&2));
+                    set_sex_checkbox.set(pending.requiredIndices.contains(&3));
+                    set_date_of_birth_checkbox.set(pending.requiredIndices.contains(&4));
+                    set_place_of_birth_checkbox.set(pending.requiredIndices.contains(&5));
+                    set_document_no_checkbox.set(pending.requiredIndices.contains(&6));
+                    set_expiry_date_checkbox.set(pending.requiredIndices.contains(&7));
+
+                    set_pending_authorization.set(Some(pending));
+                },
+                Err(error) => {
+                    set_pending_authorization.set(None);
+                    set_authorization_message.set(error.as_string().unwrap_or_default());
+                    set_authorization_sent.set(Some(false));
+
+                    set_timeout(move || {
+                        set_authorization_sent.set(None);
+                    }, 5000);
+                }
+            };
+        });
+    };
+
+    // A verifier request opened from a link is answered with a bound signature, whose form then shows the consent screen.
+    Effect::new(move |_| {
+        if let Some(DeepLink::PresentationRequest { uri }) = ctx.deep_link.get() {
+            ctx.deep_link.set(None);
+
+            set_holder_binding.set(true);
+            set_authorization_request.set(uri);
+            load_authorization_request();
+        }
+    });
+
+    // The decoded link fills the request field and is loaded, which opens the consent screen.
+    let scan_qr_code_image = move || {
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                imagePath: String
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                imagePath: qr_code_image.get_untracked()
+            }).unwrap();
+
+            match invoke_result("scan_qr_code_image", args).await {
+                Ok(request) => {
+                    set_authorization_request.set(request.as_string().unwrap_or_default());
+                    load_authorization_request();
+                },
+                Err(error) => {
+                    set_pending_authorization.set(None);
+                    set_authorization_message.set(error.as_string().unwrap_or_default());
+                    set_authorization_sent.set(Some(false));
+
+                    set_timeout(move || {
+                        set_authorization_sent.set(None);
+                    }, 5000);
+                }
+            };
+        });
+    };
+
+    let respond_authorization_request = move || {
+        let Some(pending) = pending_authorization.get_untracked() else {
+            return;
+        };
+
+        let indices_array = [surname_checkbox.get_untracked(), name_checkbox.get_untracked(), country_checkbox.get_untracked(), sex_checkbox.get_untracked(), date_of_birth_checkbox.get_untracked(), place_of_birth_checkbox.get_untracked(), document_no_checkbox.get_untracked(), expiry_date_checkbox.get_untracked()];
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                authorizationRequest: String,
+                signatureHex: String,
+                publicKeyHex: String,
+                proverBlindHex: String,
+                messagesArray: Vec<String>,
+                indicesArray: Vec<usize>
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                authorizationRequest: pending.authorizationRequest,
+                signatureHex: signature.get_untracked(),
+                publicKeyHex: public_key.get_untracked(),
+                proverBlindHex: prover_blind.get_untracked(),
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ].into_iter().chain(status_list_index.get_untracked().map(|index| index.to_string())).collect(),
+                indicesArray: indices_array.iter().enumerate().filter_map(|(i, &value)| if value { Some(i) } else { None }).collect()
+            }).unwrap();
+
+            match invoke_result("respond_authorization_request", args).await {
+                Ok(response) => {
+                    let redirect_uri: Option<String> = serde_wasm_bindgen::from_value(response).unwrap_or(None);
+
+                    set_pending_authorization.set(None);
+                    set_authorization_message.set(redirect_uri.map(|uri| format!("Continue on {}", uri)).unwrap_or_default());
+                    set_authorization_sent.set(Some(true));
+                },
+                Err(error) => {
+                    set_authorization_message.set(error.as_string().unwrap_or_default());
+                    set_authorization_sent.set(Some(false));
+                }
+            };
+
+            set_timeout(move || {
+                set_authorization_sent.set(None);
+            }, 5000);
+        });
+    };
+
+    // Answers the loaded request with a QR code the verifier scans, for when it cannot be reached from this device.
+    let show_authorization_qr_code = move || {
+        let Some(pending) = pending_authorization.get_untracked() else {
+            return;
+        };
+
+        let indices_array = [surname_checkbox.get_untracked(), name_checkbox.get_untracked(), country_checkbox.get_untracked(), sex_checkbox.get_untracked(), date_of_birth_checkbox.get_untracked(), place_of_birth_checkbox.get_untracked(), document_no_checkbox.get_untracked(), expiry_date_checkbox.get_untracked()];
+
+        spawn_local(async move {
+            #[derive(Serialize, Deserialize, Debug)]
+            struct Args {
+                surname: String,
+                name: String,
+                authorizationRequest: String,
+                signatureHex: String,
+                publicKeyHex: String,
+                proverBlindHex: String,
+                messagesArray: Vec<String>,
+                indicesArray: Vec<usize>
+            }
+
+            let args = serde_wasm_bindgen::to_value(&Args {
+                surname: surname.get_untracked(),
+                name: name.get_untracked(),
+                authorizationRequest: pending.authorizationRequest,
+                signatureHex: signature.get_untracked(),
+                publicKeyHex: public_key.get_untracked(),
+                proverBlindHex: prover_blind.get_untracked(),
+                messagesArray: vec![
+                    surname.get_untracked(),
+                    name.get_untracked(),
+                    country.get_untracked(),
+                    sex.get_untracked(),
+                    date_of_birth.get_untracked(),
+                    place_of_birth.get_untracked(),
+                    document_no.get_untracked(),
+                    expiry_date.get_untracked()
+                ].into_iter().chain(status_list_index.get_untracked().map(|index| index.to_string())).collect(),
+                indicesArray: indices_array.iter().enumerate().filter_map(|(i, &value)| if value { Some(i) } else { None }).collect()
+            }).unwrap();
+
+            #[derive(Serialize, Deserialize, Debug)]
+            pub struct QrCodeImage {
+                pub payload: String,
+                pub svg: String
+            }
+
+            match invoke_result("authorization_qr_code", args).await {
+                Ok(image) => set_qr_code.set(serde_wasm_bindgen::from_value::<QrCodeImage>(image).map(|image| image.svg).unwrap_or_default()),
+                Err(error) => {
+                    set_qr_code_error.set(error.as_string().unwrap_or_default());
+
+                    set_timeout(move || {
+                        set_qr_code_error.set(String::new());
+                    }, 5000);
+                }
+            };
+        });
+    };
+
     view! {
         <main class="flex items-center justify-center min-h-screen py-4 flex-col">
             <fieldset class="fieldset bg-base-200 border-base-300 rounded-box w-xs border p-4">
@@ -794,7 +2710,12 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     <div class="card w-128 bg-black text-white bg-[radial-gradient(circle_at_bottom_left,#ffffff04_35%,transparent_36%),radial-gradient(circle_at_top_right,#ffffff04_35%,transparent_36%)] bg-size-[4.95em_4.95em]">
                         <div class="card-body">
                             <div class="flex justify-between">
-                                <div class="font-bold">IDENTITY CARD</div>
+                                <div class="flex items-center gap-2">
+                                    <div class="font-bold">IDENTITY CARD</div>
+                                    <Show when=move || expired.get()>
+                                        <div class="badge badge-error">Expired</div>
+                                    </Show>
+                                </div>
                                 <svg class="translate-y-[-16px] translate-x-[16px]" fill="#e8eaed" xmlns="http://www.w3.org/2000/svg" height="48px" width="48px" viewBox="0 -960 960 960">
                                     <path d="M560-440h200v-80H560v80Zm0-120h200v-80H560v80ZM200-320h320v-22q0-45-44-71.5T360-440q-72 0-116 26.5T200-342v22Zm160-160q33 0 56.5-23.5T440-560q0-33-23.5-56.5T360-640q-33 0-56.5 23.5T280-560q0 33 23.5 56.5T360-480ZM160-160q-33 0-56.5-23.5T80-240v-480q0-33 23.5-56.5T160-800h640q33 0 56.5 23.5T880-720v480q0 33-23.5 56.5T800-160H160Zm0-80h640v-480H160v480Zm0 0v-480 480Z"/>
                                 </svg>
@@ -877,7 +2798,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                 </div>
                 <h2 class="text-center text-lg">Share informations</h2>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || surname_checkbox.get()
                         on:click = move |_| {
                             set_surname_checkbox.set(!surname_checkbox.get());
                         }
@@ -885,7 +2807,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Surname
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || name_checkbox.get()
                         on:click = move |_| {
                             set_name_checkbox.set(!name_checkbox.get());
                         }
@@ -893,7 +2816,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Name
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || sex_checkbox.get()
                         on:click = move |_| {
                             set_sex_checkbox.set(!sex_checkbox.get());
                         }
@@ -901,7 +2825,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Sex
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || country_checkbox.get()
                         on:click = move |_| {
                             set_country_checkbox.set(!country_checkbox.get());
                         }
@@ -909,7 +2834,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Country
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || date_of_birth_checkbox.get()
                         on:click = move |_| {
                             set_date_of_birth_checkbox.set(!date_of_birth_checkbox.get());
                         }
@@ -917,7 +2843,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Date of birth
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || place_of_birth_checkbox.get()
                         on:click = move |_| {
                             set_place_of_birth_checkbox.set(!place_of_birth_checkbox.get());
                         }
@@ -925,7 +2852,8 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Place of birth
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || document_no_checkbox.get()
                         on:click = move |_| {
                             set_document_no_checkbox.set(!document_no_checkbox.get());
                         }
@@ -933,16 +2861,31 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     Document No
                 </label>
                 <label class="label">
-                    <input type="checkbox" checked="checked" class="checkbox"
+                    <input type="checkbox" class="checkbox"
+                        prop:checked = move || expiry_date_checkbox.get()
                         on:click = move |_| {
                             set_expiry_date_checkbox.set(!expiry_date_checkbox.get());
                         }
                     />
                     Expiry date
                 </label>
+                <label class="label mt-2">
+                    <input type="checkbox" class="toggle"
+                        prop:checked = move || holder_binding.get()
+                        on:click = move |_| {
+                            set_holder_binding.set(!holder_binding.get());
+                            set_signature_generated.set(false);
+                        }
+                    />
+                    Bind to this wallet
+                </label>
                 <button class="btn btn-neutral mt-4"
                     on:click = move |_| {
-                        create_signature();
+                        if holder_binding.get() || issued_credential.get().is_some() {
+                            create_bound_signature();
+                        } else {
+                            create_signature();
+                        }
                     }
                 >Create signature</button>
                 <Show when=move || signature_generated.get() == true>
@@ -957,17 +2900,201 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                     <fieldset class="fieldset">
                         <legend class="fieldset-legend">Public key</legend>
                         <textarea class="textarea h-24 w-full"
+                            prop:value = move || public_key.get()
                             on:input = move |ev| {
                                 set_public_key.set(event_target_value(&ev));
                             }
-                        >{public_key.get()}</textarea>
+                        ></textarea>
+                        <div class="join w-full mt-2">
+                            <select class="select join-item"
+                                on:change = move |ev| {
+                                    set_key_format.set(event_target_value(&ev));
+                                }
+                            >
+                                <option value="jwk" selected>JWK</option>
+                                <option value="jwk_ec">JWK (EC)</option>
+                                <option value="multikey">Multikey</option>
+                                <option value="pem">PEM</option>
+                                <option value="hex">Hex</option>
+                            </select>
+                            <button class="btn join-item"
+                                on:click = move |_| {
+                                    export_public_key();
+                                }
+                            >Export</button>
+                            <button class="btn join-item"
+                                on:click = move |_| {
+                                    import_public_key();
+                                }
+                            >Import</button>
+                        </div>
+                        <Show when=move || exported_public_key.get() != String::from("")>
+                            <textarea class="textarea h-24 w-full mt-2" readonly=true>{move || exported_public_key.get()}</textarea>
+                        </Show>
                     </fieldset>
+                    <Show when=move || holder_binding.get() == true>
+                        <label class="label mt-2">Verifier request</label>
+                        <div class="join w-full mb-2">
+                            <input type="text" class="input join-item w-full" placeholder="openid4vp://?client_id=..."
+                                prop:value = move || authorization_request.get()
+                                on:input = move |ev| {
+                                    set_authorization_request.set(event_target_value(&ev));
+                                }
+                            />
+                            <button class="btn join-item"
+                                on:click = move |_| {
+                                    load_authorization_request();
+                                }
+                            >Load</button>
+                        </div>
+                        <div class="join w-full mb-2">
+                            <input type="text" class="input join-item w-full" placeholder="QR code image (png, jpg)"
+                                on:input = move |ev| {
+                                    set_qr_code_image.set(event_target_value(&ev));
+                                }
+                            />
+                            <button class="btn join-item"
+                                on:click = move |_| {
+                                    scan_qr_code_image();
+                                }
+                            >Scan</button>
+                        </div>
+                        <Show when=move || pending_authorization.get().is_some()>
+                            <fieldset class="fieldset">
+                                <legend class="fieldset-legend">Requested by the verifier</legend>
+                                <textarea class="textarea h-24 w-full" readonly=true>
+                                    {
+                                        pending_authorization.get().map(|pending| pending.requestedFields.join("\n")).unwrap_or_default()
+                                    }
+                                </textarea>
+                            </fieldset>
+                            <div class="join mb-2">
+                                <button class="btn btn-primary join-item"
+                                    on:click = move |_| {
+                                        respond_authorization_request();
+                                    }
+                                >Send to verifier</button>
+                                <button class="btn join-item"
+                                    on:click = move |_| {
+                                        show_authorization_qr_code();
+                                    }
+                                >Show QR code</button>
+                            </div>
+                        </Show>
+                        <label class="label mt-2">Verifier ID</label>
+                        <input type="text" class="input mb-2 w-full" placeholder="https://verifier.example"
+                            prop:value = move || verifier_id.get()
+                            on:input = move |ev| {
+                                set_verifier_id.set(event_target_value(&ev));
+                            }
+                        />
+                        <label class="label">
+                            <input type="checkbox" class="checkbox"
+                                on:click = move |_| {
+                                    set_age_over_checkbox.set(!age_over_checkbox.get());
+                                }
+                            />
+                            Prove age over 18
+                        </label>
+                        <label class="label">
+                            <input type="checkbox" class="checkbox"
+                                on:click = move |_| {
+                                    set_not_expired_checkbox.set(!not_expired_checkbox.get());
+                                }
+                            />
+                            Prove card is not expired
+                        </label>
+                        <label class="label">
+                            <input type="checkbox" class="checkbox"
+                                on:click = move |_| {
+                                    set_country_in_checkbox.set(!country_in_checkbox.get());
+                                }
+                            />
+                            Prove citizenship of an EU member state
+                        </label>
+                        <label class="label">
+                            <input type="checkbox" class="checkbox"
+                                on:click = move |_| {
+                                    set_non_revocation_checkbox.set(!non_revocation_checkbox.get());
+                                }
+                            />
+                            Prove non-revocation privately (accumulator)
+                        </label>
+                        <label class="label mt-2">Status list file</label>
+                        <input type="text" class="input mb-2 w-full" placeholder="Cached status list"
+                            on:input = move |ev| {
+                                set_status_list_file.set(event_target_value(&ev));
+                            }
+                        />
+                        <Show when=move || status_list_index.get().is_some()>
+                            <div class="join mt-2">
+                                <button class="btn btn-error join-item"
+                                    on:click = move |_| {
+                                        revoke_card();
+                                    }
+                                >Revoke</button>
+                                <button class="btn btn-warning join-item"
+                                    on:click = move |_| {
+                                        suspend_card(true);
+                                    }
+                                >Suspend</button>
+                                <button class="btn join-item"
+                                    on:click = move |_| {
+                                        suspend_card(false);
+                                    }
+                                >Reinstate</button>
+                            </div>
+                        </Show>
+                    </Show>
                     <button class="btn btn-neutral mt-4"
                         on:click = move |_| {
-                            verify_signature();
+                            if holder_binding.get() || issued_credential.get().is_some() {
+                                verify_bound_signature();
+                            } else {
+                                verify_signature();
+                            }
                         }
                     >Verify signature</button>
                 </Show>
+                <Show when=move || issued_credential.get().and_then(|credential| credential.verificationMethod).is_some()>
+                    <fieldset class="fieldset">
+                        <legend class="fieldset-legend">Issuer key</legend>
+                        <textarea class="textarea h-24 w-full" readonly=true>{issued_credential.get().and_then(|credential| credential.verificationMethod).unwrap_or_default()}</textarea>
+                    </fieldset>
+                </Show>
+                <Show when=move || qr_code.get() != String::from("")>
+                    <fieldset class="fieldset">
+                        <legend class="fieldset-legend">Presentation QR code</legend>
+                        <div class="bg-white rounded-box p-2" inner_html=move || qr_code.get()></div>
+                        <button class="btn btn-sm mt-2"
+                            on:click = move |_| {
+                                set_qr_code.set(String::new());
+                            }
+                        >Hide</button>
+                    </fieldset>
+                </Show>
+                <Show when=move || pseudonym.get() != String::from("")>
+                    <fieldset class="fieldset">
+                        <legend class="fieldset-legend">Pseudonym</legend>
+                        <textarea class="textarea h-24 w-full" readonly=true>{pseudonym.get()}</textarea>
+                    </fieldset>
+                </Show>
+                <Show when=move || card_status.get() != String::from("")>
+                    <fieldset class="fieldset">
+                        <legend class="fieldset-legend">Card status</legend>
+                        <input type="text" class="input w-full" value={ card_status.get() } readonly=true/>
+                    </fieldset>
+                </Show>
+                <Show when=move || proven_predicates.get().len() != 0>
+                    <fieldset class="fieldset">
+                        <legend class="fieldset-legend">Proven predicates</legend>
+                        <textarea class="textarea h-24 w-full" readonly=true>
+                            {
+                                proven_predicates.get().join("\n")
+                            }
+                        </textarea>
+                    </fieldset>
+                </Show>
                 <Show when=move || disclosed_messages.get().len() != 0>
                     <fieldset class="fieldset">
                         <legend class="fieldset-legend">Disclosed messages</legend>
@@ -978,26 +3105,92 @@ pub fn Wallet(account_surname: String, account_name: String) -> impl IntoView {
                         </textarea>
                     </fieldset>
                 </Show>
+                <button class="btn mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("trusted_issuers"));
+                    }
+                >Trusted issuers</button>
+                <button class="btn mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("history"));
+                    }
+                >Presentation history</button>
+                <button class="btn mt-2"
+                    on:click = move |_| {
+                        ctx.page_name.set(String::from("security"));
+                    }
+                >Security</button>
+                <button class="btn mt-2"
+                    on:click = move |_| logout()
+                >Log out</button>
+                <Show
+                    when=move || delete_confirmation.get()
+                    fallback=move || view! {
+                        <button class="btn btn-error btn-outline mt-2"
+                            on:click = move |_| {
+                                set_delete_confirmation.set(true);
+                            }
+                        >Delete card</button>
+                    }
+                >
+                    <div class="join mt-2 w-full">
+                        <button class="btn btn-error join-item w-1/2"
+                            on:click = move |_| {
+                                delete_card();
+                            }
+                        >Confirm deletion</button>
+                        <button class="btn join-item w-1/2"
+                            on:click = move |_| {
+                                set_delete_confirmation.set(false);
+                            }
+                        >Cancel</button>
+                    </div>
+                </Show>
             </fieldset>
             <div class="toast toast-end">
                 <Show when=move || signature_successfully_verified.get() == Some(true)>
                     <div class="alert alert-success">
-                        <span>Signature BBS+ successfully verified.</span>
+                        <span>Signature BBS+ successfully verified. {move || issuer_trust.get()}</span>
                     </div>
                 </Show>
                 <Show when=move || signature_successfully_verified.get() == Some(false)>
                     <div class="alert alert-error">
-                        <span>Signature BBS+ failed the verification.</span>
+                        <span>Signature BBS+ failed the verification. {move || validity_error.get()} {move || issuer_trust.get()}</span>
                     </div>
                 </Show>
                 <Show when=move || partial_signature_successfully_verified.get() == Some(true)> 
                     <div class="alert alert-success">
-                        <span>Signature ZKP BBS+ successfully verified.</span>
+                        <span>Signature ZKP BBS+ successfully verified. {move || issuer_trust.get()}</span>
                     </div>
                 </Show>
                 <Show when=move || partial_signature_successfully_verified.get() == Some(false)>
                     <div class="alert alert-error">
-                        <span>Signature ZKP BBS+ failed the verification.</span>
+                        <span>Signature ZKP BBS+ failed the verification. {move || validity_error.get()} {move || issuer_trust.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || authorization_sent.get() == Some(true)>
+                    <div class="alert alert-success">
+                        <span>Presentation sent to the verifier. {move || authorization_message.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || authorization_sent.get() == Some(false)>
+                    <div class="alert alert-error">
+                        <span>{move || authorization_message.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || key_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || key_error.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || qr_code_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || qr_code_error.get()}</span>
+                    </div>
+                </Show>
+                <Show when=move || delete_card_error.get() != String::from("")>
+                    <div class="alert alert-error">
+                        <span>{move || delete_card_error.get()}</span>
                     </div>
                 </Show>
             </div>